@@ -0,0 +1,62 @@
+//! Compares lexing into tokens borrowed from the source with also detaching
+//! every token from it with `Token::into_owned`, which shows what copying the
+//! text of each token costs on top of lexing.
+//!
+//! Run with `cargo bench --bench lexer`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use snowflake_lexer::lexer::lexer::{Lexer, Token};
+
+const QUERY: &str = r#"
+-- Daily revenue per customer
+SELECT c.customer_id, c."Full Name", SUM(o.amount) AS revenue,
+       o.payload:shipping.city::varchar AS city, 'it''s' AS note
+FROM analytics.public.customers c
+LEFT JOIN analytics.public.orders o ON o.customer_id = c.customer_id
+WHERE o.created_at >= DATEADD(day, -7, CURRENT_DATE) /* last week */
+GROUP BY 1, 2, 4
+QUALIFY ROW_NUMBER() OVER (PARTITION BY c.customer_id ORDER BY revenue DESC) = 1;
+"#;
+
+const ITERATIONS: u32 = 200;
+
+fn main() {
+    let script = include_str!("../scripts/input.sql");
+    let source = [QUERY, script].concat().repeat(500);
+
+    let borrowed = bench(|| Lexer::new(&source).get_tokens().unwrap().len());
+    let into_owned = bench(|| {
+        Lexer::new(&source)
+            .get_tokens()
+            .unwrap()
+            .into_iter()
+            .map(Token::into_owned)
+            .count()
+    });
+
+    report("borrowed", borrowed, source.len());
+    report("into_owned", into_owned, source.len());
+}
+
+fn bench(mut f: impl FnMut() -> usize) -> Duration {
+    // Warm up caches and the allocator
+    for _ in 0..10 {
+        black_box(f());
+    }
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn report(name: &str, per_iteration: Duration, bytes: usize) {
+    let throughput = bytes as f64 / per_iteration.as_secs_f64() / 1_000_000.0;
+    println!(
+        "{:<12} {:>10.3?}/iter {:>10.1} MB/s",
+        name, per_iteration, throughput
+    );
+}
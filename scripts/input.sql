@@ -0,0 +1,11 @@
+CREATE OR REPLACE TABLE COMBINED_TABLE AS
+WITH EMPLOYEES AS (
+    SELECT ID, NAME
+    FROM RAW.EMPLOYEES
+), SALARIES AS (
+    SELECT ID, RANK() OVER (ORDER BY SALARY DESC) AS SALARY_RANK
+    FROM RAW.SALARIES
+)
+SELECT E.ID, E.NAME, S.SALARY_RANK
+FROM EMPLOYEES E
+INNER JOIN SALARIES S ON E.ID = S.ID;
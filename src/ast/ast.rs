@@ -2,15 +2,15 @@ use crate::lexer::lexer;
 
 #[allow(dead_code)]
 #[derive(Clone, Debug, PartialEq)]
-enum AstNode {
-    Program(Vec<AstNode>),
-    Statement(Vec<AstNode>),
-    Expression(lexer::Token),
+enum AstNode<'a> {
+    Program(Vec<AstNode<'a>>),
+    Statement(Vec<AstNode<'a>>),
+    Expression(lexer::Token<'a>),
 }
 
 #[allow(dead_code)]
-impl AstNode {
-    fn add_child(&mut self, child: AstNode) {
+impl<'a> AstNode<'a> {
+    fn add_child(&mut self, child: AstNode<'a>) {
         match self {
             AstNode::Program(node) | AstNode::Statement(node) => node.push(child),
             AstNode::Expression(_) => todo!("Not sure what to do for an expression"),
@@ -19,14 +19,13 @@ impl AstNode {
 }
 
 #[allow(dead_code)]
-fn build_ast(tokens: Vec<lexer::Token>) -> AstNode {
+fn build_ast(tokens: Vec<lexer::Token<'_>>) -> AstNode<'_> {
     let mut program_node = AstNode::Program(vec![]);
     let mut statement_node = AstNode::Statement(vec![]);
 
     for token in tokens {
         let ast_node = match token {
-            lexer::Token::DML(lexer::DMLKeyword::Select) => AstNode::Expression(token),
-            lexer::Token::Ident(_) | lexer::Token::Varchar(_) => AstNode::Expression(token),
+            lexer::Token::Word(_) | lexer::Token::Varchar(_) => AstNode::Expression(token),
             _ => AstNode::Expression(token), // TODO: Currently treating all other tokens as
                                              // expressions
         };
@@ -59,30 +58,36 @@ mod tests {
     fn test_basic_query() {
         // Query: SELECT date, temperate FROM table WHERE date = '2022-01-01';
         let tokens = vec![
-            lexer::Token::DML(lexer::DMLKeyword::Select),
-            lexer::Token::Ident("date".into()),
+            lexer::Token::Word(lexer::Word::new("SELECT")),
+            lexer::Token::Word(lexer::Word::new("date")),
             lexer::Token::Comma,
-            lexer::Token::Ident("temperature".into()),
-            lexer::Token::From,
-            lexer::Token::Ident("table".into()),
-            lexer::Token::Where,
-            lexer::Token::Ident("date".into()),
+            lexer::Token::Word(lexer::Word::new("temperature")),
+            lexer::Token::Word(lexer::Word::new("FROM")),
+            lexer::Token::Word(lexer::Word::new("table")),
+            lexer::Token::Word(lexer::Word::new("WHERE")),
+            lexer::Token::Word(lexer::Word::new("date")),
             lexer::Token::Equal,
-            lexer::Token::Varchar("2022-01-01".into()),
+            lexer::Token::Varchar(lexer::StringLiteral {
+                raw: "'2022-01-01'".into(),
+                value: "2022-01-01".into(),
+            }),
             lexer::Token::Semicolon,
         ];
 
         let ast_expected = AstNode::Program(vec![AstNode::Statement(vec![
-            AstNode::Expression(lexer::Token::DML(lexer::DMLKeyword::Select)),
-            AstNode::Expression(lexer::Token::Ident("date".into())),
+            AstNode::Expression(lexer::Token::Word(lexer::Word::new("SELECT"))),
+            AstNode::Expression(lexer::Token::Word(lexer::Word::new("date"))),
             AstNode::Expression(lexer::Token::Comma),
-            AstNode::Expression(lexer::Token::Ident("temperature".into())),
-            AstNode::Expression(lexer::Token::From),
-            AstNode::Expression(lexer::Token::Ident("table".into())),
-            AstNode::Expression(lexer::Token::Where),
-            AstNode::Expression(lexer::Token::Ident("date".into())),
+            AstNode::Expression(lexer::Token::Word(lexer::Word::new("temperature"))),
+            AstNode::Expression(lexer::Token::Word(lexer::Word::new("FROM"))),
+            AstNode::Expression(lexer::Token::Word(lexer::Word::new("table"))),
+            AstNode::Expression(lexer::Token::Word(lexer::Word::new("WHERE"))),
+            AstNode::Expression(lexer::Token::Word(lexer::Word::new("date"))),
             AstNode::Expression(lexer::Token::Equal),
-            AstNode::Expression(lexer::Token::Varchar("2022-01-01".into())),
+            AstNode::Expression(lexer::Token::Varchar(lexer::StringLiteral {
+                raw: "'2022-01-01'".into(),
+                value: "2022-01-01".into(),
+            })),
             AstNode::Expression(lexer::Token::Semicolon),
         ])]);
 
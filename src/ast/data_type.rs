@@ -0,0 +1,126 @@
+use std::fmt;
+
+use crate::ast::expression::Ident;
+
+/// A Snowflake data type, with its parameters when they're written out.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataType {
+    /// `NUMBER`, `DECIMAL` or `NUMERIC`, e.g. `NUMBER(38, 2)`.
+    Number {
+        precision: Option<u64>,
+        scale: Option<u64>,
+    },
+    Int,
+    BigInt,
+    SmallInt,
+    TinyInt,
+    ByteInt,
+    /// `FLOAT`, `DOUBLE`, `REAL` and their variants, all 64-bit in Snowflake.
+    Float,
+    /// `VARCHAR(n)` and its synonyms such as `STRING` or `TEXT`.
+    Varchar(Option<u64>),
+    Char(Option<u64>),
+    Binary(Option<u64>),
+    Boolean,
+    Date,
+    /// `TIME(p)`
+    Time(Option<u64>),
+    /// `TIMESTAMP_LTZ(p)`, `TIMESTAMP_NTZ(p)`, `TIMESTAMP_TZ(p)` or `TIMESTAMP(p)`.
+    /// `DATETIME` is an alias of `TIMESTAMP_NTZ`.
+    Timestamp {
+        /// `None` for plain `TIMESTAMP`, which follows the
+        /// `TIMESTAMP_TYPE_MAPPING` parameter.
+        time_zone: Option<TimestampTimeZone>,
+        precision: Option<u64>,
+    },
+    Variant,
+    /// `OBJECT`, or a structured `OBJECT(name VARCHAR, age INT)`.
+    Object(Vec<(Ident, DataType)>),
+    /// `ARRAY`, or a structured `ARRAY(INT)`.
+    Array(Option<Box<DataType>>),
+    /// A structured `MAP(VARCHAR, INT)`.
+    Map {
+        key: Box<DataType>,
+        value: Box<DataType>,
+    },
+    Geography,
+    Geometry,
+    /// `VECTOR(FLOAT, 256)`
+    Vector {
+        element: Box<DataType>,
+        dimension: u64,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimestampTimeZone {
+    /// `TIMESTAMP_LTZ`, stored in UTC and shown in the session time zone.
+    Local,
+    /// `TIMESTAMP_NTZ`, wallclock time without a time zone.
+    None,
+    /// `TIMESTAMP_TZ`, stored with its own offset.
+    WithTimeZone,
+}
+
+/// Writes the type as Snowflake describes it, e.g. `NUMBER(38,0)`.
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let length = |f: &mut fmt::Formatter, name: &str, length: &Option<u64>| match length {
+            Some(length) => write!(f, "{}({})", name, length),
+            None => f.write_str(name),
+        };
+        match self {
+            DataType::Number {
+                precision: Some(precision),
+                scale: Some(scale),
+            } => write!(f, "NUMBER({},{})", precision, scale),
+            DataType::Number { precision, .. } => length(f, "NUMBER", precision),
+            DataType::Int => f.write_str("INT"),
+            DataType::BigInt => f.write_str("BIGINT"),
+            DataType::SmallInt => f.write_str("SMALLINT"),
+            DataType::TinyInt => f.write_str("TINYINT"),
+            DataType::ByteInt => f.write_str("BYTEINT"),
+            DataType::Float => f.write_str("FLOAT"),
+            DataType::Varchar(size) => length(f, "VARCHAR", size),
+            DataType::Char(size) => length(f, "CHAR", size),
+            DataType::Binary(size) => length(f, "BINARY", size),
+            DataType::Boolean => f.write_str("BOOLEAN"),
+            DataType::Date => f.write_str("DATE"),
+            DataType::Time(precision) => length(f, "TIME", precision),
+            DataType::Timestamp {
+                time_zone,
+                precision,
+            } => {
+                let name = match time_zone {
+                    Some(TimestampTimeZone::Local) => "TIMESTAMP_LTZ",
+                    Some(TimestampTimeZone::None) => "TIMESTAMP_NTZ",
+                    Some(TimestampTimeZone::WithTimeZone) => "TIMESTAMP_TZ",
+                    None => "TIMESTAMP",
+                };
+                length(f, name, precision)
+            }
+            DataType::Variant => f.write_str("VARIANT"),
+            DataType::Object(fields) if fields.is_empty() => f.write_str("OBJECT"),
+            DataType::Object(fields) => {
+                f.write_str("OBJECT(")?;
+                for (i, (name, data_type)) in fields.iter().enumerate() {
+                    let separator = if i == 0 { "" } else { ", " };
+                    match name.quote_style {
+                        Some(quote) => write!(f, "{}{}{}{}", separator, quote, name.value, quote)?,
+                        None => write!(f, "{}{}", separator, name.value)?,
+                    }
+                    write!(f, " {}", data_type)?;
+                }
+                f.write_str(")")
+            }
+            DataType::Array(None) => f.write_str("ARRAY"),
+            DataType::Array(Some(element)) => write!(f, "ARRAY({})", element),
+            DataType::Map { key, value } => write!(f, "MAP({}, {})", key, value),
+            DataType::Geography => f.write_str("GEOGRAPHY"),
+            DataType::Geometry => f.write_str("GEOMETRY"),
+            DataType::Vector { element, dimension } => {
+                write!(f, "VECTOR({}, {})", element, dimension)
+            }
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use crate::ast::data_type::DataType;
+use crate::ast::query::{OrderByExpr, Query};
+use crate::functions::functions::{self, BuiltinFunction};
+use crate::lexer::lexer::Span;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ident {
+    pub value: String,
+    /// `Some('"')` for a quoted, case-sensitive identifier.
+    pub quote_style: Option<char>,
+}
+
+impl Ident {
+    pub fn new(value: impl Into<String>) -> Self {
+        Ident {
+            value: value.into(),
+            quote_style: None,
+        }
+    }
+
+    pub fn with_quote(quote: char, value: impl Into<String>) -> Self {
+        Ident {
+            value: value.into(),
+            quote_style: Some(quote),
+        }
+    }
+}
+
+/// A possibly qualified object name such as `db.schema.table`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectName(pub Vec<Ident>);
+
+impl ObjectName {
+    /// The unqualified part of the name, e.g. `table` for `db.schema.table`.
+    pub fn base_name(&self) -> &Ident {
+        self.0.last().expect("object names are never empty")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Int(i128),
+    Decimal(String),
+    Float(f64),
+    Varchar(String),
+    Binary(Vec<u8>),
+    Bool(bool),
+    Null,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BinaryOperator {
+    Plus,
+    Minus,
+    Multiply,
+    Divide,
+    Modulo,
+    StringConcat,
+    Equal,
+    NotEqual,
+    GreaterThan,
+    GreaterThanEqual,
+    LessThan,
+    LessThanEqual,
+    And,
+    Or,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UnaryOperator {
+    Plus,
+    Minus,
+    Not,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    /// An expression, or a clause of one, that failed to parse, spanning the
+    /// source skipped over.
+    Error(Span),
+    Identifier(Ident),
+    /// A dotted reference such as `t.col` or `schema.table.col`.
+    CompoundIdentifier(Vec<Ident>),
+    /// A scripting variable referenced with a colon, e.g. `:my_var`.
+    Variable(Ident),
+    /// A session variable, e.g. `$my_var`.
+    SessionVariable(Ident),
+    /// A positional column reference, e.g. `$1`.
+    Positional(u32),
+    /// A positional column of an aliased stage, e.g. `t.$1`.
+    QualifiedPositional {
+        qualifier: Ident,
+        position: u32,
+    },
+    Value(Value),
+    /// A typed literal such as `DATE '2024-01-01'` or `TIMESTAMP_NTZ '...'`.
+    TypedString {
+        data_type: DataType,
+        value: String,
+    },
+    /// `INTERVAL '1 day, 3 hours'`
+    Interval(String),
+    BinaryOp {
+        left: Box<Expr>,
+        op: BinaryOperator,
+        right: Box<Expr>,
+    },
+    UnaryOp {
+        op: UnaryOperator,
+        expr: Box<Expr>,
+    },
+    IsNull {
+        expr: Box<Expr>,
+        negated: bool,
+    },
+    InList {
+        expr: Box<Expr>,
+        list: Vec<Expr>,
+        negated: bool,
+    },
+    InSubquery {
+        expr: Box<Expr>,
+        subquery: Box<Query>,
+        negated: bool,
+    },
+    Between {
+        expr: Box<Expr>,
+        low: Box<Expr>,
+        high: Box<Expr>,
+        negated: bool,
+    },
+    Like {
+        expr: Box<Expr>,
+        pattern: Box<Expr>,
+        case_insensitive: bool,
+        negated: bool,
+    },
+    Case {
+        operand: Option<Box<Expr>>,
+        conditions: Vec<(Expr, Expr)>,
+        else_result: Option<Box<Expr>>,
+    },
+    /// `CAST(expr AS type)`, `TRY_CAST(expr AS type)` or `expr::type`.
+    Cast {
+        expr: Box<Expr>,
+        data_type: DataType,
+        try_cast: bool,
+    },
+    Exists {
+        subquery: Box<Query>,
+        negated: bool,
+    },
+    /// Semi-structured access such as `payload:customer.name`.
+    JsonAccess {
+        expr: Box<Expr>,
+        path: Vec<Ident>,
+    },
+    /// `expr[index]` on an ARRAY or OBJECT.
+    Subscript {
+        expr: Box<Expr>,
+        index: Box<Expr>,
+    },
+    /// An ARRAY constant such as `[1, 2, 3]`.
+    Array(Vec<Expr>),
+    /// An OBJECT constant such as `{'a': 1, 'b': 2}`.
+    Object(Vec<(Expr, Expr)>),
+    /// `x -> x + 1`, an argument of higher-order functions such as `FILTER`.
+    Lambda {
+        params: Vec<Ident>,
+        body: Box<Expr>,
+    },
+    Function(FunctionCall),
+    Nested(Box<Expr>),
+    Subquery(Box<Query>),
+    /// A `?` bind parameter.
+    Placeholder,
+    /// `*`, only valid as a function argument such as `COUNT(*)`.
+    Wildcard,
+}
+
+/// A call such as `LISTAGG(DISTINCT name, ', ') WITHIN GROUP (ORDER BY name)`
+/// or `LAG(amount) IGNORE NULLS OVER (...)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionCall {
+    pub name: ObjectName,
+    pub args: Vec<FunctionArg>,
+    pub distinct: bool,
+    pub null_treatment: Option<NullTreatment>,
+    /// `WITHIN GROUP (ORDER BY ...)` of ordered-set aggregates.
+    pub within_group: Vec<OrderByExpr>,
+    pub over: Option<WindowSpec>,
+}
+
+impl FunctionCall {
+    /// The built-in function being called, if the name is unqualified and known.
+    pub fn builtin(&self) -> Option<&'static BuiltinFunction> {
+        match self.name.0.as_slice() {
+            [name] => functions::lookup(&name.value),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FunctionArg {
+    Unnamed(Expr),
+    /// `name => value`
+    Named {
+        name: Ident,
+        value: Expr,
+    },
+}
+
+impl FunctionArg {
+    pub fn expr(&self) -> &Expr {
+        match self {
+            FunctionArg::Unnamed(expr) | FunctionArg::Named { value: expr, .. } => expr,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum NullTreatment {
+    IgnoreNulls,
+    RespectNulls,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowSpec {
+    pub partition_by: Vec<Expr>,
+    pub order_by: Vec<OrderByExpr>,
+    pub window_frame: Option<WindowFrame>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum WindowFrameUnits {
+    Rows,
+    Range,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum WindowFrameBound {
+    CurrentRow,
+    /// `None` means `UNBOUNDED PRECEDING`.
+    Preceding(Option<Box<Expr>>),
+    /// `None` means `UNBOUNDED FOLLOWING`.
+    Following(Option<Box<Expr>>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowFrame {
+    pub units: WindowFrameUnits,
+    pub start_bound: WindowFrameBound,
+    pub end_bound: Option<WindowFrameBound>,
+}
@@ -1 +1,10 @@
+#[allow(clippy::module_inception)]
 pub mod ast;
+pub mod data_type;
+pub mod expression;
+pub mod query;
+pub mod scripting;
+pub mod stage;
+pub mod statement;
+pub mod visitor;
+pub mod visitor_mut;
@@ -0,0 +1,153 @@
+use crate::ast::expression::{Expr, Ident, ObjectName};
+use crate::ast::stage::{KeyValueOption, StageRef};
+
+/// A full query: optional `WITH` clause, a set expression body and the
+/// trailing `ORDER BY` / `LIMIT` clauses.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    pub with: Option<With>,
+    pub body: SetExpr,
+    pub order_by: Vec<OrderByExpr>,
+    pub limit: Option<Expr>,
+    pub offset: Option<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct With {
+    pub recursive: bool,
+    pub ctes: Vec<Cte>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cte {
+    pub alias: Ident,
+    pub columns: Vec<Ident>,
+    pub query: Query,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SetOperator {
+    Union,
+    Except,
+    Intersect,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SetExpr {
+    Select(Box<Select>),
+    Query(Box<Query>),
+    SetOperation {
+        op: SetOperator,
+        all: bool,
+        left: Box<SetExpr>,
+        right: Box<SetExpr>,
+    },
+    Values(Vec<Vec<Expr>>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Select {
+    pub distinct: bool,
+    pub top: Option<Expr>,
+    pub projection: Vec<SelectItem>,
+    /// Scripting targets of `SELECT ... INTO :var`.
+    pub into: Vec<Expr>,
+    pub from: Vec<TableWithJoins>,
+    pub selection: Option<Expr>,
+    pub group_by: Vec<Expr>,
+    pub having: Option<Expr>,
+    pub qualify: Option<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SelectItem {
+    UnnamedExpr(Expr),
+    ExprWithAlias { expr: Expr, alias: Ident },
+    QualifiedWildcard(ObjectName, WildcardOptions),
+    Wildcard(WildcardOptions),
+}
+
+/// The modifiers of `*` or `t.*`, e.g.
+/// `* EXCLUDE (ssn) REPLACE (UPPER(name) AS name) RENAME (id AS customer_id)`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WildcardOptions {
+    pub exclude: Vec<Ident>,
+    /// New expressions for columns, keeping their names.
+    pub replace: Vec<(Expr, Ident)>,
+    /// Columns renamed from the first name to the second.
+    pub rename: Vec<(Ident, Ident)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableWithJoins {
+    pub relation: TableFactor,
+    pub joins: Vec<Join>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableAlias {
+    pub name: Ident,
+    pub columns: Vec<Ident>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TableFactor {
+    Table {
+        name: ObjectName,
+        alias: Option<TableAlias>,
+    },
+    Derived {
+        lateral: bool,
+        subquery: Box<Query>,
+        alias: Option<TableAlias>,
+    },
+    /// `TABLE(my_function(...))` and `LATERAL FLATTEN(...)` style factors.
+    TableFunction {
+        lateral: bool,
+        expr: Expr,
+        alias: Option<TableAlias>,
+    },
+    NestedJoin {
+        table_with_joins: Box<TableWithJoins>,
+        alias: Option<TableAlias>,
+    },
+    /// Staged files queried directly, e.g.
+    /// `@my_stage (FILE_FORMAT => 'my_csv', PATTERN => '.*[.]csv')`.
+    Stage {
+        stage: StageRef,
+        options: Vec<KeyValueOption>,
+        alias: Option<TableAlias>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Join {
+    pub relation: TableFactor,
+    pub join_operator: JoinOperator,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JoinOperator {
+    Inner(JoinConstraint),
+    LeftOuter(JoinConstraint),
+    RightOuter(JoinConstraint),
+    FullOuter(JoinConstraint),
+    CrossJoin,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JoinConstraint {
+    On(Expr),
+    Using(Vec<Ident>),
+    Natural,
+    None,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrderByExpr {
+    pub expr: Expr,
+    /// `Some(true)` for `ASC`, `Some(false)` for `DESC`.
+    pub asc: Option<bool>,
+    /// `Some(true)` for `NULLS FIRST`, `Some(false)` for `NULLS LAST`.
+    pub nulls_first: Option<bool>,
+}
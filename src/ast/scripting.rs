@@ -0,0 +1,184 @@
+use crate::ast::data_type::DataType;
+use crate::ast::expression::{Expr, Ident};
+use crate::ast::query::Query;
+use crate::ast::statement::Statement;
+
+/// A Snowflake Scripting block:
+///
+/// ```sql
+/// DECLARE ... BEGIN ... EXCEPTION WHEN ... THEN ... END;
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Block {
+    pub declarations: Vec<Declaration>,
+    pub statements: Vec<ScriptStatement>,
+    pub exception_handlers: Vec<ExceptionHandler>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Declaration {
+    Variable {
+        name: Ident,
+        data_type: Option<DataType>,
+        default: Option<Expr>,
+    },
+    Cursor {
+        name: Ident,
+        source: CursorSource,
+    },
+    ResultSet {
+        name: Ident,
+        default: Option<Expr>,
+    },
+    Exception {
+        name: Ident,
+        code: Option<Expr>,
+        message: Option<Expr>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CursorSource {
+    Query(Box<Query>),
+    /// `CURSOR FOR my_resultset`
+    ResultSet(Ident),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExceptionHandler {
+    /// The exceptions handled, empty for `WHEN OTHER`.
+    pub exceptions: Vec<Ident>,
+    pub statements: Vec<ScriptStatement>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConditionalBlock {
+    pub condition: Expr,
+    pub statements: Vec<ScriptStatement>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptStatement {
+    /// A SQL statement nested in the block.
+    Sql(Statement),
+    Block(Block),
+    /// `LET name [type] { := | DEFAULT } ...`, including cursors and resultsets.
+    Let(Declaration),
+    Assign {
+        name: Ident,
+        value: Expr,
+    },
+    If {
+        branches: Vec<ConditionalBlock>,
+        else_statements: Option<Vec<ScriptStatement>>,
+    },
+    Case {
+        operand: Option<Expr>,
+        branches: Vec<ConditionalBlock>,
+        else_statements: Option<Vec<ScriptStatement>>,
+    },
+    /// `FOR i IN [REVERSE] start TO end DO ... END FOR`
+    ForRange {
+        variable: Ident,
+        reverse: bool,
+        start: Expr,
+        end: Expr,
+        statements: Vec<ScriptStatement>,
+        label: Option<Ident>,
+    },
+    /// `FOR row IN cursor DO ... END FOR`
+    ForCursor {
+        variable: Ident,
+        cursor: Ident,
+        statements: Vec<ScriptStatement>,
+        label: Option<Ident>,
+    },
+    While {
+        condition: Expr,
+        statements: Vec<ScriptStatement>,
+        label: Option<Ident>,
+    },
+    Repeat {
+        statements: Vec<ScriptStatement>,
+        until: Expr,
+        label: Option<Ident>,
+    },
+    Loop {
+        statements: Vec<ScriptStatement>,
+        label: Option<Ident>,
+    },
+    Break(Option<Ident>),
+    Continue(Option<Ident>),
+    Return(Option<Expr>),
+    Raise(Option<Ident>),
+    Open {
+        cursor: Ident,
+        using: Vec<Expr>,
+    },
+    Fetch {
+        cursor: Ident,
+        into: Vec<Ident>,
+    },
+    Close(Ident),
+    /// The no-op `NULL;` statement.
+    Null,
+}
+
+impl Block {
+    /// Every SQL statement nested anywhere in the block, in source order. The
+    /// queries of declarations, as in `DECLARE c CURSOR FOR <query>` or
+    /// `LET rs RESULTSET := (<query>)`, aren't statements and are left out, a
+    /// [`Visitor`](crate::ast::visitor::Visitor) reaches those as well.
+    pub fn sql_statements(&self) -> Vec<&Statement> {
+        let mut statements = Vec::new();
+        collect_sql_statements(&self.statements, &mut statements);
+        for handler in &self.exception_handlers {
+            collect_sql_statements(&handler.statements, &mut statements);
+        }
+        statements
+    }
+}
+
+fn collect_sql_statements<'a>(
+    script_statements: &'a [ScriptStatement],
+    statements: &mut Vec<&'a Statement>,
+) {
+    for script_statement in script_statements {
+        match script_statement {
+            ScriptStatement::Sql(statement) => statements.push(statement),
+            ScriptStatement::Block(block) => statements.extend(block.sql_statements()),
+            ScriptStatement::If {
+                branches,
+                else_statements,
+            }
+            | ScriptStatement::Case {
+                branches,
+                else_statements,
+                ..
+            } => {
+                for branch in branches {
+                    collect_sql_statements(&branch.statements, statements);
+                }
+                if let Some(else_statements) = else_statements {
+                    collect_sql_statements(else_statements, statements);
+                }
+            }
+            ScriptStatement::ForRange {
+                statements: body, ..
+            }
+            | ScriptStatement::ForCursor {
+                statements: body, ..
+            }
+            | ScriptStatement::While {
+                statements: body, ..
+            }
+            | ScriptStatement::Repeat {
+                statements: body, ..
+            }
+            | ScriptStatement::Loop {
+                statements: body, ..
+            } => collect_sql_statements(body, statements),
+            _ => {}
+        }
+    }
+}
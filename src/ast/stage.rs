@@ -0,0 +1,80 @@
+use crate::ast::expression::{Expr, Ident, ObjectName, Value};
+use crate::ast::query::Query;
+
+/// A stage location such as `@db.schema.my_stage/path/`, `@~` or `@%orders`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StageRef {
+    pub stage: Stage,
+    /// The path within the stage, without its leading `/`.
+    pub path: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Stage {
+    Named(ObjectName),
+    /// `@~`, the current user's stage.
+    User,
+    /// `@%my_table`, the stage of a table.
+    Table(ObjectName),
+}
+
+/// One side of a `COPY INTO` statement.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CopyLocation {
+    Table {
+        name: ObjectName,
+        columns: Vec<Ident>,
+    },
+    Stage(StageRef),
+    /// An external location such as `'s3://bucket/path/'`.
+    External(String),
+    /// `(SELECT ...)`, a transformation while loading or the rows to unload.
+    Query(Box<Query>),
+}
+
+/// `COPY INTO <location> FROM <location> ...`. Loads files into a table when
+/// `into` is a table and unloads rows into files otherwise.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CopyInto {
+    pub into: CopyLocation,
+    pub from: CopyLocation,
+    /// `FILES = ('a.csv', 'b.csv')`
+    pub files: Vec<String>,
+    pub pattern: Option<String>,
+    pub file_format: Option<FileFormat>,
+    pub partition_by: Option<Expr>,
+    /// The remaining copy options, e.g. `ON_ERROR = CONTINUE`.
+    pub options: Vec<KeyValueOption>,
+}
+
+impl CopyInto {
+    pub fn is_unload(&self) -> bool {
+        !matches!(self.into, CopyLocation::Table { .. })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileFormat {
+    /// `FILE_FORMAT = (FORMAT_NAME = 'my_format')`
+    Named(ObjectName),
+    /// `FILE_FORMAT = (TYPE = CSV FIELD_DELIMITER = '|' ...)`
+    Options(Vec<KeyValueOption>),
+}
+
+/// A `NAME = value` option of stages, file formats and copy statements.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyValueOption {
+    pub name: Ident,
+    pub value: OptionValue,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OptionValue {
+    Value(Value),
+    /// A bare word such as `CSV` or `ABORT_STATEMENT`.
+    Ident(Ident),
+    /// `('\\N', 'NULL')`
+    List(Vec<OptionValue>),
+    /// A nested option group, e.g. `CREDENTIALS = (AWS_KEY_ID = '...')`.
+    Options(Vec<KeyValueOption>),
+}
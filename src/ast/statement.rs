@@ -0,0 +1,205 @@
+use crate::ast::data_type::DataType;
+use crate::ast::expression::{Expr, FunctionArg, Ident, ObjectName};
+use crate::ast::query::{Query, TableFactor, TableWithJoins};
+use crate::ast::scripting::Block;
+use crate::ast::stage::{CopyInto, KeyValueOption, StageRef};
+use crate::lexer::lexer::Span;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Statement {
+    /// A statement that failed to parse, spanning the source skipped over.
+    Error(Span),
+    Query(Box<Query>),
+    Insert {
+        overwrite: bool,
+        table_name: ObjectName,
+        columns: Vec<Ident>,
+        source: Box<Query>,
+    },
+    Update {
+        table: Box<TableWithJoins>,
+        assignments: Vec<Assignment>,
+        from: Vec<TableWithJoins>,
+        selection: Option<Expr>,
+    },
+    Delete {
+        table_name: ObjectName,
+        using: Vec<TableWithJoins>,
+        selection: Option<Expr>,
+    },
+    Merge(Box<Merge>),
+    CreateTable {
+        or_replace: bool,
+        temporary: bool,
+        if_not_exists: bool,
+        name: ObjectName,
+        columns: Vec<ColumnDef>,
+        /// The table copied by `CLONE t` or `LIKE t`.
+        copy_of: Option<ObjectName>,
+        query: Option<Box<Query>>,
+    },
+    CreateView {
+        or_replace: bool,
+        materialized: bool,
+        if_not_exists: bool,
+        name: ObjectName,
+        columns: Vec<Ident>,
+        query: Box<Query>,
+    },
+    CreateRoutine(Box<CreateRoutine>),
+    CreateStage {
+        or_replace: bool,
+        temporary: bool,
+        if_not_exists: bool,
+        name: ObjectName,
+        options: Vec<KeyValueOption>,
+    },
+    CreateFileFormat {
+        or_replace: bool,
+        temporary: bool,
+        if_not_exists: bool,
+        name: ObjectName,
+        options: Vec<KeyValueOption>,
+    },
+    CopyInto(Box<CopyInto>),
+    /// `PUT file://... @stage`, uploading local files to an internal stage.
+    Put {
+        file: String,
+        stage: StageRef,
+        options: Vec<KeyValueOption>,
+    },
+    /// `GET @stage file://...`, downloading staged files.
+    Get {
+        stage: StageRef,
+        file: String,
+        options: Vec<KeyValueOption>,
+    },
+    /// `LIST @stage` (or `LS`).
+    List {
+        stage: StageRef,
+        pattern: Option<String>,
+    },
+    /// `REMOVE @stage` (or `RM`).
+    Remove {
+        stage: StageRef,
+        pattern: Option<String>,
+    },
+    Call {
+        name: ObjectName,
+        args: Vec<FunctionArg>,
+    },
+    Drop {
+        object_type: ObjectType,
+        if_exists: bool,
+        name: ObjectName,
+    },
+    Truncate {
+        if_exists: bool,
+        table_name: ObjectName,
+    },
+    /// An anonymous Snowflake Scripting block run directly as a statement.
+    Block(Box<Block>),
+    ExecuteImmediate(ExecuteImmediate),
+    StartTransaction,
+    Commit,
+    Rollback,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Assignment {
+    pub target: Ident,
+    pub value: Expr,
+}
+
+/// `MERGE INTO target USING source ON ... WHEN [NOT] MATCHED ...`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Merge {
+    pub target: TableFactor,
+    pub source: TableFactor,
+    pub on: Expr,
+    pub clauses: Vec<MergeClause>,
+}
+
+/// `WHEN [NOT] MATCHED [AND predicate] THEN action`
+#[derive(Clone, Debug, PartialEq)]
+pub struct MergeClause {
+    pub matched: bool,
+    pub predicate: Option<Expr>,
+    pub action: MergeAction,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MergeAction {
+    Update(Vec<Assignment>),
+    Delete,
+    Insert {
+        columns: Vec<Ident>,
+        values: Vec<Expr>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnDef {
+    pub name: Ident,
+    pub data_type: DataType,
+    pub not_null: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ObjectType {
+    Table,
+    View,
+    Procedure,
+    Function,
+    Other(Ident),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RoutineKind {
+    Procedure,
+    Function,
+}
+
+/// `CREATE [OR REPLACE] { PROCEDURE | FUNCTION } ...`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreateRoutine {
+    pub kind: RoutineKind,
+    pub or_replace: bool,
+    pub name: ObjectName,
+    pub args: Vec<RoutineArg>,
+    pub returns: Option<RoutineReturns>,
+    pub language: Option<Ident>,
+    pub execute_as: Option<Ident>,
+    pub body: RoutineBody,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoutineArg {
+    pub name: Ident,
+    pub data_type: DataType,
+    pub default: Option<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RoutineReturns {
+    DataType(DataType),
+    Table(Vec<ColumnDef>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RoutineBody {
+    /// A Snowflake Scripting body of a `LANGUAGE SQL` procedure.
+    Script(Block),
+    /// The query body of a SQL UDF or UDTF.
+    Query(Box<Query>),
+    /// The expression body of a scalar SQL UDF.
+    Expr(Expr),
+    /// The source of a body we don't parse, such as JavaScript or Python.
+    Unparsed(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExecuteImmediate {
+    Block(Box<Block>),
+    Expr(Expr),
+}
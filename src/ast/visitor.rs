@@ -0,0 +1,732 @@
+//! Read-only traversal of the AST.
+//!
+//! Every `visit_*` method defaults to the matching `walk_*` function, which
+//! visits the node's children. Override a method to act on a node, and call
+//! the `walk_*` function from it to keep descending.
+
+use crate::ast::data_type::DataType;
+use crate::ast::expression::{
+    Expr, FunctionArg, FunctionCall, ObjectName, WindowFrameBound, WindowSpec,
+};
+use crate::ast::query::{
+    Cte, Join, JoinConstraint, JoinOperator, OrderByExpr, Query, Select, SelectItem, SetExpr,
+    TableFactor, TableWithJoins,
+};
+use crate::ast::scripting::{
+    Block, ConditionalBlock, CursorSource, Declaration, ExceptionHandler, ScriptStatement,
+};
+use crate::ast::stage::{CopyInto, CopyLocation, Stage, StageRef};
+use crate::ast::statement::{
+    ColumnDef, CreateRoutine, ExecuteImmediate, Merge, MergeAction, RoutineBody, RoutineReturns,
+    Statement,
+};
+
+pub trait Visitor {
+    fn visit_statement(&mut self, statement: &Statement) {
+        walk_statement(self, statement);
+    }
+
+    fn visit_query(&mut self, query: &Query) {
+        walk_query(self, query);
+    }
+
+    fn visit_cte(&mut self, cte: &Cte) {
+        walk_cte(self, cte);
+    }
+
+    fn visit_set_expr(&mut self, set_expr: &SetExpr) {
+        walk_set_expr(self, set_expr);
+    }
+
+    fn visit_select(&mut self, select: &Select) {
+        walk_select(self, select);
+    }
+
+    fn visit_select_item(&mut self, item: &SelectItem) {
+        walk_select_item(self, item);
+    }
+
+    fn visit_table_with_joins(&mut self, table: &TableWithJoins) {
+        walk_table_with_joins(self, table);
+    }
+
+    fn visit_table_factor(&mut self, factor: &TableFactor) {
+        walk_table_factor(self, factor);
+    }
+
+    fn visit_join(&mut self, join: &Join) {
+        walk_join(self, join);
+    }
+
+    fn visit_order_by_expr(&mut self, order_by: &OrderByExpr) {
+        walk_order_by_expr(self, order_by);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr);
+    }
+
+    fn visit_function_call(&mut self, function: &FunctionCall) {
+        walk_function_call(self, function);
+    }
+
+    fn visit_window_spec(&mut self, window: &WindowSpec) {
+        walk_window_spec(self, window);
+    }
+
+    fn visit_data_type(&mut self, data_type: &DataType) {
+        walk_data_type(self, data_type);
+    }
+
+    /// A table, view, function, routine or stage name. Table references in
+    /// queries are visited through [`Visitor::visit_table_factor`] first.
+    fn visit_object_name(&mut self, _name: &ObjectName) {}
+
+    fn visit_column_def(&mut self, column: &ColumnDef) {
+        walk_column_def(self, column);
+    }
+
+    fn visit_create_routine(&mut self, routine: &CreateRoutine) {
+        walk_create_routine(self, routine);
+    }
+
+    fn visit_copy_into(&mut self, copy: &CopyInto) {
+        walk_copy_into(self, copy);
+    }
+
+    fn visit_merge(&mut self, merge: &Merge) {
+        walk_merge(self, merge);
+    }
+
+    fn visit_stage_ref(&mut self, stage: &StageRef) {
+        walk_stage_ref(self, stage);
+    }
+
+    fn visit_block(&mut self, block: &Block) {
+        walk_block(self, block);
+    }
+
+    fn visit_declaration(&mut self, declaration: &Declaration) {
+        walk_declaration(self, declaration);
+    }
+
+    fn visit_script_statement(&mut self, statement: &ScriptStatement) {
+        walk_script_statement(self, statement);
+    }
+}
+
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Statement) {
+    match statement {
+        Statement::Error(_)
+        | Statement::StartTransaction
+        | Statement::Commit
+        | Statement::Rollback => {}
+        Statement::Query(query) => visitor.visit_query(query),
+        Statement::Insert {
+            table_name, source, ..
+        } => {
+            visitor.visit_object_name(table_name);
+            visitor.visit_query(source);
+        }
+        Statement::Update {
+            table,
+            assignments,
+            from,
+            selection,
+        } => {
+            visitor.visit_table_with_joins(table);
+            for assignment in assignments {
+                visitor.visit_expr(&assignment.value);
+            }
+            for table in from {
+                visitor.visit_table_with_joins(table);
+            }
+            if let Some(selection) = selection {
+                visitor.visit_expr(selection);
+            }
+        }
+        Statement::Delete {
+            table_name,
+            using,
+            selection,
+        } => {
+            visitor.visit_object_name(table_name);
+            for table in using {
+                visitor.visit_table_with_joins(table);
+            }
+            if let Some(selection) = selection {
+                visitor.visit_expr(selection);
+            }
+        }
+        Statement::Merge(merge) => visitor.visit_merge(merge),
+        Statement::CreateTable {
+            name,
+            columns,
+            copy_of,
+            query,
+            ..
+        } => {
+            visitor.visit_object_name(name);
+            for column in columns {
+                visitor.visit_column_def(column);
+            }
+            if let Some(copy_of) = copy_of {
+                visitor.visit_object_name(copy_of);
+            }
+            if let Some(query) = query {
+                visitor.visit_query(query);
+            }
+        }
+        Statement::CreateView { name, query, .. } => {
+            visitor.visit_object_name(name);
+            visitor.visit_query(query);
+        }
+        Statement::CreateRoutine(routine) => visitor.visit_create_routine(routine),
+        Statement::CreateStage { name, .. } | Statement::CreateFileFormat { name, .. } => {
+            visitor.visit_object_name(name);
+        }
+        Statement::CopyInto(copy) => visitor.visit_copy_into(copy),
+        Statement::Put { stage, .. }
+        | Statement::Get { stage, .. }
+        | Statement::List { stage, .. }
+        | Statement::Remove { stage, .. } => visitor.visit_stage_ref(stage),
+        Statement::Call { name, args } => {
+            visitor.visit_object_name(name);
+            walk_function_args(visitor, args);
+        }
+        Statement::Drop { name, .. } => visitor.visit_object_name(name),
+        Statement::Truncate { table_name, .. } => visitor.visit_object_name(table_name),
+        Statement::Block(block) => visitor.visit_block(block),
+        Statement::ExecuteImmediate(ExecuteImmediate::Block(block)) => visitor.visit_block(block),
+        Statement::ExecuteImmediate(ExecuteImmediate::Expr(expr)) => visitor.visit_expr(expr),
+    }
+}
+
+pub fn walk_query<V: Visitor + ?Sized>(visitor: &mut V, query: &Query) {
+    if let Some(with) = &query.with {
+        for cte in &with.ctes {
+            visitor.visit_cte(cte);
+        }
+    }
+    visitor.visit_set_expr(&query.body);
+    for order_by in &query.order_by {
+        visitor.visit_order_by_expr(order_by);
+    }
+    if let Some(limit) = &query.limit {
+        visitor.visit_expr(limit);
+    }
+    if let Some(offset) = &query.offset {
+        visitor.visit_expr(offset);
+    }
+}
+
+pub fn walk_cte<V: Visitor + ?Sized>(visitor: &mut V, cte: &Cte) {
+    visitor.visit_query(&cte.query);
+}
+
+pub fn walk_set_expr<V: Visitor + ?Sized>(visitor: &mut V, set_expr: &SetExpr) {
+    match set_expr {
+        SetExpr::Select(select) => visitor.visit_select(select),
+        SetExpr::Query(query) => visitor.visit_query(query),
+        SetExpr::SetOperation { left, right, .. } => {
+            visitor.visit_set_expr(left);
+            visitor.visit_set_expr(right);
+        }
+        SetExpr::Values(rows) => {
+            for expr in rows.iter().flatten() {
+                visitor.visit_expr(expr);
+            }
+        }
+    }
+}
+
+/// Visits the clauses of a `SELECT` in the order Snowflake evaluates them,
+/// starting with `FROM`, so that tables are seen before the columns that
+/// refer to them.
+pub fn walk_select<V: Visitor + ?Sized>(visitor: &mut V, select: &Select) {
+    for table in &select.from {
+        visitor.visit_table_with_joins(table);
+    }
+    if let Some(selection) = &select.selection {
+        visitor.visit_expr(selection);
+    }
+    for expr in &select.group_by {
+        visitor.visit_expr(expr);
+    }
+    if let Some(having) = &select.having {
+        visitor.visit_expr(having);
+    }
+    if let Some(qualify) = &select.qualify {
+        visitor.visit_expr(qualify);
+    }
+    if let Some(top) = &select.top {
+        visitor.visit_expr(top);
+    }
+    for item in &select.projection {
+        visitor.visit_select_item(item);
+    }
+    for expr in &select.into {
+        visitor.visit_expr(expr);
+    }
+}
+
+pub fn walk_select_item<V: Visitor + ?Sized>(visitor: &mut V, item: &SelectItem) {
+    match item {
+        SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+            visitor.visit_expr(expr)
+        }
+        SelectItem::QualifiedWildcard(_, options) | SelectItem::Wildcard(options) => {
+            for (expr, _) in &options.replace {
+                visitor.visit_expr(expr);
+            }
+        }
+    }
+}
+
+pub fn walk_table_with_joins<V: Visitor + ?Sized>(visitor: &mut V, table: &TableWithJoins) {
+    visitor.visit_table_factor(&table.relation);
+    for join in &table.joins {
+        visitor.visit_join(join);
+    }
+}
+
+pub fn walk_table_factor<V: Visitor + ?Sized>(visitor: &mut V, factor: &TableFactor) {
+    match factor {
+        TableFactor::Table { name, .. } => visitor.visit_object_name(name),
+        TableFactor::Derived { subquery, .. } => visitor.visit_query(subquery),
+        TableFactor::TableFunction { expr, .. } => visitor.visit_expr(expr),
+        TableFactor::NestedJoin {
+            table_with_joins, ..
+        } => visitor.visit_table_with_joins(table_with_joins),
+        TableFactor::Stage { stage, .. } => visitor.visit_stage_ref(stage),
+    }
+}
+
+pub fn walk_join<V: Visitor + ?Sized>(visitor: &mut V, join: &Join) {
+    visitor.visit_table_factor(&join.relation);
+    match &join.join_operator {
+        JoinOperator::Inner(constraint)
+        | JoinOperator::LeftOuter(constraint)
+        | JoinOperator::RightOuter(constraint)
+        | JoinOperator::FullOuter(constraint) => {
+            if let JoinConstraint::On(expr) = constraint {
+                visitor.visit_expr(expr);
+            }
+        }
+        JoinOperator::CrossJoin => {}
+    }
+}
+
+pub fn walk_order_by_expr<V: Visitor + ?Sized>(visitor: &mut V, order_by: &OrderByExpr) {
+    visitor.visit_expr(&order_by.expr);
+}
+
+pub fn walk_expr<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expr) {
+    match expr {
+        Expr::Error(_)
+        | Expr::Identifier(_)
+        | Expr::CompoundIdentifier(_)
+        | Expr::Variable(_)
+        | Expr::SessionVariable(_)
+        | Expr::Positional(_)
+        | Expr::QualifiedPositional { .. }
+        | Expr::Value(_)
+        | Expr::Interval(_)
+        | Expr::Placeholder
+        | Expr::Wildcard => {}
+        Expr::TypedString { data_type, .. } => visitor.visit_data_type(data_type),
+        Expr::BinaryOp { left, right, .. } => {
+            visitor.visit_expr(left);
+            visitor.visit_expr(right);
+        }
+        Expr::UnaryOp { expr, .. }
+        | Expr::IsNull { expr, .. }
+        | Expr::JsonAccess { expr, .. }
+        | Expr::Nested(expr) => visitor.visit_expr(expr),
+        Expr::InList { expr, list, .. } => {
+            visitor.visit_expr(expr);
+            for item in list {
+                visitor.visit_expr(item);
+            }
+        }
+        Expr::InSubquery { expr, subquery, .. } => {
+            visitor.visit_expr(expr);
+            visitor.visit_query(subquery);
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            visitor.visit_expr(expr);
+            visitor.visit_expr(low);
+            visitor.visit_expr(high);
+        }
+        Expr::Like { expr, pattern, .. } => {
+            visitor.visit_expr(expr);
+            visitor.visit_expr(pattern);
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            else_result,
+        } => {
+            if let Some(operand) = operand {
+                visitor.visit_expr(operand);
+            }
+            for (condition, result) in conditions {
+                visitor.visit_expr(condition);
+                visitor.visit_expr(result);
+            }
+            if let Some(else_result) = else_result {
+                visitor.visit_expr(else_result);
+            }
+        }
+        Expr::Cast {
+            expr, data_type, ..
+        } => {
+            visitor.visit_expr(expr);
+            visitor.visit_data_type(data_type);
+        }
+        Expr::Exists { subquery, .. } | Expr::Subquery(subquery) => visitor.visit_query(subquery),
+        Expr::Subscript { expr, index } => {
+            visitor.visit_expr(expr);
+            visitor.visit_expr(index);
+        }
+        Expr::Array(items) => {
+            for item in items {
+                visitor.visit_expr(item);
+            }
+        }
+        Expr::Object(pairs) => {
+            for (key, value) in pairs {
+                visitor.visit_expr(key);
+                visitor.visit_expr(value);
+            }
+        }
+        Expr::Lambda { body, .. } => visitor.visit_expr(body),
+        Expr::Function(function) => visitor.visit_function_call(function),
+    }
+}
+
+pub fn walk_function_call<V: Visitor + ?Sized>(visitor: &mut V, function: &FunctionCall) {
+    visitor.visit_object_name(&function.name);
+    walk_function_args(visitor, &function.args);
+    for order_by in &function.within_group {
+        visitor.visit_order_by_expr(order_by);
+    }
+    if let Some(window) = &function.over {
+        visitor.visit_window_spec(window);
+    }
+}
+
+pub fn walk_window_spec<V: Visitor + ?Sized>(visitor: &mut V, window: &WindowSpec) {
+    for expr in &window.partition_by {
+        visitor.visit_expr(expr);
+    }
+    for order_by in &window.order_by {
+        visitor.visit_order_by_expr(order_by);
+    }
+    if let Some(frame) = &window.window_frame {
+        for bound in std::iter::once(&frame.start_bound).chain(&frame.end_bound) {
+            if let WindowFrameBound::Preceding(Some(expr))
+            | WindowFrameBound::Following(Some(expr)) = bound
+            {
+                visitor.visit_expr(expr);
+            }
+        }
+    }
+}
+
+pub fn walk_data_type<V: Visitor + ?Sized>(visitor: &mut V, data_type: &DataType) {
+    match data_type {
+        DataType::Object(fields) => {
+            for (_, field_type) in fields {
+                visitor.visit_data_type(field_type);
+            }
+        }
+        DataType::Array(Some(element)) | DataType::Vector { element, .. } => {
+            visitor.visit_data_type(element)
+        }
+        DataType::Map { key, value } => {
+            visitor.visit_data_type(key);
+            visitor.visit_data_type(value);
+        }
+        _ => {}
+    }
+}
+
+pub fn walk_column_def<V: Visitor + ?Sized>(visitor: &mut V, column: &ColumnDef) {
+    visitor.visit_data_type(&column.data_type);
+}
+
+pub fn walk_create_routine<V: Visitor + ?Sized>(visitor: &mut V, routine: &CreateRoutine) {
+    visitor.visit_object_name(&routine.name);
+    for arg in &routine.args {
+        visitor.visit_data_type(&arg.data_type);
+        if let Some(default) = &arg.default {
+            visitor.visit_expr(default);
+        }
+    }
+    match &routine.returns {
+        Some(RoutineReturns::DataType(data_type)) => visitor.visit_data_type(data_type),
+        Some(RoutineReturns::Table(columns)) => {
+            for column in columns {
+                visitor.visit_column_def(column);
+            }
+        }
+        None => {}
+    }
+    match &routine.body {
+        RoutineBody::Script(block) => visitor.visit_block(block),
+        RoutineBody::Query(query) => visitor.visit_query(query),
+        RoutineBody::Expr(expr) => visitor.visit_expr(expr),
+        RoutineBody::Unparsed(_) => {}
+    }
+}
+
+pub fn walk_copy_into<V: Visitor + ?Sized>(visitor: &mut V, copy: &CopyInto) {
+    for location in [&copy.into, &copy.from] {
+        match location {
+            CopyLocation::Table { name, .. } => visitor.visit_object_name(name),
+            CopyLocation::Stage(stage) => visitor.visit_stage_ref(stage),
+            CopyLocation::External(_) => {}
+            CopyLocation::Query(query) => visitor.visit_query(query),
+        }
+    }
+    if let Some(partition_by) = &copy.partition_by {
+        visitor.visit_expr(partition_by);
+    }
+}
+
+pub fn walk_stage_ref<V: Visitor + ?Sized>(visitor: &mut V, stage: &StageRef) {
+    match &stage.stage {
+        Stage::Named(name) | Stage::Table(name) => visitor.visit_object_name(name),
+        Stage::User => {}
+    }
+}
+
+pub fn walk_block<V: Visitor + ?Sized>(visitor: &mut V, block: &Block) {
+    for declaration in &block.declarations {
+        visitor.visit_declaration(declaration);
+    }
+    walk_script_statements(visitor, &block.statements);
+    for ExceptionHandler { statements, .. } in &block.exception_handlers {
+        walk_script_statements(visitor, statements);
+    }
+}
+
+pub fn walk_declaration<V: Visitor + ?Sized>(visitor: &mut V, declaration: &Declaration) {
+    match declaration {
+        Declaration::Variable {
+            data_type, default, ..
+        } => {
+            if let Some(data_type) = data_type {
+                visitor.visit_data_type(data_type);
+            }
+            if let Some(default) = default {
+                visitor.visit_expr(default);
+            }
+        }
+        Declaration::Cursor { source, .. } => {
+            if let CursorSource::Query(query) = source {
+                visitor.visit_query(query);
+            }
+        }
+        Declaration::ResultSet { default, .. } => {
+            if let Some(default) = default {
+                visitor.visit_expr(default);
+            }
+        }
+        Declaration::Exception { code, message, .. } => {
+            for expr in [code, message].into_iter().flatten() {
+                visitor.visit_expr(expr);
+            }
+        }
+    }
+}
+
+pub fn walk_script_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &ScriptStatement) {
+    match statement {
+        ScriptStatement::Sql(statement) => visitor.visit_statement(statement),
+        ScriptStatement::Block(block) => visitor.visit_block(block),
+        ScriptStatement::Let(declaration) => visitor.visit_declaration(declaration),
+        ScriptStatement::Assign { value, .. } => visitor.visit_expr(value),
+        ScriptStatement::If {
+            branches,
+            else_statements,
+        } => walk_branches(visitor, branches, else_statements),
+        ScriptStatement::Case {
+            operand,
+            branches,
+            else_statements,
+        } => {
+            if let Some(operand) = operand {
+                visitor.visit_expr(operand);
+            }
+            walk_branches(visitor, branches, else_statements);
+        }
+        ScriptStatement::ForRange {
+            start,
+            end,
+            statements,
+            ..
+        } => {
+            visitor.visit_expr(start);
+            visitor.visit_expr(end);
+            walk_script_statements(visitor, statements);
+        }
+        ScriptStatement::ForCursor { statements, .. }
+        | ScriptStatement::Loop { statements, .. } => walk_script_statements(visitor, statements),
+        ScriptStatement::While {
+            condition,
+            statements,
+            ..
+        } => {
+            visitor.visit_expr(condition);
+            walk_script_statements(visitor, statements);
+        }
+        ScriptStatement::Repeat {
+            statements, until, ..
+        } => {
+            walk_script_statements(visitor, statements);
+            visitor.visit_expr(until);
+        }
+        ScriptStatement::Return(expr) => {
+            if let Some(expr) = expr {
+                visitor.visit_expr(expr);
+            }
+        }
+        ScriptStatement::Open { using, .. } => {
+            for expr in using {
+                visitor.visit_expr(expr);
+            }
+        }
+        ScriptStatement::Break(_)
+        | ScriptStatement::Continue(_)
+        | ScriptStatement::Raise(_)
+        | ScriptStatement::Fetch { .. }
+        | ScriptStatement::Close(_)
+        | ScriptStatement::Null => {}
+    }
+}
+
+pub fn walk_merge<V: Visitor + ?Sized>(visitor: &mut V, merge: &Merge) {
+    visitor.visit_table_factor(&merge.target);
+    visitor.visit_table_factor(&merge.source);
+    visitor.visit_expr(&merge.on);
+    for clause in &merge.clauses {
+        if let Some(predicate) = &clause.predicate {
+            visitor.visit_expr(predicate);
+        }
+        match &clause.action {
+            MergeAction::Update(assignments) => {
+                for assignment in assignments {
+                    visitor.visit_expr(&assignment.value);
+                }
+            }
+            MergeAction::Delete => {}
+            MergeAction::Insert { values, .. } => {
+                for value in values {
+                    visitor.visit_expr(value);
+                }
+            }
+        }
+    }
+}
+
+fn walk_function_args<V: Visitor + ?Sized>(visitor: &mut V, args: &[FunctionArg]) {
+    for arg in args {
+        match arg {
+            FunctionArg::Unnamed(expr) | FunctionArg::Named { value: expr, .. } => {
+                visitor.visit_expr(expr)
+            }
+        }
+    }
+}
+
+fn walk_script_statements<V: Visitor + ?Sized>(visitor: &mut V, statements: &[ScriptStatement]) {
+    for statement in statements {
+        visitor.visit_script_statement(statement);
+    }
+}
+
+fn walk_branches<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    branches: &[ConditionalBlock],
+    else_statements: &Option<Vec<ScriptStatement>>,
+) {
+    for branch in branches {
+        visitor.visit_expr(&branch.condition);
+        walk_script_statements(visitor, &branch.statements);
+    }
+    if let Some(else_statements) = else_statements {
+        walk_script_statements(visitor, else_statements);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{walk_table_factor, Visitor};
+    use crate::ast::expression::{Expr, FunctionCall};
+    use crate::ast::query::TableFactor;
+    use crate::parser::parser::Parser;
+
+    #[derive(Default)]
+    struct References {
+        tables: Vec<String>,
+        functions: Vec<String>,
+        columns: usize,
+    }
+
+    impl Visitor for References {
+        fn visit_table_factor(&mut self, factor: &TableFactor) {
+            if let TableFactor::Table { name, .. } = factor {
+                self.tables.push(name.base_name().value.clone());
+            }
+            walk_table_factor(self, factor);
+        }
+
+        fn visit_function_call(&mut self, function: &FunctionCall) {
+            self.functions.push(function.name.base_name().value.clone());
+            super::walk_function_call(self, function);
+        }
+
+        fn visit_expr(&mut self, expr: &Expr) {
+            if matches!(expr, Expr::Identifier(_) | Expr::CompoundIdentifier(_)) {
+                self.columns += 1;
+            }
+            super::walk_expr(self, expr);
+        }
+    }
+
+    #[test]
+    fn visits_nested_queries_and_blocks() -> Result<()> {
+        let sql = "
+WITH recent AS (SELECT id FROM orders WHERE ts > DATEADD(day, -7, CURRENT_DATE))
+SELECT r.id, UPPER(c.name) FROM recent r JOIN customers c ON c.id = r.id
+WHERE EXISTS (SELECT 1 FROM refunds f WHERE f.id = r.id);
+BEGIN
+    IF (1 = 1) THEN
+        INSERT INTO audit SELECT COUNT(*) FROM staging;
+    END IF;
+END;";
+
+        let mut references = References::default();
+        for statement in Parser::from_sql(sql).parse_statements()? {
+            references.visit_statement(&statement);
+        }
+
+        assert_eq!(
+            references.tables,
+            vec!["orders", "recent", "customers", "refunds", "staging"]
+        );
+        assert_eq!(
+            references.functions,
+            vec!["DATEADD", "CURRENT_DATE", "UPPER", "COUNT"]
+        );
+        assert_eq!(references.columns, 9);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,731 @@
+//! Mutable traversal of the AST, for rewrites.
+//!
+//! The counterpart of [`crate::ast::visitor`]: every `visit_*` method defaults
+//! to the matching `walk_*_mut` function, which visits the node's children.
+
+use crate::ast::data_type::DataType;
+use crate::ast::expression::{
+    Expr, FunctionArg, FunctionCall, ObjectName, WindowFrameBound, WindowSpec,
+};
+use crate::ast::query::{
+    Cte, Join, JoinConstraint, JoinOperator, OrderByExpr, Query, Select, SelectItem, SetExpr,
+    TableFactor, TableWithJoins,
+};
+use crate::ast::scripting::{
+    Block, ConditionalBlock, CursorSource, Declaration, ExceptionHandler, ScriptStatement,
+};
+use crate::ast::stage::{CopyInto, CopyLocation, Stage, StageRef};
+use crate::ast::statement::{
+    ColumnDef, CreateRoutine, ExecuteImmediate, Merge, MergeAction, RoutineBody, RoutineReturns,
+    Statement,
+};
+
+pub trait VisitorMut {
+    fn visit_statement(&mut self, statement: &mut Statement) {
+        walk_statement_mut(self, statement);
+    }
+
+    fn visit_query(&mut self, query: &mut Query) {
+        walk_query_mut(self, query);
+    }
+
+    fn visit_cte(&mut self, cte: &mut Cte) {
+        walk_cte_mut(self, cte);
+    }
+
+    fn visit_set_expr(&mut self, set_expr: &mut SetExpr) {
+        walk_set_expr_mut(self, set_expr);
+    }
+
+    fn visit_select(&mut self, select: &mut Select) {
+        walk_select_mut(self, select);
+    }
+
+    fn visit_select_item(&mut self, item: &mut SelectItem) {
+        walk_select_item_mut(self, item);
+    }
+
+    fn visit_table_with_joins(&mut self, table: &mut TableWithJoins) {
+        walk_table_with_joins_mut(self, table);
+    }
+
+    fn visit_table_factor(&mut self, factor: &mut TableFactor) {
+        walk_table_factor_mut(self, factor);
+    }
+
+    fn visit_join(&mut self, join: &mut Join) {
+        walk_join_mut(self, join);
+    }
+
+    fn visit_order_by_expr(&mut self, order_by: &mut OrderByExpr) {
+        walk_order_by_expr_mut(self, order_by);
+    }
+
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        walk_expr_mut(self, expr);
+    }
+
+    fn visit_function_call(&mut self, function: &mut FunctionCall) {
+        walk_function_call_mut(self, function);
+    }
+
+    fn visit_window_spec(&mut self, window: &mut WindowSpec) {
+        walk_window_spec_mut(self, window);
+    }
+
+    fn visit_data_type(&mut self, data_type: &mut DataType) {
+        walk_data_type_mut(self, data_type);
+    }
+
+    /// A table, view, function, routine or stage name. Table references in
+    /// queries are visited through [`VisitorMut::visit_table_factor`] first.
+    fn visit_object_name(&mut self, _name: &mut ObjectName) {}
+
+    fn visit_column_def(&mut self, column: &mut ColumnDef) {
+        walk_column_def_mut(self, column);
+    }
+
+    fn visit_create_routine(&mut self, routine: &mut CreateRoutine) {
+        walk_create_routine_mut(self, routine);
+    }
+
+    fn visit_copy_into(&mut self, copy: &mut CopyInto) {
+        walk_copy_into_mut(self, copy);
+    }
+
+    fn visit_merge(&mut self, merge: &mut Merge) {
+        walk_merge_mut(self, merge);
+    }
+
+    fn visit_stage_ref(&mut self, stage: &mut StageRef) {
+        walk_stage_ref_mut(self, stage);
+    }
+
+    fn visit_block(&mut self, block: &mut Block) {
+        walk_block_mut(self, block);
+    }
+
+    fn visit_declaration(&mut self, declaration: &mut Declaration) {
+        walk_declaration_mut(self, declaration);
+    }
+
+    fn visit_script_statement(&mut self, statement: &mut ScriptStatement) {
+        walk_script_statement_mut(self, statement);
+    }
+}
+
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement) {
+    match statement {
+        Statement::Error(_)
+        | Statement::StartTransaction
+        | Statement::Commit
+        | Statement::Rollback => {}
+        Statement::Query(query) => visitor.visit_query(query),
+        Statement::Insert {
+            table_name, source, ..
+        } => {
+            visitor.visit_object_name(table_name);
+            visitor.visit_query(source);
+        }
+        Statement::Update {
+            table,
+            assignments,
+            from,
+            selection,
+        } => {
+            visitor.visit_table_with_joins(table);
+            for assignment in assignments {
+                visitor.visit_expr(&mut assignment.value);
+            }
+            for table in from {
+                visitor.visit_table_with_joins(table);
+            }
+            if let Some(selection) = selection {
+                visitor.visit_expr(selection);
+            }
+        }
+        Statement::Delete {
+            table_name,
+            using,
+            selection,
+        } => {
+            visitor.visit_object_name(table_name);
+            for table in using {
+                visitor.visit_table_with_joins(table);
+            }
+            if let Some(selection) = selection {
+                visitor.visit_expr(selection);
+            }
+        }
+        Statement::Merge(merge) => visitor.visit_merge(merge),
+        Statement::CreateTable {
+            name,
+            columns,
+            copy_of,
+            query,
+            ..
+        } => {
+            visitor.visit_object_name(name);
+            for column in columns {
+                visitor.visit_column_def(column);
+            }
+            if let Some(copy_of) = copy_of {
+                visitor.visit_object_name(copy_of);
+            }
+            if let Some(query) = query {
+                visitor.visit_query(query);
+            }
+        }
+        Statement::CreateView { name, query, .. } => {
+            visitor.visit_object_name(name);
+            visitor.visit_query(query);
+        }
+        Statement::CreateRoutine(routine) => visitor.visit_create_routine(routine),
+        Statement::CreateStage { name, .. } | Statement::CreateFileFormat { name, .. } => {
+            visitor.visit_object_name(name);
+        }
+        Statement::CopyInto(copy) => visitor.visit_copy_into(copy),
+        Statement::Put { stage, .. }
+        | Statement::Get { stage, .. }
+        | Statement::List { stage, .. }
+        | Statement::Remove { stage, .. } => visitor.visit_stage_ref(stage),
+        Statement::Call { name, args } => {
+            visitor.visit_object_name(name);
+            walk_function_args_mut(visitor, args);
+        }
+        Statement::Drop { name, .. } => visitor.visit_object_name(name),
+        Statement::Truncate { table_name, .. } => visitor.visit_object_name(table_name),
+        Statement::Block(block) => visitor.visit_block(block),
+        Statement::ExecuteImmediate(ExecuteImmediate::Block(block)) => visitor.visit_block(block),
+        Statement::ExecuteImmediate(ExecuteImmediate::Expr(expr)) => visitor.visit_expr(expr),
+    }
+}
+
+pub fn walk_query_mut<V: VisitorMut + ?Sized>(visitor: &mut V, query: &mut Query) {
+    if let Some(with) = &mut query.with {
+        for cte in &mut with.ctes {
+            visitor.visit_cte(cte);
+        }
+    }
+    visitor.visit_set_expr(&mut query.body);
+    for order_by in &mut query.order_by {
+        visitor.visit_order_by_expr(order_by);
+    }
+    if let Some(limit) = &mut query.limit {
+        visitor.visit_expr(limit);
+    }
+    if let Some(offset) = &mut query.offset {
+        visitor.visit_expr(offset);
+    }
+}
+
+pub fn walk_cte_mut<V: VisitorMut + ?Sized>(visitor: &mut V, cte: &mut Cte) {
+    visitor.visit_query(&mut cte.query);
+}
+
+pub fn walk_set_expr_mut<V: VisitorMut + ?Sized>(visitor: &mut V, set_expr: &mut SetExpr) {
+    match set_expr {
+        SetExpr::Select(select) => visitor.visit_select(select),
+        SetExpr::Query(query) => visitor.visit_query(query),
+        SetExpr::SetOperation { left, right, .. } => {
+            visitor.visit_set_expr(left);
+            visitor.visit_set_expr(right);
+        }
+        SetExpr::Values(rows) => {
+            for expr in rows.iter_mut().flatten() {
+                visitor.visit_expr(expr);
+            }
+        }
+    }
+}
+
+/// Visits the clauses of a `SELECT` in the order Snowflake evaluates them,
+/// starting with `FROM`, so that tables are seen before the columns that
+/// refer to them.
+pub fn walk_select_mut<V: VisitorMut + ?Sized>(visitor: &mut V, select: &mut Select) {
+    for table in &mut select.from {
+        visitor.visit_table_with_joins(table);
+    }
+    if let Some(selection) = &mut select.selection {
+        visitor.visit_expr(selection);
+    }
+    for expr in &mut select.group_by {
+        visitor.visit_expr(expr);
+    }
+    if let Some(having) = &mut select.having {
+        visitor.visit_expr(having);
+    }
+    if let Some(qualify) = &mut select.qualify {
+        visitor.visit_expr(qualify);
+    }
+    if let Some(top) = &mut select.top {
+        visitor.visit_expr(top);
+    }
+    for item in &mut select.projection {
+        visitor.visit_select_item(item);
+    }
+    for expr in &mut select.into {
+        visitor.visit_expr(expr);
+    }
+}
+
+pub fn walk_select_item_mut<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut SelectItem) {
+    match item {
+        SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+            visitor.visit_expr(expr)
+        }
+        SelectItem::QualifiedWildcard(_, options) | SelectItem::Wildcard(options) => {
+            for (expr, _) in &mut options.replace {
+                visitor.visit_expr(expr);
+            }
+        }
+    }
+}
+
+pub fn walk_table_with_joins_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    table: &mut TableWithJoins,
+) {
+    visitor.visit_table_factor(&mut table.relation);
+    for join in &mut table.joins {
+        visitor.visit_join(join);
+    }
+}
+
+pub fn walk_table_factor_mut<V: VisitorMut + ?Sized>(visitor: &mut V, factor: &mut TableFactor) {
+    match factor {
+        TableFactor::Table { name, .. } => visitor.visit_object_name(name),
+        TableFactor::Derived { subquery, .. } => visitor.visit_query(subquery),
+        TableFactor::TableFunction { expr, .. } => visitor.visit_expr(expr),
+        TableFactor::NestedJoin {
+            table_with_joins, ..
+        } => visitor.visit_table_with_joins(table_with_joins),
+        TableFactor::Stage { stage, .. } => visitor.visit_stage_ref(stage),
+    }
+}
+
+pub fn walk_join_mut<V: VisitorMut + ?Sized>(visitor: &mut V, join: &mut Join) {
+    visitor.visit_table_factor(&mut join.relation);
+    match &mut join.join_operator {
+        JoinOperator::Inner(constraint)
+        | JoinOperator::LeftOuter(constraint)
+        | JoinOperator::RightOuter(constraint)
+        | JoinOperator::FullOuter(constraint) => {
+            if let JoinConstraint::On(expr) = constraint {
+                visitor.visit_expr(expr);
+            }
+        }
+        JoinOperator::CrossJoin => {}
+    }
+}
+
+pub fn walk_order_by_expr_mut<V: VisitorMut + ?Sized>(visitor: &mut V, order_by: &mut OrderByExpr) {
+    visitor.visit_expr(&mut order_by.expr);
+}
+
+pub fn walk_expr_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut Expr) {
+    match expr {
+        Expr::Error(_)
+        | Expr::Identifier(_)
+        | Expr::CompoundIdentifier(_)
+        | Expr::Variable(_)
+        | Expr::SessionVariable(_)
+        | Expr::Positional(_)
+        | Expr::QualifiedPositional { .. }
+        | Expr::Value(_)
+        | Expr::Interval(_)
+        | Expr::Placeholder
+        | Expr::Wildcard => {}
+        Expr::TypedString { data_type, .. } => visitor.visit_data_type(data_type),
+        Expr::BinaryOp { left, right, .. } => {
+            visitor.visit_expr(left);
+            visitor.visit_expr(right);
+        }
+        Expr::UnaryOp { expr, .. }
+        | Expr::IsNull { expr, .. }
+        | Expr::JsonAccess { expr, .. }
+        | Expr::Nested(expr) => visitor.visit_expr(expr),
+        Expr::InList { expr, list, .. } => {
+            visitor.visit_expr(expr);
+            for item in list {
+                visitor.visit_expr(item);
+            }
+        }
+        Expr::InSubquery { expr, subquery, .. } => {
+            visitor.visit_expr(expr);
+            visitor.visit_query(subquery);
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            visitor.visit_expr(expr);
+            visitor.visit_expr(low);
+            visitor.visit_expr(high);
+        }
+        Expr::Like { expr, pattern, .. } => {
+            visitor.visit_expr(expr);
+            visitor.visit_expr(pattern);
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            else_result,
+        } => {
+            if let Some(operand) = operand {
+                visitor.visit_expr(operand);
+            }
+            for (condition, result) in conditions {
+                visitor.visit_expr(condition);
+                visitor.visit_expr(result);
+            }
+            if let Some(else_result) = else_result {
+                visitor.visit_expr(else_result);
+            }
+        }
+        Expr::Cast {
+            expr, data_type, ..
+        } => {
+            visitor.visit_expr(expr);
+            visitor.visit_data_type(data_type);
+        }
+        Expr::Exists { subquery, .. } | Expr::Subquery(subquery) => visitor.visit_query(subquery),
+        Expr::Subscript { expr, index } => {
+            visitor.visit_expr(expr);
+            visitor.visit_expr(index);
+        }
+        Expr::Array(items) => {
+            for item in items {
+                visitor.visit_expr(item);
+            }
+        }
+        Expr::Object(pairs) => {
+            for (key, value) in pairs {
+                visitor.visit_expr(key);
+                visitor.visit_expr(value);
+            }
+        }
+        Expr::Lambda { body, .. } => visitor.visit_expr(body),
+        Expr::Function(function) => visitor.visit_function_call(function),
+    }
+}
+
+pub fn walk_function_call_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    function: &mut FunctionCall,
+) {
+    visitor.visit_object_name(&mut function.name);
+    walk_function_args_mut(visitor, &mut function.args);
+    for order_by in &mut function.within_group {
+        visitor.visit_order_by_expr(order_by);
+    }
+    if let Some(window) = &mut function.over {
+        visitor.visit_window_spec(window);
+    }
+}
+
+pub fn walk_window_spec_mut<V: VisitorMut + ?Sized>(visitor: &mut V, window: &mut WindowSpec) {
+    for expr in &mut window.partition_by {
+        visitor.visit_expr(expr);
+    }
+    for order_by in &mut window.order_by {
+        visitor.visit_order_by_expr(order_by);
+    }
+    if let Some(frame) = &mut window.window_frame {
+        for bound in std::iter::once(&mut frame.start_bound).chain(&mut frame.end_bound) {
+            if let WindowFrameBound::Preceding(Some(expr))
+            | WindowFrameBound::Following(Some(expr)) = bound
+            {
+                visitor.visit_expr(expr);
+            }
+        }
+    }
+}
+
+pub fn walk_data_type_mut<V: VisitorMut + ?Sized>(visitor: &mut V, data_type: &mut DataType) {
+    match data_type {
+        DataType::Object(fields) => {
+            for (_, field_type) in fields {
+                visitor.visit_data_type(field_type);
+            }
+        }
+        DataType::Array(Some(element)) | DataType::Vector { element, .. } => {
+            visitor.visit_data_type(element)
+        }
+        DataType::Map { key, value } => {
+            visitor.visit_data_type(key);
+            visitor.visit_data_type(value);
+        }
+        _ => {}
+    }
+}
+
+pub fn walk_column_def_mut<V: VisitorMut + ?Sized>(visitor: &mut V, column: &mut ColumnDef) {
+    visitor.visit_data_type(&mut column.data_type);
+}
+
+pub fn walk_create_routine_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    routine: &mut CreateRoutine,
+) {
+    visitor.visit_object_name(&mut routine.name);
+    for arg in &mut routine.args {
+        visitor.visit_data_type(&mut arg.data_type);
+        if let Some(default) = &mut arg.default {
+            visitor.visit_expr(default);
+        }
+    }
+    match &mut routine.returns {
+        Some(RoutineReturns::DataType(data_type)) => visitor.visit_data_type(data_type),
+        Some(RoutineReturns::Table(columns)) => {
+            for column in columns {
+                visitor.visit_column_def(column);
+            }
+        }
+        None => {}
+    }
+    match &mut routine.body {
+        RoutineBody::Script(block) => visitor.visit_block(block),
+        RoutineBody::Query(query) => visitor.visit_query(query),
+        RoutineBody::Expr(expr) => visitor.visit_expr(expr),
+        RoutineBody::Unparsed(_) => {}
+    }
+}
+
+pub fn walk_copy_into_mut<V: VisitorMut + ?Sized>(visitor: &mut V, copy: &mut CopyInto) {
+    for location in [&mut copy.into, &mut copy.from] {
+        match location {
+            CopyLocation::Table { name, .. } => visitor.visit_object_name(name),
+            CopyLocation::Stage(stage) => visitor.visit_stage_ref(stage),
+            CopyLocation::External(_) => {}
+            CopyLocation::Query(query) => visitor.visit_query(query),
+        }
+    }
+    if let Some(partition_by) = &mut copy.partition_by {
+        visitor.visit_expr(partition_by);
+    }
+}
+
+pub fn walk_stage_ref_mut<V: VisitorMut + ?Sized>(visitor: &mut V, stage: &mut StageRef) {
+    match &mut stage.stage {
+        Stage::Named(name) | Stage::Table(name) => visitor.visit_object_name(name),
+        Stage::User => {}
+    }
+}
+
+pub fn walk_block_mut<V: VisitorMut + ?Sized>(visitor: &mut V, block: &mut Block) {
+    for declaration in &mut block.declarations {
+        visitor.visit_declaration(declaration);
+    }
+    walk_script_statements_mut(visitor, &mut block.statements);
+    for ExceptionHandler { statements, .. } in &mut block.exception_handlers {
+        walk_script_statements_mut(visitor, statements);
+    }
+}
+
+pub fn walk_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut Declaration,
+) {
+    match declaration {
+        Declaration::Variable {
+            data_type, default, ..
+        } => {
+            if let Some(data_type) = data_type {
+                visitor.visit_data_type(data_type);
+            }
+            if let Some(default) = default {
+                visitor.visit_expr(default);
+            }
+        }
+        Declaration::Cursor { source, .. } => {
+            if let CursorSource::Query(query) = source {
+                visitor.visit_query(query);
+            }
+        }
+        Declaration::ResultSet { default, .. } => {
+            if let Some(default) = default {
+                visitor.visit_expr(default);
+            }
+        }
+        Declaration::Exception { code, message, .. } => {
+            for expr in [code, message].into_iter().flatten() {
+                visitor.visit_expr(expr);
+            }
+        }
+    }
+}
+
+pub fn walk_script_statement_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    statement: &mut ScriptStatement,
+) {
+    match statement {
+        ScriptStatement::Sql(statement) => visitor.visit_statement(statement),
+        ScriptStatement::Block(block) => visitor.visit_block(block),
+        ScriptStatement::Let(declaration) => visitor.visit_declaration(declaration),
+        ScriptStatement::Assign { value, .. } => visitor.visit_expr(value),
+        ScriptStatement::If {
+            branches,
+            else_statements,
+        } => walk_branches_mut(visitor, branches, else_statements),
+        ScriptStatement::Case {
+            operand,
+            branches,
+            else_statements,
+        } => {
+            if let Some(operand) = operand {
+                visitor.visit_expr(operand);
+            }
+            walk_branches_mut(visitor, branches, else_statements);
+        }
+        ScriptStatement::ForRange {
+            start,
+            end,
+            statements,
+            ..
+        } => {
+            visitor.visit_expr(start);
+            visitor.visit_expr(end);
+            walk_script_statements_mut(visitor, statements);
+        }
+        ScriptStatement::ForCursor { statements, .. }
+        | ScriptStatement::Loop { statements, .. } => {
+            walk_script_statements_mut(visitor, statements)
+        }
+        ScriptStatement::While {
+            condition,
+            statements,
+            ..
+        } => {
+            visitor.visit_expr(condition);
+            walk_script_statements_mut(visitor, statements);
+        }
+        ScriptStatement::Repeat {
+            statements, until, ..
+        } => {
+            walk_script_statements_mut(visitor, statements);
+            visitor.visit_expr(until);
+        }
+        ScriptStatement::Return(expr) => {
+            if let Some(expr) = expr {
+                visitor.visit_expr(expr);
+            }
+        }
+        ScriptStatement::Open { using, .. } => {
+            for expr in using {
+                visitor.visit_expr(expr);
+            }
+        }
+        ScriptStatement::Break(_)
+        | ScriptStatement::Continue(_)
+        | ScriptStatement::Raise(_)
+        | ScriptStatement::Fetch { .. }
+        | ScriptStatement::Close(_)
+        | ScriptStatement::Null => {}
+    }
+}
+
+pub fn walk_merge_mut<V: VisitorMut + ?Sized>(visitor: &mut V, merge: &mut Merge) {
+    visitor.visit_table_factor(&mut merge.target);
+    visitor.visit_table_factor(&mut merge.source);
+    visitor.visit_expr(&mut merge.on);
+    for clause in &mut merge.clauses {
+        if let Some(predicate) = &mut clause.predicate {
+            visitor.visit_expr(predicate);
+        }
+        match &mut clause.action {
+            MergeAction::Update(assignments) => {
+                for assignment in assignments {
+                    visitor.visit_expr(&mut assignment.value);
+                }
+            }
+            MergeAction::Delete => {}
+            MergeAction::Insert { values, .. } => {
+                for value in values {
+                    visitor.visit_expr(value);
+                }
+            }
+        }
+    }
+}
+
+fn walk_function_args_mut<V: VisitorMut + ?Sized>(visitor: &mut V, args: &mut [FunctionArg]) {
+    for arg in args {
+        match arg {
+            FunctionArg::Unnamed(expr) | FunctionArg::Named { value: expr, .. } => {
+                visitor.visit_expr(expr)
+            }
+        }
+    }
+}
+
+fn walk_script_statements_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    statements: &mut [ScriptStatement],
+) {
+    for statement in statements {
+        visitor.visit_script_statement(statement);
+    }
+}
+
+fn walk_branches_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    branches: &mut [ConditionalBlock],
+    else_statements: &mut Option<Vec<ScriptStatement>>,
+) {
+    for branch in branches {
+        visitor.visit_expr(&mut branch.condition);
+        walk_script_statements_mut(visitor, &mut branch.statements);
+    }
+    if let Some(else_statements) = else_statements {
+        walk_script_statements_mut(visitor, else_statements);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{walk_expr_mut, VisitorMut};
+    use crate::ast::expression::{Expr, Ident, ObjectName};
+    use crate::parser::parser::Parser;
+
+    /// Renames a table, and qualified references to it.
+    struct RenameTable;
+
+    impl VisitorMut for RenameTable {
+        fn visit_object_name(&mut self, name: &mut ObjectName) {
+            if name.base_name().value.eq_ignore_ascii_case("old_orders") {
+                *name = ObjectName(vec![Ident::new("sales"), Ident::new("orders")]);
+            }
+        }
+
+        fn visit_expr(&mut self, expr: &mut Expr) {
+            if let Expr::CompoundIdentifier(parts) = expr {
+                if parts[0].value.eq_ignore_ascii_case("old_orders") {
+                    parts[0] = Ident::new("orders");
+                }
+            }
+            walk_expr_mut(self, expr);
+        }
+    }
+
+    #[test]
+    fn rewrites_in_place() -> Result<()> {
+        let mut statements = Parser::from_sql(
+            "SELECT old_orders.id FROM old_orders WHERE old_orders.id IN (SELECT id FROM old_orders)",
+        )
+        .parse_statements()?;
+        for statement in &mut statements {
+            RenameTable.visit_statement(statement);
+        }
+
+        let expected = Parser::from_sql(
+            "SELECT orders.id FROM sales.orders WHERE orders.id IN (SELECT id FROM sales.orders)",
+        )
+        .parse_statements()?;
+        assert_eq!(statements, expected);
+
+        Ok(())
+    }
+}
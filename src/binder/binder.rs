@@ -0,0 +1,1320 @@
+//! Name resolution for column references.
+//!
+//! The [`Binder`] walks statements with a stack of scopes, one per query block,
+//! and resolves every column reference to the relation it reads from: a base
+//! table, a CTE, a subquery, a table function such as `FLATTEN` or a staged
+//! file. Names are looked up from the innermost scope outwards, so references
+//! from correlated subqueries resolve to the enclosing query, and select-list
+//! aliases are visible to later items and to `WHERE`, `GROUP BY`, `HAVING`,
+//! `QUALIFY` and `ORDER BY`, as Snowflake allows.
+//!
+//! The columns of base tables are only known when declared with
+//! [`Binder::with_table`]. Otherwise an unqualified name is attributed to a
+//! table only when it's the sole candidate in its scope.
+//!
+//! Along the way the binder tracks the source columns of every output column,
+//! see [`Binder::lineage`], and infers its type, see [`crate::types::types`].
+
+use crate::ast::data_type::DataType;
+use crate::ast::expression::{Expr, FunctionArg, FunctionCall, Ident, ObjectName};
+use crate::ast::query::{
+    Cte, JoinConstraint, JoinOperator, OrderByExpr, Query, Select, SelectItem, SetExpr, TableAlias,
+    TableFactor, TableWithJoins, WildcardOptions,
+};
+use crate::ast::scripting::{Block, Declaration, ScriptStatement};
+use crate::ast::statement::{Assignment, CreateRoutine, Merge, MergeAction, Statement};
+use crate::ast::visitor::{self, Visitor};
+use crate::functions::functions::{FunctionKind, ReturnType};
+use crate::lineage::lineage::{rename_columns, ColumnLineage, LineageKind, SourceColumn};
+use crate::types::types::{canonical, common_type, ImplicitCast};
+
+/// The columns every staged file has besides its positional `$n` columns.
+const STAGE_METADATA_COLUMNS: &[&str] = &[
+    "METADATA$FILENAME",
+    "METADATA$FILE_ROW_NUMBER",
+    "METADATA$FILE_CONTENT_KEY",
+    "METADATA$FILE_LAST_MODIFIED",
+    "METADATA$START_SCAN_TIME",
+];
+
+/// Functions taking a date or time part such as `day` as a bare word.
+const DATE_PART_FUNCTIONS: &[&str] = &[
+    "DATE_PART",
+    "DATE_TRUNC",
+    "DATEADD",
+    "DATEDIFF",
+    "EXTRACT",
+    "LAST_DAY",
+    "TIMESTAMPADD",
+    "TIMESTAMPDIFF",
+];
+
+const DATE_PARTS: &[&str] = &[
+    "YEAR",
+    "YEARS",
+    "Y",
+    "YY",
+    "YYY",
+    "YYYY",
+    "YR",
+    "YRS",
+    "QUARTER",
+    "QUARTERS",
+    "Q",
+    "QTR",
+    "QTRS",
+    "MONTH",
+    "MONTHS",
+    "MM",
+    "MON",
+    "MONS",
+    "WEEK",
+    "WEEKS",
+    "W",
+    "WK",
+    "WEEKOFYEAR",
+    "WOY",
+    "WY",
+    "DAY",
+    "DAYS",
+    "D",
+    "DD",
+    "DAYOFMONTH",
+    "DAYOFWEEK",
+    "DOW",
+    "DW",
+    "DAYOFYEAR",
+    "DOY",
+    "DY",
+    "HOUR",
+    "HOURS",
+    "H",
+    "HH",
+    "HR",
+    "HRS",
+    "MINUTE",
+    "MINUTES",
+    "M",
+    "MI",
+    "MIN",
+    "MINS",
+    "SECOND",
+    "SECONDS",
+    "S",
+    "SEC",
+    "SECS",
+    "MILLISECOND",
+    "MS",
+    "MSEC",
+    "MICROSECOND",
+    "US",
+    "USEC",
+    "NANOSECOND",
+    "NS",
+    "NSEC",
+    "EPOCH",
+    "EPOCH_SECOND",
+    "EPOCH_MILLISECOND",
+    "EPOCH_MICROSECOND",
+    "EPOCH_NANOSECOND",
+];
+
+/// Where a relation in scope reads its rows from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+    /// A table or view, by its declared name when it's known and as written
+    /// otherwise, e.g. `["RAW", "EMPLOYEES"]`.
+    Table(Vec<String>),
+    Cte(String),
+    /// A subquery in `FROM`, lateral or not.
+    Derived,
+    /// A table function such as `FLATTEN`.
+    TableFunction(String),
+    Stage,
+}
+
+/// A column of a relation in scope.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnRef {
+    /// The name the relation goes by in its query, i.e. its alias if it has
+    /// one. `None` for unaliased subqueries and stages.
+    pub relation: Option<String>,
+    pub source: Source,
+    pub column: String,
+    /// How many query blocks out the relation is, non-zero for correlated
+    /// references.
+    pub depth: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Resolution {
+    Column(ColumnRef),
+    /// An alias of the select list, e.g. `doubled` in
+    /// `SELECT x * 2 AS doubled FROM t WHERE doubled > 10`.
+    Alias(String),
+    /// A Snowflake Scripting variable, loop variable or routine argument.
+    Variable(String),
+    /// A name more than one relation in scope may provide, listing them.
+    Ambiguous(Vec<String>),
+    Unresolved,
+}
+
+/// A column reference and what it resolved to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Binding {
+    /// The reference as written, e.g. `e.id`.
+    pub reference: Vec<Ident>,
+    pub resolution: Resolution,
+}
+
+impl Binding {
+    /// Describes an ambiguous or unresolved reference, `None` if it resolved.
+    pub fn error(&self) -> Option<String> {
+        let name = self
+            .reference
+            .iter()
+            .map(|part| part.value.as_str())
+            .collect::<Vec<_>>()
+            .join(".");
+        match &self.resolution {
+            Resolution::Ambiguous(relations) => Some(format!(
+                "Column reference {} is ambiguous, it could refer to {}",
+                name,
+                relations.join(" or ")
+            )),
+            Resolution::Unresolved => Some(format!("Column {} could not be resolved", name)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Relation {
+    /// The possibly qualified name the relation can be referenced by, which
+    /// is just its alias when it has one. Empty for unaliased subqueries.
+    name: Vec<String>,
+    source: Source,
+    /// `None` when the columns aren't known, e.g. for undeclared tables.
+    columns: Option<Vec<ColumnLineage>>,
+}
+
+impl Relation {
+    fn aliased(mut self, alias: Option<&TableAlias>) -> Self {
+        if let Some(alias) = alias {
+            self.name = vec![normalize(&alias.name)];
+            if !alias.columns.is_empty() {
+                let names = alias.columns.iter().map(normalize).collect();
+                self.columns = Some(rename_columns(self.columns.take(), names));
+            }
+        }
+        self
+    }
+
+    fn is_named(&self, qualifier: &[String]) -> bool {
+        !self.name.is_empty() && self.name.ends_with(qualifier)
+    }
+
+    fn has_column(&self, column: &str) -> Option<bool> {
+        self.columns.as_ref().map(|columns| {
+            columns
+                .iter()
+                .any(|output| output.name.as_deref() == Some(column))
+        })
+    }
+
+    fn display_name(&self) -> String {
+        match self.name.is_empty() {
+            true => "a subquery".to_string(),
+            false => self.name.join("."),
+        }
+    }
+
+    fn column(&self, column: &str, depth: usize) -> Resolution {
+        Resolution::Column(ColumnRef {
+            relation: self.name.last().cloned(),
+            source: self.source.clone(),
+            column: column.to_string(),
+            depth,
+        })
+    }
+
+    /// The source columns `column` derives from, which for a table is the
+    /// column itself.
+    fn sources(&self, column: &str) -> Vec<SourceColumn> {
+        let output = self
+            .columns
+            .iter()
+            .flatten()
+            .find(|output| output.name.as_deref() == Some(column));
+        match (output, &self.source) {
+            (Some(output), _) if !output.sources.is_empty() => output.sources.clone(),
+            (_, Source::Table(table)) => vec![SourceColumn {
+                table: table.clone(),
+                column: column.to_string(),
+                kind: LineageKind::Direct,
+            }],
+            _ => vec![],
+        }
+    }
+
+    fn data_type(&self, column: &str) -> Option<DataType> {
+        self.columns
+            .iter()
+            .flatten()
+            .find(|output| output.name.as_deref() == Some(column))
+            .and_then(|output| output.data_type.clone())
+    }
+
+    /// The columns `*` expands to, when they're known.
+    fn wildcard_columns(&self) -> Option<Vec<ColumnLineage>> {
+        let columns = self.columns.as_ref()?;
+        let lineage = columns.iter().map(|output| ColumnLineage {
+            name: output.name.clone(),
+            sources: match &output.name {
+                Some(name) => self.sources(name),
+                None => output.sources.clone(),
+            },
+            data_type: output.data_type.clone(),
+        });
+        Some(lineage.collect())
+    }
+}
+
+/// The relations and aliases visible in one query block.
+#[derive(Default)]
+struct Scope {
+    relations: Vec<Relation>,
+    /// Columns merged by `JOIN ... USING`, which aren't ambiguous.
+    using: Vec<String>,
+    /// The select-list aliases defined so far.
+    aliases: Vec<ColumnLineage>,
+    /// The columns deciding which rows the block returns, from `JOIN ... ON`,
+    /// `WHERE`, `GROUP BY`, `HAVING` and `QUALIFY`.
+    filters: Vec<SourceColumn>,
+}
+
+/// A declared table's qualified name, with its columns and their types.
+type DeclaredTable = (Vec<String>, Vec<(String, Option<DataType>)>);
+
+#[derive(Default)]
+pub struct Binder {
+    tables: Vec<DeclaredTable>,
+    scopes: Vec<Scope>,
+    /// CTEs visible at this point, innermost last.
+    ctes: Vec<Relation>,
+    /// Scripting variables and routine arguments, one frame per block.
+    variables: Vec<Vec<String>>,
+    lambda_params: Vec<String>,
+    bindings: Vec<Binding>,
+    /// The source columns of the expression being bound.
+    collected: Vec<SourceColumn>,
+    /// How many aggregate calls the expression being bound is nested in.
+    aggregates: usize,
+    pub(crate) expansions: Vec<Expansion>,
+    /// The type of the one column of each expression subquery bound, by the
+    /// address of its `Query`.
+    pub(crate) subquery_types: Vec<(usize, Option<DataType>)>,
+    pub(crate) casts: Vec<ImplicitCast>,
+}
+
+/// A column a wildcard select item stands for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WildcardColumn {
+    /// The name of the relation the column is read from, `None` for an
+    /// unaliased subquery.
+    pub relation: Option<String>,
+    pub column: String,
+    /// The output name, which differs from `column` when renamed.
+    pub name: String,
+    /// The index of the `REPLACE` expression giving the column its value.
+    pub replacement: Option<usize>,
+}
+
+/// The columns of the `index`th item of a select, a wildcard.
+#[derive(Clone, Debug)]
+pub(crate) struct Expansion {
+    /// The address of the `Select`, identifying it while the statement is
+    /// borrowed.
+    pub select: usize,
+    pub index: usize,
+    /// `None` when some column can't be named, e.g. an unknown table's.
+    pub columns: Option<Vec<WildcardColumn>>,
+    /// Whether the select reads several relations, so its columns need
+    /// their relation's name.
+    pub qualify: bool,
+}
+
+impl Binder {
+    pub fn new() -> Self {
+        Binder::default()
+    }
+
+    /// Declares the columns of a table or view, named as Snowflake stores it,
+    /// e.g. `RAW.EMPLOYEES` with `["ID", "NAME"]`.
+    pub fn with_table(mut self, name: &str, columns: &[&str]) -> Self {
+        self.add_table(
+            name,
+            columns.iter().map(|column| column.to_string()).collect(),
+        );
+        self
+    }
+
+    pub fn add_table(&mut self, name: &str, columns: Vec<String>) {
+        let columns = columns.into_iter().map(|column| (column, None)).collect();
+        self.add_typed_table(name.split('.').map(String::from).collect(), columns);
+    }
+
+    /// Declares a table by the parts of its name, e.g. `["RAW", "my.t"]`,
+    /// along with the types of its columns, so that the expressions reading
+    /// them can be typed.
+    pub fn add_typed_table(&mut self, name: Vec<String>, columns: Vec<(String, Option<DataType>)>) {
+        self.tables.push((name, columns));
+    }
+
+    /// Resolves the column references of `statement`, in the order they're
+    /// bound: `FROM` before the select list, then the remaining clauses.
+    pub fn bind(&mut self, statement: &Statement) -> Vec<Binding> {
+        self.visit_statement(statement);
+        self.take_bindings()
+    }
+
+    pub(crate) fn take_bindings(&mut self) -> Vec<Binding> {
+        self.collected.clear();
+        self.expansions.clear();
+        self.subquery_types.clear();
+        self.casts.clear();
+        std::mem::take(&mut self.bindings)
+    }
+
+    fn scope(&mut self) -> &mut Scope {
+        self.scopes
+            .last_mut()
+            .expect("relations are bound inside a scope")
+    }
+
+    /// The declared name and columns of the one table matching `name`.
+    fn declared_table(&self, name: &[String]) -> Option<&DeclaredTable> {
+        let mut matches = self
+            .tables
+            .iter()
+            .filter(|(table, _)| table.ends_with(name) || name.ends_with(table));
+        match (matches.next(), matches.next()) {
+            (Some(table), None) => Some(table),
+            _ => None,
+        }
+    }
+
+    /// Runs `bind` and returns the source columns of the expressions it bound.
+    fn collect(&mut self, bind: impl FnOnce(&mut Self)) -> Vec<SourceColumn> {
+        let outer = std::mem::take(&mut self.collected);
+        let aggregates = std::mem::take(&mut self.aggregates);
+        bind(self);
+        self.aggregates = aggregates;
+        let sources = std::mem::replace(&mut self.collected, outer);
+        let mut unique = Vec::with_capacity(sources.len());
+        for source in sources {
+            if !unique.contains(&source) {
+                unique.push(source);
+            }
+        }
+        unique
+    }
+
+    fn collect_sources(&mut self, sources: Vec<SourceColumn>, kind: LineageKind) {
+        self.collected
+            .extend(sources.into_iter().map(|source| source.derived(kind)));
+    }
+
+    /// Binds an output expression, whose columns are copied when it's a
+    /// plain reference and transformed otherwise.
+    fn bind_output(&mut self, expr: &Expr) -> Vec<SourceColumn> {
+        let sources = self.collect(|binder| binder.visit_expr(expr));
+        match expr {
+            Expr::Identifier(_) | Expr::CompoundIdentifier(_) => sources,
+            _ => sources
+                .into_iter()
+                .map(|source| source.derived(LineageKind::Transformation))
+                .collect(),
+        }
+    }
+
+    /// The columns `*` or `t.*` stands for after its `EXCLUDE`, `REPLACE`
+    /// and `RENAME` options, when the columns of the relations are known.
+    fn bind_wildcard(
+        &mut self,
+        select: &Select,
+        index: usize,
+        qualifier: Option<&ObjectName>,
+        options: &WildcardOptions,
+    ) -> Option<Vec<ColumnLineage>> {
+        let relations = &self.scope().relations;
+        let qualify = relations.len() > 1;
+        let relations: Vec<_> = match qualifier {
+            Some(name) => {
+                let qualifier: Vec<String> = name.0.iter().map(normalize).collect();
+                let relation = relations
+                    .iter()
+                    .find(|relation| relation.is_named(&qualifier));
+                relation.into_iter().collect()
+            }
+            None => relations.iter().collect(),
+        };
+        let relations: Option<Vec<_>> = relations
+            .into_iter()
+            .map(|relation| Some((relation.name.join("."), relation.wildcard_columns()?)))
+            .collect();
+
+        let excluded: Vec<String> = options.exclude.iter().map(normalize).collect();
+        let mut expansion = Some(Vec::new());
+        let mut outputs = Vec::new();
+        for (relation, columns) in relations? {
+            for mut output in columns {
+                let Some(column) = output.name.clone() else {
+                    // Unnamed columns can't be selected by name
+                    expansion = None;
+                    outputs.push(output);
+                    continue;
+                };
+                if excluded.contains(&column) {
+                    continue;
+                }
+
+                let replacement = options
+                    .replace
+                    .iter()
+                    .position(|(_, name)| normalize(name) == column);
+                if let Some(replacement) = replacement {
+                    let expr = &options.replace[replacement].0;
+                    output.sources = self.bind_output(expr);
+                    output.data_type = self.infer(expr);
+                }
+                if let Some((_, name)) = options
+                    .rename
+                    .iter()
+                    .find(|(name, _)| normalize(name) == column)
+                {
+                    output.name = Some(normalize(name));
+                }
+
+                if let Some(expansion) = &mut expansion {
+                    expansion.push(WildcardColumn {
+                        relation: (!relation.is_empty()).then(|| relation.clone()),
+                        name: output.name.clone().unwrap_or_default(),
+                        column,
+                        replacement,
+                    });
+                }
+                outputs.push(output);
+            }
+        }
+
+        self.expansions.push(Expansion {
+            select: select as *const Select as usize,
+            index,
+            columns: expansion,
+            qualify,
+        });
+        Some(outputs)
+    }
+
+    fn bind_filter(&mut self, expr: &Expr) {
+        let sources = self.collect(|binder| binder.visit_expr(expr));
+        self.infer(expr);
+        let filters = &mut self.scope().filters;
+        for source in sources {
+            let source = source.derived(LineageKind::Filter);
+            if !filters.contains(&source) {
+                filters.push(source);
+            }
+        }
+    }
+
+    /// Binds a query in its own scope, returning its output columns when
+    /// they're known.
+    pub(crate) fn bind_query(&mut self, query: &Query) -> Option<Vec<ColumnLineage>> {
+        let ctes = self.ctes.len();
+        if let Some(with) = &query.with {
+            for cte in &with.ctes {
+                self.bind_cte(cte, with.recursive);
+            }
+        }
+        let columns = self.bind_set_expr(&query.body, &query.order_by);
+        for expr in query.limit.iter().chain(&query.offset) {
+            self.collect(|binder| binder.visit_expr(expr));
+        }
+        self.ctes.truncate(ctes);
+        columns
+    }
+
+    fn bind_cte(&mut self, cte: &Cte, recursive: bool) {
+        let name = normalize(&cte.alias);
+        let declared: Option<Vec<String>> =
+            (!cte.columns.is_empty()).then(|| cte.columns.iter().map(normalize).collect());
+        let relation = |columns| Relation {
+            name: vec![name.clone()],
+            source: Source::Cte(name.clone()),
+            columns,
+        };
+
+        // A recursive CTE can reference itself, with the columns it declares
+        if recursive {
+            let columns = declared.clone().map(|names| rename_columns(None, names));
+            self.ctes.push(relation(columns));
+        }
+        let columns = self.bind_query(&cte.query);
+        if recursive {
+            self.ctes.pop();
+        }
+        let columns = match declared {
+            Some(names) => Some(rename_columns(columns, names)),
+            None => columns,
+        };
+        self.ctes.push(relation(columns));
+    }
+
+    fn bind_set_expr(
+        &mut self,
+        body: &SetExpr,
+        order_by: &[OrderByExpr],
+    ) -> Option<Vec<ColumnLineage>> {
+        let columns = match body {
+            SetExpr::Select(select) => return self.bind_select(select, order_by),
+            SetExpr::Query(query) => self.bind_query(query),
+            SetExpr::SetOperation { left, right, .. } => {
+                let left = self.bind_set_expr(left, &[]);
+                let right = self.bind_set_expr(right, &[]);
+                match (left, right) {
+                    (Some(mut left), Some(right)) => {
+                        for (column, other) in left.iter_mut().zip(right) {
+                            column.data_type =
+                                common_type(column.data_type.take(), other.data_type);
+                            for source in other.sources {
+                                if !column.sources.contains(&source) {
+                                    column.sources.push(source);
+                                }
+                            }
+                        }
+                        Some(left)
+                    }
+                    (left, _) => left,
+                }
+            }
+            SetExpr::Values(rows) => {
+                let width = rows.first().map_or(0, Vec::len);
+                let mut columns: Vec<ColumnLineage> = (1..=width)
+                    .map(|i| ColumnLineage {
+                        name: Some(format!("COLUMN{}", i)),
+                        sources: vec![],
+                        data_type: None,
+                    })
+                    .collect();
+                for row in rows {
+                    for (column, expr) in columns.iter_mut().zip(row) {
+                        column.sources.extend(self.bind_output(expr));
+                        column.data_type = common_type(column.data_type.take(), self.infer(expr));
+                    }
+                }
+                Some(columns)
+            }
+        };
+
+        // The ORDER BY of a compound query sees only its output columns
+        if !order_by.is_empty() {
+            self.scopes.push(Scope {
+                aliases: columns.clone().unwrap_or_default(),
+                ..Scope::default()
+            });
+            for order_by in order_by {
+                self.collect(|binder| binder.visit_order_by_expr(order_by));
+            }
+            self.scopes.pop();
+        }
+        columns
+    }
+
+    fn bind_select(
+        &mut self,
+        select: &Select,
+        order_by: &[OrderByExpr],
+    ) -> Option<Vec<ColumnLineage>> {
+        if let Some(top) = &select.top {
+            self.collect(|binder| binder.visit_expr(top));
+        }
+
+        self.scopes.push(Scope::default());
+        for table in &select.from {
+            self.bind_table_with_joins(table);
+        }
+
+        let mut columns = Some(Vec::new());
+        for (index, item) in select.projection.iter().enumerate() {
+            let outputs = match item {
+                SelectItem::UnnamedExpr(expr) => Some(vec![ColumnLineage {
+                    name: output_name(expr),
+                    sources: self.bind_output(expr),
+                    data_type: self.infer(expr),
+                }]),
+                SelectItem::ExprWithAlias { expr, alias } => {
+                    let output = ColumnLineage {
+                        name: Some(normalize(alias)),
+                        sources: self.bind_output(expr),
+                        data_type: self.infer(expr),
+                    };
+                    self.scope().aliases.push(output.clone());
+                    Some(vec![output])
+                }
+                SelectItem::Wildcard(options) => self.bind_wildcard(select, index, None, options),
+                SelectItem::QualifiedWildcard(name, options) => {
+                    self.bind_wildcard(select, index, Some(name), options)
+                }
+            };
+            columns = columns.zip(outputs).map(|(mut columns, outputs)| {
+                columns.extend(outputs);
+                columns
+            });
+        }
+
+        for expr in &select.into {
+            self.collect(|binder| binder.visit_expr(expr));
+        }
+        for expr in select
+            .selection
+            .iter()
+            .chain(&select.group_by)
+            .chain(&select.having)
+            .chain(&select.qualify)
+        {
+            self.bind_filter(expr);
+        }
+        for order_by in order_by {
+            self.collect(|binder| binder.visit_order_by_expr(order_by));
+        }
+
+        // Every column depends on the columns filtering the rows
+        let filters = self.scopes.pop().unwrap_or_default().filters;
+        columns.map(|columns| {
+            columns
+                .into_iter()
+                .map(|mut column| {
+                    column.sources.extend(filters.iter().cloned());
+                    column
+                })
+                .collect()
+        })
+    }
+
+    /// Binds `UPDATE`, returning the lineage of the assigned columns.
+    pub(crate) fn bind_update(
+        &mut self,
+        table: &TableWithJoins,
+        assignments: &[Assignment],
+        from: &[TableWithJoins],
+        selection: Option<&Expr>,
+    ) -> Vec<ColumnLineage> {
+        self.scopes.push(Scope::default());
+        self.bind_table_with_joins(table);
+        for table in from {
+            self.bind_table_with_joins(table);
+        }
+        if let Some(selection) = selection {
+            self.bind_filter(selection);
+        }
+        let mut columns = Vec::new();
+        for assignment in assignments {
+            let mut sources = self.bind_output(&assignment.value);
+            sources.extend(self.scope().filters.iter().cloned());
+            columns.push(ColumnLineage {
+                name: Some(normalize(&assignment.target)),
+                sources,
+                data_type: self.infer(&assignment.value),
+            });
+        }
+        self.scopes.pop();
+        columns
+    }
+
+    /// Binds `MERGE`, returning the lineage of the columns it updates or
+    /// inserts.
+    pub(crate) fn bind_merge(&mut self, merge: &Merge) -> Vec<ColumnLineage> {
+        self.scopes.push(Scope::default());
+        self.bind_table_factor(&merge.target);
+        self.bind_table_factor(&merge.source);
+        self.bind_filter(&merge.on);
+        for predicate in merge
+            .clauses
+            .iter()
+            .filter_map(|clause| clause.predicate.as_ref())
+        {
+            self.bind_filter(predicate);
+        }
+
+        let mut columns: Vec<ColumnLineage> = Vec::new();
+        for clause in &merge.clauses {
+            let assigned: Vec<(Option<String>, &Expr)> = match &clause.action {
+                MergeAction::Update(assignments) => assignments
+                    .iter()
+                    .map(|assignment| (Some(normalize(&assignment.target)), &assignment.value))
+                    .collect(),
+                MergeAction::Delete => vec![],
+                MergeAction::Insert { columns, values } => {
+                    let mut names = columns.iter().map(|column| Some(normalize(column)));
+                    values
+                        .iter()
+                        .map(|value| (names.next().flatten(), value))
+                        .collect()
+                }
+            };
+            for (name, value) in assigned {
+                let sources = self.bind_output(value);
+                let data_type = self.infer(value);
+                let Some(name) = name else { continue };
+                match columns
+                    .iter_mut()
+                    .find(|column| column.name.as_ref() == Some(&name))
+                {
+                    Some(column) => {
+                        column.data_type = common_type(column.data_type.take(), data_type);
+                        for source in sources {
+                            if !column.sources.contains(&source) {
+                                column.sources.push(source);
+                            }
+                        }
+                    }
+                    None => columns.push(ColumnLineage {
+                        name: Some(name),
+                        sources,
+                        data_type,
+                    }),
+                }
+            }
+        }
+
+        let filters = self.scopes.pop().unwrap_or_default().filters;
+        for column in &mut columns {
+            column.sources.extend(filters.iter().cloned());
+        }
+        columns
+    }
+
+    fn bind_table_with_joins(&mut self, table: &TableWithJoins) {
+        self.bind_table_factor(&table.relation);
+        for join in &table.joins {
+            self.bind_table_factor(&join.relation);
+            match &join.join_operator {
+                JoinOperator::Inner(constraint)
+                | JoinOperator::LeftOuter(constraint)
+                | JoinOperator::RightOuter(constraint)
+                | JoinOperator::FullOuter(constraint) => match constraint {
+                    JoinConstraint::On(expr) => self.bind_filter(expr),
+                    JoinConstraint::Using(columns) => {
+                        let columns = columns.iter().map(normalize);
+                        self.scope().using.extend(columns);
+                    }
+                    JoinConstraint::Natural | JoinConstraint::None => {}
+                },
+                JoinOperator::CrossJoin => {}
+            }
+        }
+    }
+
+    fn bind_table_factor(&mut self, factor: &TableFactor) {
+        let relation = match factor {
+            TableFactor::Table { name, alias } => self.table(name).aliased(alias.as_ref()),
+            TableFactor::Derived {
+                lateral,
+                subquery,
+                alias,
+            } => {
+                // Only lateral subqueries see the relations before them
+                let hidden = (!lateral).then(|| std::mem::take(&mut self.scope().relations));
+                let columns = self.bind_query(subquery);
+                if let Some(relations) = hidden {
+                    self.scope().relations = relations;
+                }
+                Relation {
+                    name: vec![],
+                    source: Source::Derived,
+                    columns,
+                }
+                .aliased(alias.as_ref())
+            }
+            TableFactor::TableFunction { expr, alias, .. } => {
+                // Table functions are implicitly lateral, and every column
+                // they produce derives from their arguments
+                let sources = self.bind_output(expr);
+                let function = match expr {
+                    Expr::Function(function) => Some(function),
+                    _ => None,
+                };
+                let name = function
+                    .map(|function| normalize(function.name.base_name()))
+                    .unwrap_or_default();
+                let columns = function
+                    .and_then(FunctionCall::builtin)
+                    .and_then(|builtin| match builtin.return_type {
+                        ReturnType::Table(columns) if !columns.is_empty() => Some(
+                            columns
+                                .iter()
+                                .map(|column| ColumnLineage {
+                                    name: Some(column.to_string()),
+                                    sources: sources.clone(),
+                                    data_type: None,
+                                })
+                                .collect(),
+                        ),
+                        _ => None,
+                    });
+                Relation {
+                    name: vec![name.clone()],
+                    source: Source::TableFunction(name),
+                    columns,
+                }
+                .aliased(alias.as_ref())
+            }
+            TableFactor::NestedJoin {
+                table_with_joins, ..
+            } => return self.bind_table_with_joins(table_with_joins),
+            TableFactor::Stage { alias, .. } => Relation {
+                name: vec![],
+                source: Source::Stage,
+                columns: Some(rename_columns(
+                    None,
+                    STAGE_METADATA_COLUMNS
+                        .iter()
+                        .map(|column| column.to_string())
+                        .collect(),
+                )),
+            }
+            .aliased(alias.as_ref()),
+        };
+        self.scope().relations.push(relation);
+    }
+
+    /// The relation a table name refers to, which is a CTE when one is in
+    /// scope with that name.
+    fn table(&self, name: &ObjectName) -> Relation {
+        let name: Vec<String> = name.0.iter().map(normalize).collect();
+        if let [base] = name.as_slice() {
+            if let Some(cte) = self.ctes.iter().rev().find(|cte| cte.name[0] == *base) {
+                return cte.clone();
+            }
+        }
+        let (table, columns) = match self.declared_table(&name) {
+            Some((table, columns)) => (table.clone(), Some(columns)),
+            None => (name.clone(), None),
+        };
+        let columns = columns.map(|columns| {
+            columns
+                .iter()
+                .map(|(column, data_type)| ColumnLineage {
+                    name: Some(column.clone()),
+                    sources: vec![],
+                    data_type: data_type.as_ref().map(canonical),
+                })
+                .collect()
+        });
+        Relation {
+            name,
+            source: Source::Table(table),
+            columns,
+        }
+    }
+
+    /// The type of the column `reference` resolves to, when it's known.
+    pub(crate) fn column_type(&self, reference: &[Ident]) -> Option<DataType> {
+        let (_, column) = self.resolve(reference)?;
+        column.data_type
+    }
+
+    /// Resolves a reference along with the source columns it reads and its
+    /// type, or returns `None` for lambda parameters, which aren't column
+    /// references.
+    fn resolve(&self, reference: &[Ident]) -> Option<(Resolution, ColumnLineage)> {
+        let parts: Vec<String> = reference.iter().map(normalize).collect();
+        if self.lambda_params.contains(&parts[0]) {
+            return None;
+        }
+
+        let (column, qualifier) = parts.split_last().expect("references are never empty");
+        let (resolution, sources) = match qualifier.is_empty() {
+            true => self.resolve_column(column),
+            false => self.resolve_qualified(qualifier, column),
+        };
+        if resolution == Resolution::Unresolved && self.is_variable(&parts[0]) {
+            return Some((
+                Resolution::Variable(parts[0].clone()),
+                ColumnLineage::default(),
+            ));
+        }
+        Some((resolution, sources))
+    }
+
+    fn resolve_column(&self, column: &str) -> (Resolution, ColumnLineage) {
+        for (depth, scope) in self.scopes.iter().rev().enumerate() {
+            let candidates: Vec<&Relation> = scope
+                .relations
+                .iter()
+                .filter(|relation| relation.has_column(column) != Some(false))
+                .collect();
+
+            // Columns known to exist take precedence over aliases, which take
+            // precedence over tables that might have the column
+            let known = candidates
+                .iter()
+                .any(|relation| relation.has_column(column) == Some(true));
+            let alias = scope
+                .aliases
+                .iter()
+                .find(|alias| alias.name.as_deref() == Some(column));
+            if let Some(alias) = alias.filter(|_| depth == 0 && !known) {
+                return (Resolution::Alias(column.to_string()), alias.clone());
+            }
+
+            match candidates.as_slice() {
+                [] => {}
+                [relation] => return resolved(relation, column, depth),
+                [relation, ..] if scope.using.iter().any(|using| using == column) => {
+                    return resolved(relation, column, depth)
+                }
+                _ => return (ambiguous(&candidates), ColumnLineage::default()),
+            }
+        }
+        (Resolution::Unresolved, ColumnLineage::default())
+    }
+
+    fn resolve_qualified(&self, qualifier: &[String], column: &str) -> (Resolution, ColumnLineage) {
+        for (depth, scope) in self.scopes.iter().rev().enumerate() {
+            let matches: Vec<&Relation> = scope
+                .relations
+                .iter()
+                .filter(|relation| relation.is_named(qualifier))
+                .collect();
+            match matches.as_slice() {
+                [] => {}
+                [relation] if relation.has_column(column) == Some(false) => {
+                    return (Resolution::Unresolved, ColumnLineage::default())
+                }
+                [relation] => return resolved(relation, column, depth),
+                _ => return (ambiguous(&matches), ColumnLineage::default()),
+            }
+        }
+        (Resolution::Unresolved, ColumnLineage::default())
+    }
+
+    fn is_variable(&self, name: &String) -> bool {
+        self.variables
+            .iter()
+            .flatten()
+            .any(|variable| variable == name)
+    }
+
+    fn declare(&mut self, name: &Ident) {
+        let name = normalize(name);
+        match self.variables.last_mut() {
+            Some(frame) => frame.push(name),
+            None => self.variables.push(vec![name]),
+        }
+    }
+}
+
+impl Visitor for Binder {
+    fn visit_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Update {
+                table,
+                assignments,
+                from,
+                selection,
+            } => {
+                self.bind_update(table, assignments, from, selection.as_ref());
+            }
+            Statement::Delete {
+                table_name,
+                using,
+                selection,
+            } => {
+                self.scopes.push(Scope::default());
+                let relation = self.table(table_name);
+                self.scope().relations.push(relation);
+                for table in using {
+                    self.bind_table_with_joins(table);
+                }
+                if let Some(selection) = selection {
+                    self.bind_filter(selection);
+                }
+                self.scopes.pop();
+            }
+            Statement::Merge(merge) => {
+                self.bind_merge(merge);
+            }
+            _ => visitor::walk_statement(self, statement),
+        }
+    }
+
+    /// Binds a query nested in an expression, whose output feeds the
+    /// expression.
+    fn visit_query(&mut self, query: &Query) {
+        let columns = self.bind_query(query);
+        let data_type = match columns.as_deref() {
+            Some([column]) => column.data_type.clone(),
+            _ => None,
+        };
+        self.subquery_types
+            .push((query as *const Query as usize, data_type));
+        let kind = match self.aggregates {
+            0 => LineageKind::Transformation,
+            _ => LineageKind::Aggregation,
+        };
+        for column in columns.into_iter().flatten() {
+            self.collect_sources(column.sources, kind);
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        let reference = match expr {
+            Expr::Identifier(ident) => std::slice::from_ref(ident),
+            Expr::CompoundIdentifier(parts) => parts.as_slice(),
+            Expr::Lambda { params, body } => {
+                let len = self.lambda_params.len();
+                self.lambda_params.extend(params.iter().map(normalize));
+                self.visit_expr(body);
+                self.lambda_params.truncate(len);
+                return;
+            }
+            _ => {
+                visitor::walk_expr(self, expr);
+                return;
+            }
+        };
+        if let Some((resolution, column)) = self.resolve(reference) {
+            let kind = match self.aggregates {
+                0 => LineageKind::Direct,
+                _ => LineageKind::Aggregation,
+            };
+            self.collect_sources(column.sources, kind);
+            self.bindings.push(Binding {
+                reference: reference.to_vec(),
+                resolution,
+            });
+        }
+    }
+
+    fn visit_function_call(&mut self, function: &FunctionCall) {
+        let builtin = function.builtin();
+        if builtin.is_some_and(|builtin| DATE_PART_FUNCTIONS.contains(&builtin.name)) {
+            for arg in function.args.iter().map(FunctionArg::expr) {
+                if !is_date_part(arg) {
+                    self.visit_expr(arg);
+                }
+            }
+        } else if builtin.is_some_and(|builtin| builtin.kind == FunctionKind::Aggregate) {
+            self.aggregates += 1;
+            visitor::walk_function_call(self, function);
+            self.aggregates -= 1;
+        } else {
+            visitor::walk_function_call(self, function);
+        }
+    }
+
+    fn visit_create_routine(&mut self, routine: &CreateRoutine) {
+        self.variables.push(
+            routine
+                .args
+                .iter()
+                .map(|arg| normalize(&arg.name))
+                .collect(),
+        );
+        visitor::walk_create_routine(self, routine);
+        self.variables.pop();
+    }
+
+    fn visit_block(&mut self, block: &Block) {
+        self.variables.push(Vec::new());
+        visitor::walk_block(self, block);
+        self.variables.pop();
+    }
+
+    fn visit_declaration(&mut self, declaration: &Declaration) {
+        visitor::walk_declaration(self, declaration);
+        match declaration {
+            Declaration::Variable { name, .. }
+            | Declaration::Cursor { name, .. }
+            | Declaration::ResultSet { name, .. }
+            | Declaration::Exception { name, .. } => self.declare(name),
+        }
+    }
+
+    fn visit_script_statement(&mut self, statement: &ScriptStatement) {
+        match statement {
+            ScriptStatement::ForRange { variable, .. }
+            | ScriptStatement::ForCursor { variable, .. } => {
+                self.variables.push(vec![normalize(variable)]);
+                visitor::walk_script_statement(self, statement);
+                self.variables.pop();
+            }
+            _ => visitor::walk_script_statement(self, statement),
+        }
+    }
+}
+
+/// Snowflake resolves unquoted identifiers as uppercase.
+pub(crate) fn normalize(ident: &Ident) -> String {
+    match ident.quote_style {
+        Some(_) => ident.value.clone(),
+        None => ident.value.to_uppercase(),
+    }
+}
+
+/// The name of a select item without an alias, when it's a plain column.
+fn output_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Identifier(ident) => Some(normalize(ident)),
+        Expr::CompoundIdentifier(parts) => parts.last().map(normalize),
+        _ => None,
+    }
+}
+
+fn is_date_part(expr: &Expr) -> bool {
+    match expr {
+        Expr::Identifier(ident) if ident.quote_style.is_none() => {
+            DATE_PARTS.contains(&ident.value.to_uppercase().as_str())
+        }
+        _ => false,
+    }
+}
+
+fn resolved(relation: &Relation, column: &str, depth: usize) -> (Resolution, ColumnLineage) {
+    let output = ColumnLineage {
+        name: Some(column.to_string()),
+        sources: relation.sources(column),
+        data_type: relation.data_type(column),
+    };
+    (relation.column(column, depth), output)
+}
+
+fn ambiguous(relations: &[&Relation]) -> Resolution {
+    Resolution::Ambiguous(
+        relations
+            .iter()
+            .map(|relation| relation.display_name())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{Binder, Binding, ColumnRef, Resolution, Source};
+    use crate::parser::parser::Parser;
+
+    fn bind(binder: &mut Binder, sql: &str) -> Result<Vec<(String, Resolution)>> {
+        let mut bindings = Vec::new();
+        for statement in Parser::from_sql(sql).parse_statements()? {
+            bindings.extend(binder.bind(&statement));
+        }
+        Ok(bindings
+            .into_iter()
+            .map(
+                |Binding {
+                     reference,
+                     resolution,
+                 }| {
+                    let name = reference.iter().map(|part| part.value.as_str());
+                    (name.collect::<Vec<_>>().join("."), resolution)
+                },
+            )
+            .collect())
+    }
+
+    fn column(relation: &str, source: Source, column: &str, depth: usize) -> Resolution {
+        Resolution::Column(ColumnRef {
+            relation: Some(relation.to_string()),
+            source,
+            column: column.to_string(),
+            depth,
+        })
+    }
+
+    #[test]
+    fn resolves_columns_through_ctes() -> Result<()> {
+        let sql = std::fs::read_to_string("scripts/input.sql")?;
+        let mut binder = Binder::new()
+            .with_table("RAW.EMPLOYEES", &["ID", "NAME"])
+            .with_table("RAW.SALARIES", &["ID", "SALARY"]);
+
+        let employees = || Source::Table(vec!["RAW".into(), "EMPLOYEES".into()]);
+        let salaries = || Source::Table(vec!["RAW".into(), "SALARIES".into()]);
+        let e = || Source::Cte("EMPLOYEES".into());
+        let s = || Source::Cte("SALARIES".into());
+        assert_eq!(
+            bind(&mut binder, &sql)?,
+            vec![
+                ("ID".into(), column("EMPLOYEES", employees(), "ID", 0)),
+                ("NAME".into(), column("EMPLOYEES", employees(), "NAME", 0)),
+                ("ID".into(), column("SALARIES", salaries(), "ID", 0)),
+                ("SALARY".into(), column("SALARIES", salaries(), "SALARY", 0)),
+                ("E.ID".into(), column("E", e(), "ID", 0)),
+                ("S.ID".into(), column("S", s(), "ID", 0)),
+                ("E.ID".into(), column("E", e(), "ID", 0)),
+                ("E.NAME".into(), column("E", e(), "NAME", 0)),
+                ("S.SALARY_RANK".into(), column("S", s(), "SALARY_RANK", 0)),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn resolves_correlated_references_aliases_and_flatten() -> Result<()> {
+        let sql = "
+SELECT o.id, f.value AS item, item:sku AS sku
+FROM orders o, LATERAL FLATTEN(input => o.items) f
+WHERE EXISTS (SELECT 1 FROM returns r WHERE r.order_id = o.id AND DATEADD(day, 30, o.ts) > r.ts)
+QUALIFY ROW_NUMBER() OVER (PARTITION BY sku ORDER BY o.id) = 1";
+
+        let orders = || Source::Table(vec!["ORDERS".into()]);
+        let returns = || Source::Table(vec!["RETURNS".into()]);
+        let flatten = || Source::TableFunction("FLATTEN".into());
+        assert_eq!(
+            bind(&mut Binder::new(), sql)?,
+            vec![
+                ("o.items".into(), column("O", orders(), "ITEMS", 0)),
+                ("o.id".into(), column("O", orders(), "ID", 0)),
+                ("f.value".into(), column("F", flatten(), "VALUE", 0)),
+                ("item".into(), Resolution::Alias("ITEM".into())),
+                ("r.order_id".into(), column("R", returns(), "ORDER_ID", 0)),
+                ("o.id".into(), column("O", orders(), "ID", 1)),
+                ("o.ts".into(), column("O", orders(), "TS", 1)),
+                ("r.ts".into(), column("R", returns(), "TS", 0)),
+                ("sku".into(), Resolution::Alias("SKU".into())),
+                ("o.id".into(), column("O", orders(), "ID", 0)),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reports_ambiguous_and_unresolved_names() -> Result<()> {
+        let sql = "
+SELECT id, c.name, x.total, region FROM customers c JOIN accounts a ON a.customer_id = c.id";
+        let mut binder = Binder::new().with_table("ACCOUNTS", &["ID", "CUSTOMER_ID", "BALANCE"]);
+
+        let statement = Parser::from_sql(sql).parse_statement()?;
+        let errors: Vec<String> = binder
+            .bind(&statement)
+            .iter()
+            .filter_map(Binding::error)
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                "Column reference id is ambiguous, it could refer to C or A",
+                "Column x.total could not be resolved",
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,442 @@
+//! The tables and columns a set of SQL files defines.
+//!
+//! A [`Catalog`] is filled from `CREATE TABLE` and `CREATE VIEW` statements,
+//! then declares its tables to a [`Binder`] so that `*` expands to their
+//! columns, both in lineage and as a rewrite of the SQL with
+//! [`Catalog::expand_wildcards`].
+
+use anyhow::Result;
+
+use crate::ast::data_type::DataType;
+use crate::ast::query::Select;
+use crate::ast::statement::{ObjectType, Statement};
+use crate::ast::visitor::Visitor;
+use crate::binder::binder::{normalize, Binder, WildcardColumn};
+use crate::cst::cst::{trimmed_text, Cst, CstElement, CstNode, CstToken, SyntaxKind};
+use crate::lexer::lexer::Token;
+use crate::parser::parser::Parser;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+    pub name: String,
+    /// `None` when the type isn't declared and can't be inferred, e.g. for a
+    /// view's column read from an unknown table.
+    pub data_type: Option<DataType>,
+    pub nullable: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table {
+    /// The qualified name, e.g. `["RAW", "EMPLOYEES"]`.
+    pub name: Vec<String>,
+    pub columns: Vec<Column>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Catalog {
+    tables: Vec<Table>,
+}
+
+impl Catalog {
+    pub fn new() -> Self {
+        Catalog::default()
+    }
+
+    pub fn from_sql(sql: &str) -> Self {
+        let mut catalog = Catalog::new();
+        catalog.add_sql(sql);
+        catalog
+    }
+
+    /// Adds the tables and views `sql` creates, skipping statements with
+    /// syntax errors.
+    pub fn add_sql(&mut self, sql: &str) {
+        let (statements, _) = Parser::from_sql(sql).parse_statements_with_recovery();
+        for statement in &statements {
+            self.add_statement(statement);
+        }
+    }
+
+    /// Records the table or view `statement` creates, replacing any of the
+    /// same name, or forgets the one it drops. The columns of `CREATE TABLE
+    /// ... AS` and views without a column list come from their query, when
+    /// its output columns are all known and named, and those of `CLONE` or
+    /// `LIKE` from the table copied.
+    pub fn add_statement(&mut self, statement: &Statement) {
+        let (name, columns) = match statement {
+            Statement::CreateTable { name, columns, .. } if !columns.is_empty() => {
+                let columns = columns.iter().map(|column| Column {
+                    name: normalize(&column.name),
+                    data_type: Some(column.data_type.clone()),
+                    nullable: !column.not_null,
+                });
+                (name, columns.collect())
+            }
+            Statement::CreateTable {
+                name,
+                copy_of: Some(source),
+                ..
+            } => {
+                let source: Vec<String> = source.0.iter().map(normalize).collect();
+                let Some(source) = self.table(&source) else {
+                    return;
+                };
+                (name, source.columns.clone())
+            }
+            Statement::CreateTable { name, .. } | Statement::CreateView { name, .. } => {
+                let lineage = self.binder().lineage(statement);
+                let Some(columns) = lineage.and_then(|lineage| lineage.columns) else {
+                    return;
+                };
+                let columns = columns.into_iter().map(|column| {
+                    Some(Column {
+                        name: column.name?,
+                        data_type: column.data_type,
+                        nullable: true,
+                    })
+                });
+                let Some(columns) = columns.collect() else {
+                    return;
+                };
+                (name, columns)
+            }
+            Statement::Drop {
+                object_type: ObjectType::Table | ObjectType::View,
+                name,
+                ..
+            } => {
+                let name: Vec<String> = name.0.iter().map(normalize).collect();
+                self.tables.retain(|table| table.name != name);
+                return;
+            }
+            _ => return,
+        };
+
+        self.add_table(Table {
+            name: name.0.iter().map(normalize).collect(),
+            columns,
+        });
+    }
+
+    /// Adds `table`, replacing any of the same name.
+    pub fn add_table(&mut self, table: Table) {
+        self.tables.retain(|existing| existing.name != table.name);
+        self.tables.push(table);
+    }
+
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    /// The one table whose qualified name ends with `name`, e.g. `EMPLOYEES`
+    /// finds `RAW.EMPLOYEES` unless another schema has an `EMPLOYEES`.
+    pub fn table(&self, name: &[String]) -> Option<&Table> {
+        let mut matches = self
+            .tables
+            .iter()
+            .filter(|table| table.name.ends_with(name));
+        match (matches.next(), matches.next()) {
+            (Some(table), None) => Some(table),
+            _ => None,
+        }
+    }
+
+    /// A binder that knows the columns of every table in the catalog, and
+    /// their types.
+    pub fn binder(&self) -> Binder {
+        let mut binder = Binder::new();
+        for table in &self.tables {
+            let columns = table
+                .columns
+                .iter()
+                .map(|column| (column.name.clone(), column.data_type.clone()));
+            binder.add_typed_table(table.name.clone(), columns.collect());
+        }
+        binder
+    }
+
+    /// Rewrites every `*` and `t.*` of `sql` whose columns are known as the
+    /// explicit list of those columns, applying its `EXCLUDE`, `REPLACE` and
+    /// `RENAME`. Everything else, comments and formatting included, is left
+    /// as it is, and so are statements that don't parse.
+    pub fn expand_wildcards(&self, sql: &str) -> Result<String> {
+        let mut cst = Cst::from_sql(sql)?;
+        let statements = cst
+            .root_mut()
+            .child_nodes_mut()
+            .filter(|node| node.kind() == SyntaxKind::Statement);
+        for node in statements {
+            let statement = match Parser::from_sql(&node.to_string()).parse_statements() {
+                Ok(statements) if statements.len() == 1 => statements,
+                _ => continue,
+            };
+
+            let mut selects = SourceOrder::default();
+            selects.visit_statement(&statement[0]);
+            let lists = node.descendants().into_iter();
+            let lists = lists.filter(|node| node.kind() == SyntaxKind::SelectList);
+            // The select lists of the syntax tree pair up with the selects
+            // of the statement only when they're the same in number
+            if lists.count() != selects.0.len() {
+                continue;
+            }
+
+            let mut binder = self.binder();
+            binder.visit_statement(&statement[0]);
+            // Later lists first, as replacing an item flattens any list in it
+            let mut expansions: Vec<_> = std::mem::take(&mut binder.expansions)
+                .into_iter()
+                .filter_map(|expansion| {
+                    let n = selects
+                        .0
+                        .iter()
+                        .position(|select| *select == expansion.select)?;
+                    Some((n, expansion))
+                })
+                .collect();
+            expansions.sort_by_key(|(n, expansion)| std::cmp::Reverse((*n, expansion.index)));
+
+            for (n, expansion) in expansions {
+                let Some(columns) = expansion.columns.filter(|columns| !columns.is_empty()) else {
+                    continue;
+                };
+                let item = node
+                    .nth_descendant_mut(SyntaxKind::SelectList, n)
+                    .and_then(|list| list.child_nodes_mut().nth(expansion.index));
+                let Some(item) = item else {
+                    continue;
+                };
+
+                let replacements = replacements(item);
+                let columns: Vec<_> = columns
+                    .iter()
+                    .map(|column| expanded_column(column, expansion.qualify, &replacements))
+                    .collect();
+                item.replace_with_sql(&columns.join(", "))?;
+            }
+        }
+
+        Ok(cst.to_string())
+    }
+}
+
+/// The addresses of the selects of a statement in source order, the order
+/// of their select lists in the syntax tree.
+#[derive(Default)]
+struct SourceOrder(Vec<usize>);
+
+impl Visitor for SourceOrder {
+    fn visit_select(&mut self, select: &Select) {
+        self.0.push(select as *const Select as usize);
+        if let Some(top) = &select.top {
+            self.visit_expr(top);
+        }
+        for item in &select.projection {
+            self.visit_select_item(item);
+        }
+        for expr in &select.into {
+            self.visit_expr(expr);
+        }
+        for table in &select.from {
+            self.visit_table_with_joins(table);
+        }
+        for expr in select
+            .selection
+            .iter()
+            .chain(&select.group_by)
+            .chain(&select.having)
+            .chain(&select.qualify)
+        {
+            self.visit_expr(expr);
+        }
+    }
+}
+
+/// The source of the `REPLACE` expressions of a wildcard select item.
+fn replacements(item: &CstNode) -> Vec<String> {
+    let group = item.children().windows(2).find_map(|pair| match pair {
+        [CstElement::Token(token), CstElement::Node(group)]
+            if matches!(&token.token, Token::Word(word)
+                if word.quote_style.is_none() && word.value.eq_ignore_ascii_case("replace")) =>
+        {
+            Some(group)
+        }
+        _ => None,
+    });
+    let Some(group) = group else {
+        return vec![];
+    };
+
+    // Each part is `expr AS column`, without the parentheses around them
+    let children = group.children();
+    let mut parts: Vec<Vec<&CstToken>> = vec![vec![]];
+    for child in &children[1..children.len().saturating_sub(1)] {
+        match child {
+            CstElement::Token(token) if token.token == Token::Comma => parts.push(vec![]),
+            CstElement::Token(token) => parts.last_mut().unwrap().push(token),
+            CstElement::Node(node) => parts.last_mut().unwrap().extend(node.tokens()),
+        }
+    }
+    parts
+        .iter()
+        .map(|tokens| trimmed_text(&tokens[..tokens.len().saturating_sub(2)]))
+        .collect()
+}
+
+/// A column of an expanded wildcard, e.g. `e.ID` or `UPPER(name) AS NAME`.
+fn expanded_column(column: &WildcardColumn, qualify: bool, replacements: &[String]) -> String {
+    let value = match column.replacement.and_then(|i| replacements.get(i)) {
+        Some(replacement) => replacement.clone(),
+        None => match (&column.relation, qualify) {
+            (Some(relation), true) => {
+                let mut parts: Vec<_> = relation.split('.').map(identifier).collect();
+                parts.push(identifier(&column.column));
+                parts.join(".")
+            }
+            _ => identifier(&column.column),
+        },
+    };
+    match column.replacement.is_some() || column.name != column.column {
+        true => format!("{} AS {}", value, identifier(&column.name)),
+        false => value,
+    }
+}
+
+/// `name` as an identifier, quoted unless it reads the same unquoted.
+fn identifier(name: &str) -> String {
+    let mut chars = name.chars();
+    let plain = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_uppercase() || c == '_')
+        && chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_' || c == '$');
+    match plain {
+        true => name.to_string(),
+        false => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::Catalog;
+    use crate::parser::parser::Parser;
+
+    const DDL: &str = "
+CREATE TABLE raw.customers (id INT NOT NULL, name VARCHAR, ssn VARCHAR, \"region\" VARCHAR);
+CREATE TABLE raw.orders (id INT, customer_id INT, amount NUMBER(10, 2));
+CREATE VIEW analytics.customer_totals AS
+SELECT c.id, SUM(o.amount) AS total FROM raw.customers c JOIN raw.orders o ON o.customer_id = c.id
+GROUP BY 1";
+
+    #[test]
+    fn builds_tables_from_ddl() -> Result<()> {
+        let catalog = Catalog::from_sql(DDL);
+
+        let names = |table: &[&str]| -> Vec<String> {
+            let name: Vec<String> = table.iter().map(|part| part.to_string()).collect();
+            let table = catalog.table(&name).expect("a table");
+            table
+                .columns
+                .iter()
+                .map(|column| column.name.clone())
+                .collect()
+        };
+        assert_eq!(names(&["CUSTOMERS"]), vec!["ID", "NAME", "SSN", "region"]);
+        assert_eq!(
+            names(&["ANALYTICS", "CUSTOMER_TOTALS"]),
+            vec!["ID", "TOTAL"]
+        );
+        let id = &catalog
+            .table(&["RAW".into(), "CUSTOMERS".into()])
+            .unwrap()
+            .columns[0];
+        assert!(!id.nullable);
+
+        // CLONE and LIKE copy the columns of the table copied
+        let mut copies = Catalog::from_sql(DDL);
+        copies.add_sql("CREATE TABLE c CLONE raw.orders; CREATE TABLE l LIKE c;");
+        let columns = copies.table(&["L".into()]).unwrap().columns.len();
+        assert_eq!(columns, 3);
+
+        let sql = "SELECT * EXCLUDE ssn REPLACE (UPPER(name) AS name) RENAME id AS customer_id \
+                   FROM raw.customers";
+        let statement = Parser::from_sql(sql).parse_statement()?;
+        let lineage = catalog.binder().lineage(&statement).unwrap();
+        let columns: Vec<_> = lineage
+            .columns
+            .unwrap()
+            .into_iter()
+            .map(|column| {
+                let sources = column
+                    .sources
+                    .iter()
+                    .map(|source| format!("{:?}", source.kind));
+                (column.name.unwrap(), sources.collect::<Vec<_>>())
+            })
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                ("CUSTOMER_ID".to_string(), vec!["Direct".to_string()]),
+                ("NAME".to_string(), vec!["Transformation".to_string()]),
+                ("region".to_string(), vec!["Direct".to_string()]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rewrites_wildcards_as_column_lists() -> Result<()> {
+        let catalog = Catalog::from_sql(DDL);
+        let sql = "\
+-- Customers with their totals
+SELECT c.* EXCLUDE (ssn, \"region\") RENAME name AS customer_name, t.*
+FROM raw.customers c JOIN analytics.customer_totals t ON t.id = c.id;
+SELECT * REPLACE (ROUND(amount, 0) AS amount), (SELECT COUNT(*) FROM raw.orders) AS n
+FROM (SELECT * FROM raw.orders) -- every order
+;
+SELECT * FROM unknown";
+
+        assert_eq!(
+            catalog.expand_wildcards(sql)?,
+            "\
+-- Customers with their totals
+SELECT C.ID, C.NAME AS CUSTOMER_NAME, T.ID, T.TOTAL
+FROM raw.customers c JOIN analytics.customer_totals t ON t.id = c.id;
+SELECT ID, CUSTOMER_ID, ROUND(amount, 0) AS AMOUNT, (SELECT COUNT(*) FROM raw.orders) AS n
+FROM (SELECT ID, CUSTOMER_ID, AMOUNT FROM raw.orders) -- every order
+;
+SELECT * FROM unknown"
+        );
+
+        // Comments right before a token or `;` don't hide it
+        let sql = "SELECT * /* all */FROM raw.orders /* x */;\nSELECT * FROM raw.orders;";
+        assert_eq!(
+            catalog.expand_wildcards(sql)?,
+            "SELECT ID, CUSTOMER_ID, AMOUNT /* all */FROM raw.orders /* x */;\n\
+             SELECT ID, CUSTOMER_ID, AMOUNT FROM raw.orders;"
+        );
+
+        // Quoted names may have dots in them
+        let sql = "CREATE TABLE raw.\"my.t\" (a INT, b INT);\nSELECT * FROM raw.\"my.t\";";
+        let mut catalog_with_dots = Catalog::from_sql(DDL);
+        catalog_with_dots.add_sql(sql);
+        assert_eq!(
+            catalog_with_dots.expand_wildcards(sql)?,
+            "CREATE TABLE raw.\"my.t\" (a INT, b INT);\nSELECT A, B FROM raw.\"my.t\";"
+        );
+
+        // Wildcards inside a scripting block are expanded too
+        let sql =
+            "BEGIN\n  INSERT INTO u SELECT * FROM raw.orders;\n  SELECT * FROM raw.orders;\nEND;";
+        assert_eq!(
+            catalog.expand_wildcards(sql)?,
+            "BEGIN\n  INSERT INTO u SELECT ID, CUSTOMER_ID, AMOUNT FROM raw.orders;\n  \
+             SELECT ID, CUSTOMER_ID, AMOUNT FROM raw.orders;\nEND;"
+        );
+
+        Ok(())
+    }
+}
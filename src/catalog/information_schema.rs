@@ -0,0 +1,309 @@
+//! Loads a [`Catalog`] from an export of `INFORMATION_SCHEMA.COLUMNS`, so
+//! production schemas can be used offline.
+//!
+//! Exports are read as CSV with a header row, or as JSON: an array of
+//! objects, or one object per line as `COPY INTO` a stage writes them. Only
+//! the columns below are used, matched by name in any case, and the others
+//! are ignored.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::ast::data_type::DataType;
+use crate::catalog::catalog::{Catalog, Column, Table};
+use crate::json::json::Json;
+use crate::lexer::lexer::Token;
+use crate::parser::parser::Parser;
+
+const TABLE_CATALOG: &str = "TABLE_CATALOG";
+const TABLE_SCHEMA: &str = "TABLE_SCHEMA";
+const TABLE_NAME: &str = "TABLE_NAME";
+const COLUMN_NAME: &str = "COLUMN_NAME";
+const ORDINAL_POSITION: &str = "ORDINAL_POSITION";
+const DATA_TYPE: &str = "DATA_TYPE";
+const IS_NULLABLE: &str = "IS_NULLABLE";
+const CHARACTER_MAXIMUM_LENGTH: &str = "CHARACTER_MAXIMUM_LENGTH";
+const NUMERIC_PRECISION: &str = "NUMERIC_PRECISION";
+const NUMERIC_SCALE: &str = "NUMERIC_SCALE";
+const DATETIME_PRECISION: &str = "DATETIME_PRECISION";
+
+/// One row of the export, by upper-cased column name. Missing values and
+/// SQL `NULL`s are absent.
+type Record = BTreeMap<String, String>;
+
+impl Catalog {
+    /// Adds the tables of an `INFORMATION_SCHEMA.COLUMNS` export in CSV,
+    /// replacing any of the same name.
+    pub fn add_columns_csv(&mut self, csv: &str) -> Result<()> {
+        let mut rows = parse_csv(csv)?.into_iter();
+        let Some(header) = rows.next() else {
+            return Ok(());
+        };
+        let header: Vec<String> = header.iter().map(|name| name.to_uppercase()).collect();
+
+        let records = rows
+            .filter(|row| row.iter().any(|value| !value.is_empty()))
+            .map(|row| {
+                let values = header.iter().cloned().zip(row);
+                values.filter(|(_, value)| !value.is_empty()).collect()
+            });
+        self.add_records(records.collect())
+    }
+
+    /// Adds the tables of an `INFORMATION_SCHEMA.COLUMNS` export in JSON,
+    /// replacing any of the same name.
+    pub fn add_columns_json(&mut self, json: &str) -> Result<()> {
+        let documents = match json.trim_start().starts_with('[') {
+            true => match Json::parse(json)? {
+                Json::Array(rows) => rows,
+                _ => unreachable!("the document starts with ["),
+            },
+            false => json
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(i, line)| Json::parse(line).with_context(|| format!("Line {}", i + 1)))
+                .collect::<Result<_>>()?,
+        };
+
+        let mut records = Vec::new();
+        for (i, document) in documents.into_iter().enumerate() {
+            let Json::Object(entries) = document else {
+                bail!("Row {} isn't an object", i + 1);
+            };
+            let values = entries.into_iter().filter_map(|(key, value)| {
+                let value = match value {
+                    Json::String(value) => value,
+                    Json::Number(value) => value.to_string(),
+                    Json::Bool(value) => value.to_string(),
+                    _ => return None,
+                };
+                Some((key.to_uppercase(), value))
+            });
+            records.push(values.collect());
+        }
+        self.add_records(records)
+    }
+
+    fn add_records(&mut self, records: Vec<Record>) -> Result<()> {
+        let mut tables: BTreeMap<Vec<String>, Vec<(u64, Column)>> = BTreeMap::new();
+        for (i, record) in records.iter().enumerate() {
+            let (name, column) = read_record(record).with_context(|| format!("Row {}", i + 1))?;
+            tables.entry(name).or_default().push(column);
+        }
+
+        for (name, mut columns) in tables {
+            columns.sort_by_key(|(ordinal, _)| *ordinal);
+            self.add_table(Table {
+                name,
+                columns: columns.into_iter().map(|(_, column)| column).collect(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The qualified table name and the column a record describes, along with
+/// the column's position in the table.
+fn read_record(record: &Record) -> Result<(Vec<String>, (u64, Column))> {
+    let required = |key: &str| record.get(key).ok_or_else(|| anyhow!("Missing {}", key));
+    let number = |key: &str| -> Result<Option<u64>> {
+        record
+            .get(key)
+            .map(|value| {
+                value
+                    .parse::<f64>()
+                    .map(|value| value as u64)
+                    .with_context(|| format!("Invalid {} {:?}", key, value))
+            })
+            .transpose()
+    };
+
+    let name = [TABLE_CATALOG, TABLE_SCHEMA]
+        .into_iter()
+        .filter_map(|key| record.get(key).cloned())
+        .chain([required(TABLE_NAME)?.clone()])
+        .collect();
+    let ordinal = number(ORDINAL_POSITION)?.unwrap_or(u64::MAX);
+    let nullable =
+        !matches!(record.get(IS_NULLABLE), Some(value) if value.eq_ignore_ascii_case("NO"));
+
+    let data_type = record.get(DATA_TYPE).and_then(|name| {
+        let parameters = match (
+            number(NUMERIC_PRECISION).ok().flatten(),
+            number(NUMERIC_SCALE).ok().flatten(),
+            number(CHARACTER_MAXIMUM_LENGTH).ok().flatten(),
+            number(DATETIME_PRECISION).ok().flatten(),
+        ) {
+            (Some(precision), Some(scale), _, _) => format!("({}, {})", precision, scale),
+            (_, _, Some(length), _) => format!("({})", length),
+            (_, _, _, Some(precision)) => format!("({})", precision),
+            _ => String::new(),
+        };
+        // Types that take no parameters, such as FLOAT, still report a
+        // precision
+        parse_data_type(&format!("{}{}", name, parameters)).or_else(|| parse_data_type(name))
+    });
+
+    let column = Column {
+        name: required(COLUMN_NAME)?.clone(),
+        data_type,
+        nullable,
+    };
+    Ok((name, (ordinal, column)))
+}
+
+fn parse_data_type(sql: &str) -> Option<DataType> {
+    let mut parser = Parser::from_sql(sql);
+    let data_type = parser.parse_data_type().ok()?;
+    (parser.peek_token() == &Token::EOF).then_some(data_type)
+}
+
+/// The rows of `csv`, with fields optionally quoted by `"` and quotes within
+/// them doubled.
+fn parse_csv(csv: &str) -> Result<Vec<Vec<String>>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut chars = csv.chars().peekable();
+    let mut line = 1;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if field.is_empty() => {
+                let start = line;
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            line += usize::from(c == '\n');
+                            field.push(c);
+                        }
+                        None => bail!("Unterminated quoted field starting on line {}", start),
+                    }
+                }
+            }
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                line += 1;
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::ast::data_type::{DataType, TimestampTimeZone};
+    use crate::catalog::catalog::Catalog;
+
+    /// Each column of `table` as `NAME TYPE NULL?`.
+    fn describe(catalog: &Catalog, table: &[&str]) -> Vec<String> {
+        let name: Vec<String> = table.iter().map(|part| part.to_string()).collect();
+        let table = catalog.table(&name).expect("a table");
+        table
+            .columns
+            .iter()
+            .map(|column| format!("{} {:?} {}", column.name, column.data_type, column.nullable))
+            .collect()
+    }
+
+    #[test]
+    fn loads_csv_exports() -> Result<()> {
+        let csv = "\
+table_catalog,table_schema,table_name,column_name,ordinal_position,is_nullable,data_type,\
+character_maximum_length,numeric_precision,numeric_scale,datetime_precision,comment\r
+PROD,RAW,ORDERS,AMOUNT,3,YES,NUMBER,,12,2,,\"Total, in \"\"USD\"\"\"\r
+PROD,RAW,ORDERS,ID,1,NO,NUMBER,,38,0,,\r
+PROD,RAW,ORDERS,PLACED_AT,2,YES,TIMESTAMP_NTZ,,,,9,\"multi
+line\"\r
+PROD,RAW,ORDERS,RATE,4,YES,FLOAT,,53,,,\r
+";
+        let mut catalog = Catalog::new();
+        catalog.add_columns_csv(csv)?;
+
+        let number = |precision, scale| {
+            Some(DataType::Number {
+                precision: Some(precision),
+                scale: Some(scale),
+            })
+        };
+        let timestamp = Some(DataType::Timestamp {
+            time_zone: Some(TimestampTimeZone::None),
+            precision: Some(9),
+        });
+        assert_eq!(
+            describe(&catalog, &["ORDERS"]),
+            vec![
+                format!("ID {:?} false", number(38, 0)),
+                format!("PLACED_AT {:?} true", timestamp),
+                format!("AMOUNT {:?} true", number(12, 2)),
+                format!("RATE {:?} true", Some(DataType::Float)),
+            ]
+        );
+        assert_eq!(catalog.tables()[0].name, vec!["PROD", "RAW", "ORDERS"]);
+
+        assert!(Catalog::new().add_columns_csv("table_name\nT\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn loads_json_exports() -> Result<()> {
+        let array = r#"[
+  {"TABLE_SCHEMA": "RAW", "TABLE_NAME": "users", "COLUMN_NAME": "Email",
+   "ORDINAL_POSITION": 2, "DATA_TYPE": "TEXT", "CHARACTER_MAXIMUM_LENGTH": 256,
+   "IS_NULLABLE": "YES"},
+  {"TABLE_SCHEMA": "RAW", "TABLE_NAME": "users", "COLUMN_NAME": "ID",
+   "ORDINAL_POSITION": 1, "DATA_TYPE": "NUMBER", "NUMERIC_PRECISION": 38,
+   "NUMERIC_SCALE": 0, "IS_NULLABLE": "NO", "COMMENT": null}
+]"#;
+        let lines = r#"{"table_schema":"RAW","table_name":"EVENTS","column_name":"PAYLOAD","ordinal_position":1,"data_type":"VARIANT","is_nullable":"YES"}
+
+{"table_schema":"RAW","table_name":"EVENTS","column_name":"OK","ordinal_position":2,"data_type":"BOOLEAN","is_nullable":"NO"}"#;
+
+        let mut catalog = Catalog::new();
+        catalog.add_columns_json(array)?;
+        catalog.add_columns_json(lines)?;
+
+        let id = DataType::Number {
+            precision: Some(38),
+            scale: Some(0),
+        };
+        assert_eq!(
+            describe(&catalog, &["RAW", "users"]),
+            vec![
+                format!("ID {:?} false", Some(id)),
+                format!("Email {:?} true", Some(DataType::Varchar(Some(256)))),
+            ]
+        );
+        assert_eq!(
+            describe(&catalog, &["EVENTS"]),
+            vec![
+                format!("PAYLOAD {:?} true", Some(DataType::Variant)),
+                format!("OK {:?} false", Some(DataType::Boolean)),
+            ]
+        );
+
+        // Loaded tables expand like those from DDL
+        let expanded = catalog.expand_wildcards("SELECT * FROM raw.\"users\"")?;
+        assert_eq!(expanded, "SELECT ID, \"Email\" FROM raw.\"users\"");
+
+        assert!(Catalog::new().add_columns_json("[1]").is_err());
+        Ok(())
+    }
+}
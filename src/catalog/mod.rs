@@ -0,0 +1,3 @@
+#[allow(clippy::module_inception)]
+pub mod catalog;
+pub mod information_schema;
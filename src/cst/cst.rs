@@ -0,0 +1,696 @@
+use std::fmt;
+
+use anyhow::{bail, Result};
+
+use crate::lexer::lexer::{DMLKeyword, Keyword, Lexer, SpannedToken, Token};
+use crate::splitter::splitter::split_statements;
+
+/// Unreserved words that end a select list.
+const SELECT_LIST_TERMINATORS: [&str; 3] = ["fetch", "minus", "offset"];
+
+/// A lossless concrete syntax tree. Every byte of the source, whitespace and
+/// comments included, belongs to exactly one token, so printing the tree gives
+/// back the source unchanged, and edits leave everything else in place.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cst {
+    root: CstNode,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyntaxKind {
+    Root,
+    /// A statement along with its terminating `;`, if any.
+    Statement,
+    /// Tokens between matching `()`, `[]` or `{}`, including the delimiters.
+    Group,
+    /// The comma-separated items following `SELECT`.
+    SelectList,
+    SelectItem,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CstNode {
+    kind: SyntaxKind,
+    children: Vec<CstElement>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CstElement {
+    Node(CstNode),
+    Token(CstToken),
+}
+
+/// A token and the trivia around it. A token owns the trivia up to the end of
+/// its line, and the next token owns the rest, so a line can be removed along
+/// with its comment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CstToken {
+    pub token: Token<'static>,
+    /// The token exactly as written in the source.
+    pub text: String,
+    pub leading: Vec<Trivia>,
+    pub trailing: Vec<Trivia>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub text: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriviaKind {
+    Whitespace,
+    InlineComment,
+    BlockComment,
+}
+
+impl Cst {
+    pub fn from_sql(sql: &str) -> Result<Self> {
+        let tokens = Lexer::new(sql).get_spanned_tokens()?;
+        // Semicolons inside scripting blocks don't end a statement, which the
+        // splitter knows
+        let statements = split_statements(sql);
+        let statement_ends = tokens
+            .iter()
+            .filter(|token| {
+                !matches!(
+                    token.token,
+                    Token::InlineComment(_) | Token::BlockComment(_)
+                )
+            })
+            .enumerate()
+            .filter(|(_, token)| {
+                token.token == Token::Semicolon
+                    && !statements.iter().any(|statement| {
+                        (statement.span.start..statement.span.end).contains(&token.span.start)
+                    })
+            })
+            .map(|(i, _)| i)
+            .collect();
+        let mut builder = Builder {
+            tokens: attach_trivia(sql, tokens),
+            statement_ends,
+            index: 0,
+            depth: 0,
+        };
+
+        Ok(Cst {
+            root: builder.build_root(),
+        })
+    }
+
+    pub fn root(&self) -> &CstNode {
+        &self.root
+    }
+
+    pub fn root_mut(&mut self) -> &mut CstNode {
+        &mut self.root
+    }
+
+    pub fn statements(&self) -> impl Iterator<Item = &CstNode> {
+        self.root
+            .child_nodes()
+            .filter(|node| node.kind == SyntaxKind::Statement)
+    }
+}
+
+impl fmt::Display for Cst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.root.fmt(f)
+    }
+}
+
+impl CstNode {
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    pub fn children(&self) -> &[CstElement] {
+        &self.children
+    }
+
+    pub fn child_nodes(&self) -> impl Iterator<Item = &CstNode> {
+        self.children.iter().filter_map(|child| match child {
+            CstElement::Node(node) => Some(node),
+            CstElement::Token(_) => None,
+        })
+    }
+
+    pub fn child_nodes_mut(&mut self) -> impl Iterator<Item = &mut CstNode> {
+        self.children.iter_mut().filter_map(|child| match child {
+            CstElement::Node(node) => Some(node),
+            CstElement::Token(_) => None,
+        })
+    }
+
+    /// Every node below this one, in source order.
+    pub fn descendants(&self) -> Vec<&CstNode> {
+        let mut nodes = Vec::new();
+        for node in self.child_nodes() {
+            nodes.push(node);
+            nodes.extend(node.descendants());
+        }
+
+        nodes
+    }
+
+    /// The `n`th node of the given kind below this one, in source order.
+    pub fn nth_descendant_mut(&mut self, kind: SyntaxKind, n: usize) -> Option<&mut CstNode> {
+        let mut remaining = n;
+        self.find_descendant_mut(kind, &mut remaining)
+    }
+
+    fn find_descendant_mut(
+        &mut self,
+        kind: SyntaxKind,
+        remaining: &mut usize,
+    ) -> Option<&mut CstNode> {
+        for node in self.child_nodes_mut() {
+            if node.kind == kind {
+                if *remaining == 0 {
+                    return Some(node);
+                }
+                *remaining -= 1;
+            }
+            if let Some(found) = node.find_descendant_mut(kind, remaining) {
+                return Some(found);
+            }
+        }
+
+        None
+    }
+
+    /// Every token of the node, in source order.
+    pub fn tokens(&self) -> Vec<&CstToken> {
+        let mut tokens = Vec::new();
+        for child in &self.children {
+            match child {
+                CstElement::Node(node) => tokens.extend(node.tokens()),
+                CstElement::Token(token) => tokens.push(token),
+            }
+        }
+
+        tokens
+    }
+
+    /// The node's source without the trivia before its first token and after
+    /// its last, e.g. `e.name AS employee_name` for a select item.
+    pub fn trimmed_text(&self) -> String {
+        trimmed_text(&self.tokens())
+    }
+
+    /// Replaces the node's tokens with those of `sql`, keeping the trivia
+    /// before the first token and after the last.
+    pub fn replace_with_sql(&mut self, sql: &str) -> Result<()> {
+        let mut tokens = attach_trivia(sql, Lexer::new(sql).get_spanned_tokens()?);
+        tokens.retain(|token| token.token != Token::EOF);
+        let old = self.tokens();
+        let (Some(first), Some(last)) = (old.first(), old.last()) else {
+            bail!("Can't replace the empty {:?}", self.kind);
+        };
+        let (leading, trailing) = (first.leading.clone(), last.trailing.clone());
+        let Some(first) = tokens.first_mut() else {
+            bail!("No tokens in {:?}", sql);
+        };
+        first.leading = leading;
+        if let Some(last) = tokens.last_mut() {
+            last.trailing = trailing;
+        }
+
+        self.children = tokens.into_iter().map(CstElement::Token).collect();
+        Ok(())
+    }
+
+    /// The child nodes of a comma-separated list, such as the items of a
+    /// [`SyntaxKind::SelectList`].
+    pub fn items(&self) -> impl Iterator<Item = &CstNode> {
+        self.child_nodes()
+    }
+
+    /// Removes the `index`th item of a comma-separated list along with one of
+    /// the commas around it. The trivia ending the line of the last item is
+    /// kept, so dropping it doesn't join the list with what follows.
+    pub fn remove_item(&mut self, index: usize) -> Result<CstNode> {
+        let Some(position) = self
+            .children
+            .iter()
+            .enumerate()
+            .filter(|(_, child)| matches!(child, CstElement::Node(_)))
+            .nth(index)
+            .map(|(position, _)| position)
+        else {
+            bail!("No item {} in {:?}", index, self.kind);
+        };
+
+        let removed = if is_comma(self.children.get(position + 1)) {
+            self.children.drain(position..=position + 1).next()
+        } else if position > 0 && is_comma(self.children.get(position - 1)) {
+            let mut removed = self.children.drain(position - 1..=position).nth(1);
+            if let (Some(CstElement::Node(item)), Some(previous)) =
+                (&mut removed, self.children.get_mut(position - 2))
+            {
+                if let (Some(last), Some(previous)) =
+                    (item.last_token_mut(), previous.last_token_mut())
+                {
+                    previous.trailing = std::mem::take(&mut last.trailing);
+                }
+            }
+            removed
+        } else {
+            bail!("Can't remove the only item of {:?}", self.kind);
+        };
+
+        match removed {
+            Some(CstElement::Node(node)) => Ok(node),
+            _ => unreachable!("the position is a node"),
+        }
+    }
+
+    fn last_token_mut(&mut self) -> Option<&mut CstToken> {
+        self.children
+            .iter_mut()
+            .rev()
+            .find_map(CstElement::last_token_mut)
+    }
+}
+
+impl fmt::Display for CstNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for child in &self.children {
+            match child {
+                CstElement::Node(node) => node.fmt(f)?,
+                CstElement::Token(token) => token.fmt(f)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl CstElement {
+    fn last_token_mut(&mut self) -> Option<&mut CstToken> {
+        match self {
+            CstElement::Node(node) => node.last_token_mut(),
+            CstElement::Token(token) => Some(token),
+        }
+    }
+}
+
+impl fmt::Display for CstToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut text = String::new();
+        push_trivia(&mut text, &self.leading);
+        text.push_str(&self.text);
+        push_trivia(&mut text, &self.trailing);
+        f.write_str(&text)
+    }
+}
+
+/// The source of consecutive `tokens` without the trivia before the first
+/// and after the last.
+pub fn trimmed_text(tokens: &[&CstToken]) -> String {
+    let mut text = String::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if i > 0 {
+            push_trivia(&mut text, &token.leading);
+        }
+        text.push_str(&token.text);
+        if i + 1 < tokens.len() {
+            push_trivia(&mut text, &token.trailing);
+        }
+    }
+
+    text
+}
+
+fn push_trivia(text: &mut String, trivia: &[Trivia]) {
+    for trivia in trivia {
+        text.push_str(&trivia.text);
+    }
+}
+
+fn is_comma(element: Option<&CstElement>) -> bool {
+    matches!(element, Some(CstElement::Token(token)) if token.token == Token::Comma)
+}
+
+/// Turns comments and the whitespace between spans into trivia, split between
+/// the tokens around them at the first line break.
+fn attach_trivia(sql: &str, tokens: Vec<SpannedToken<'_>>) -> Vec<CstToken> {
+    let mut cst_tokens: Vec<CstToken> = Vec::new();
+    let mut pending = Vec::new();
+    let mut previous_end = 0;
+    for SpannedToken { token, span } in tokens {
+        if span.start > previous_end {
+            pending.push(Trivia {
+                kind: TriviaKind::Whitespace,
+                text: sql[previous_end..span.start].to_string(),
+            });
+        }
+        previous_end = span.end;
+
+        let text = sql[span.start..span.end].to_string();
+        let kind = match token {
+            Token::InlineComment(_) => TriviaKind::InlineComment,
+            Token::BlockComment(_) => TriviaKind::BlockComment,
+            token => {
+                let mut leading = std::mem::take(&mut pending);
+                if let Some(previous) = cst_tokens.last_mut() {
+                    previous.trailing = take_line(&mut leading);
+                }
+                cst_tokens.push(CstToken {
+                    token: token.into_owned(),
+                    text,
+                    leading,
+                    trailing: vec![],
+                });
+                continue;
+            }
+        };
+        pending.push(Trivia { kind, text });
+    }
+
+    cst_tokens
+}
+
+/// Takes the trivia up to and including the first line break.
+fn take_line(trivia: &mut Vec<Trivia>) -> Vec<Trivia> {
+    let mut line = Vec::new();
+    while !trivia.is_empty() {
+        let piece = trivia.remove(0);
+        match piece.text.find('\n') {
+            Some(newline) if piece.kind == TriviaKind::Whitespace => {
+                let (end_of_line, rest) = piece.text.split_at(newline + 1);
+                if !rest.is_empty() {
+                    trivia.insert(
+                        0,
+                        Trivia {
+                            kind: TriviaKind::Whitespace,
+                            text: rest.to_string(),
+                        },
+                    );
+                }
+                line.push(Trivia {
+                    kind: TriviaKind::Whitespace,
+                    text: end_of_line.to_string(),
+                });
+                break;
+            }
+            // A block comment spanning lines starts the next token's trivia
+            Some(_) => {
+                trivia.insert(0, piece);
+                break;
+            }
+            None => line.push(piece),
+        }
+    }
+
+    line
+}
+
+struct Builder {
+    tokens: Vec<CstToken>,
+    /// The indexes of the `;` tokens that end a statement.
+    statement_ends: Vec<usize>,
+    index: usize,
+    depth: usize,
+}
+
+impl Builder {
+    fn peek(&self) -> &Token<'static> {
+        &self.tokens[self.index].token
+    }
+
+    fn bump(&mut self) -> CstElement {
+        let token = self.tokens[self.index].clone();
+        if self.index + 1 < self.tokens.len() {
+            self.index += 1;
+        }
+        CstElement::Token(token)
+    }
+
+    fn build_root(&mut self) -> CstNode {
+        let mut children = Vec::new();
+        while self.peek() != &Token::EOF {
+            let mut statement = Vec::new();
+            loop {
+                statement.extend(self.build_sequence(&|token| token == &Token::Semicolon));
+                if self.peek() != &Token::Semicolon {
+                    break;
+                }
+                let end = self.statement_ends.contains(&self.index);
+                statement.push(self.bump());
+                if end {
+                    break;
+                }
+            }
+            children.push(CstElement::Node(CstNode {
+                kind: SyntaxKind::Statement,
+                children: statement,
+            }));
+        }
+        // Trailing trivia of the source belongs to the end of input
+        children.push(self.bump());
+
+        CstNode {
+            kind: SyntaxKind::Root,
+            children,
+        }
+    }
+
+    /// Builds elements up to, but not including, a token matching `stop`, a
+    /// closing delimiter of an enclosing group or the end of input.
+    fn build_sequence(&mut self, stop: &dyn Fn(&Token) -> bool) -> Vec<CstElement> {
+        let mut children = Vec::new();
+        loop {
+            let token = self.peek();
+            if token == &Token::EOF || stop(token) || self.depth > 0 && is_closer(token) {
+                return children;
+            }
+
+            if is_opener(token) {
+                children.push(CstElement::Node(self.build_group()));
+            } else if matches!(token, Token::Word(word) if word.keyword == Some(Keyword::DML(DMLKeyword::Select)))
+            {
+                children.push(self.bump());
+                self.build_select_list(&mut children, stop);
+            } else {
+                children.push(self.bump());
+            }
+        }
+    }
+
+    fn build_group(&mut self) -> CstNode {
+        let mut children = vec![self.bump()];
+        self.depth += 1;
+        children.extend(self.build_sequence(&|_| false));
+        self.depth -= 1;
+        if is_closer(self.peek()) {
+            children.push(self.bump());
+        }
+
+        CstNode {
+            kind: SyntaxKind::Group,
+            children,
+        }
+    }
+
+    fn build_select_list(&mut self, children: &mut Vec<CstElement>, stop: &dyn Fn(&Token) -> bool) {
+        let keyword = |builder: &Builder| match builder.peek() {
+            Token::Word(word) => word.keyword,
+            _ => None,
+        };
+        while matches!(keyword(self), Some(Keyword::Distinct | Keyword::All)) {
+            children.push(self.bump());
+        }
+        if keyword(self) == Some(Keyword::Top)
+            && matches!(
+                self.tokens.get(self.index + 1),
+                Some(CstToken {
+                    token: Token::Int(_),
+                    ..
+                })
+            )
+        {
+            children.push(self.bump());
+            children.push(self.bump());
+        }
+
+        let mut list = Vec::new();
+        loop {
+            let item = self.build_sequence(&|token| {
+                token == &Token::Comma || ends_select_list(token) || stop(token)
+            });
+            if !item.is_empty() {
+                list.push(CstElement::Node(CstNode {
+                    kind: SyntaxKind::SelectItem,
+                    children: item,
+                }));
+            }
+            if self.peek() != &Token::Comma {
+                break;
+            }
+            list.push(self.bump());
+        }
+
+        children.push(CstElement::Node(CstNode {
+            kind: SyntaxKind::SelectList,
+            children: list,
+        }));
+    }
+}
+
+fn is_opener(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::OpenParen | Token::OpenBracket | Token::OpenBrace
+    )
+}
+
+fn is_closer(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::CloseParen | Token::CloseBracket | Token::CloseBrace
+    )
+}
+
+fn ends_select_list(token: &Token) -> bool {
+    let Token::Word(word) = token else {
+        return token == &Token::Semicolon;
+    };
+    if word.quote_style.is_some() {
+        return false;
+    }
+
+    matches!(
+        word.keyword,
+        Some(
+            Keyword::From
+                | Keyword::Into
+                | Keyword::Where
+                | Keyword::Group
+                | Keyword::Having
+                | Keyword::Qualify
+                | Keyword::Order
+                | Keyword::Limit
+                | Keyword::Union
+                | Keyword::Except
+                | Keyword::Intersect
+        )
+    ) || SELECT_LIST_TERMINATORS
+        .iter()
+        .any(|terminator| word.value.eq_ignore_ascii_case(terminator))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{Cst, SyntaxKind};
+
+    #[test]
+    fn round_trips_source_exactly() -> Result<()> {
+        let sql = "  -- Daily orders\n\
+                   WITH recent AS (\n\
+                   \tSELECT id, /* inline */ amount FROM orders   \n\
+                   )\n\
+                   select  distinct r.id ,\n  {'a': [1, 2]}  AS obj -- trailing\n\
+                   FROM recent r;\n\
+                   \n\
+                   CREATE PROCEDURE p() RETURNS STRING LANGUAGE SQL AS $$ SELECT 1; $$ ;\n\
+                   /* the end */\n";
+
+        let cst = Cst::from_sql(sql)?;
+
+        assert_eq!(cst.to_string(), sql);
+        assert_eq!(cst.statements().count(), 2);
+
+        let select_lists: Vec<Vec<String>> = cst
+            .root()
+            .descendants()
+            .into_iter()
+            .filter(|node| node.kind() == SyntaxKind::SelectList)
+            .map(|list| list.items().map(|item| item.trimmed_text()).collect())
+            .collect();
+        assert_eq!(
+            select_lists,
+            vec![vec!["id", "amount"], vec!["r.id", "{'a': [1, 2]}  AS obj"],]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_tokens_right_after_comments() -> Result<()> {
+        let sql = "SELECT * /* all */FROM t /* x */;\nSELECT 2/**/;";
+        let cst = Cst::from_sql(sql)?;
+
+        assert_eq!(cst.to_string(), sql);
+        let statements: Vec<String> = cst
+            .statements()
+            .map(|statement| statement.trimmed_text())
+            .collect();
+        assert_eq!(
+            statements,
+            vec!["SELECT * /* all */FROM t /* x */;", "SELECT 2/**/;"]
+        );
+        let texts: Vec<&str> = cst
+            .statements()
+            .flat_map(|statement| statement.tokens())
+            .map(|token| token.text.as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            vec!["SELECT", "*", "FROM", "t", ";", "SELECT", "2", ";"]
+        );
+
+        // A scripting block is one statement, semicolons and all
+        let sql = "BEGIN\n  INSERT INTO u SELECT * FROM t;\n  SELECT 1;\nEND;\nSELECT 2;";
+        let statements: Vec<String> = Cst::from_sql(sql)?
+            .statements()
+            .map(|statement| statement.trimmed_text())
+            .collect();
+        assert_eq!(
+            statements,
+            vec![
+                "BEGIN\n  INSERT INTO u SELECT * FROM t;\n  SELECT 1;\nEND;",
+                "SELECT 2;"
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn removes_select_items_in_place() -> Result<()> {
+        let sql =
+            "SELECT\n    id,\n    -- deprecated\n    legacy_code,\n    name\nFROM customers\n";
+        let mut cst = Cst::from_sql(sql)?;
+
+        let list = cst
+            .root_mut()
+            .nth_descendant_mut(SyntaxKind::SelectList, 0)
+            .expect("a select list");
+        let removed = list.remove_item(1)?;
+        assert_eq!(removed.trimmed_text(), "legacy_code");
+        assert_eq!(
+            cst.to_string(),
+            "SELECT\n    id,\n    name\nFROM customers\n"
+        );
+
+        let list = cst
+            .root_mut()
+            .nth_descendant_mut(SyntaxKind::SelectList, 0)
+            .expect("a select list");
+        list.remove_item(1)?;
+        assert_eq!(cst.to_string(), "SELECT\n    id\nFROM customers\n");
+
+        let list = cst
+            .root_mut()
+            .nth_descendant_mut(SyntaxKind::SelectList, 0)
+            .expect("a select list");
+        assert!(list.remove_item(0).is_err());
+
+        Ok(())
+    }
+}
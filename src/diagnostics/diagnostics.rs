@@ -0,0 +1,347 @@
+//! Error and warning reports pointing at the SQL they're about.
+//!
+//! A [`Diagnostic`] carries a message and labelled spans of source, and is
+//! built from lexing errors, parse errors and [`ImplicitCast`] warnings. The
+//! [`Renderer`] prints it with the lines it points at, in color on a terminal.
+
+use std::fmt::Write;
+use std::io::IsTerminal;
+
+use crate::ast::expression::{Expr, Value};
+use crate::lexer::lexer::{LexError, Location, Span};
+use crate::parser::error::ParseError;
+use crate::types::types::ImplicitCast;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const GREEN: &str = "\x1b[1;32m";
+const BLUE: &str = "\x1b[1;34m";
+
+/// Width a tab is expanded to when showing a line of source.
+const TAB_WIDTH: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem found in a source file, whether by the lexer, the parser or a
+/// lint, along with everything needed to explain it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// The parts of the source the diagnostic is about. The first one is where
+    /// the problem is, the others add context.
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    /// Suggestions for how to fix the problem.
+    pub help: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Diagnostic::new(Severity::Error, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Diagnostic::new(Severity::Warning, message)
+    }
+
+    fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity,
+            message: message.into(),
+            labels: vec![],
+            notes: vec![],
+            help: vec![],
+        }
+    }
+
+    /// Points at `span`, with an optional explanation (empty for none).
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+}
+
+impl From<&LexError> for Diagnostic {
+    fn from(error: &LexError) -> Self {
+        Diagnostic::error(&error.message).with_label(error.span, "")
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(error: &ParseError) -> Self {
+        let label = match &error.expected {
+            Some(expected) => format!("expected {}", expected),
+            None => String::new(),
+        };
+        Diagnostic::error(&error.message).with_label(error.span, label)
+    }
+}
+
+impl From<&ImplicitCast> for Diagnostic {
+    fn from(cast: &ImplicitCast) -> Self {
+        let expr = match &cast.expr {
+            Expr::Identifier(ident) => ident.value.clone(),
+            Expr::CompoundIdentifier(parts) => {
+                let parts: Vec<&str> = parts.iter().map(|part| part.value.as_str()).collect();
+                parts.join(".")
+            }
+            Expr::Value(Value::Varchar(value)) => format!("'{}'", value.replace('\'', "''")),
+            Expr::Value(Value::Int(value)) => value.to_string(),
+            Expr::Value(Value::Decimal(value)) => value.clone(),
+            _ => "An expression".to_string(),
+        };
+        Diagnostic::warning(format!(
+            "{} is implicitly cast from {} to {}",
+            expr, cast.from, cast.to
+        ))
+        .with_label(cast.span, "in this statement")
+        .with_note("Values that don't convert fail at runtime, and pruning can't use the column")
+        .with_help(format!(
+            "Compare values of the same type, or cast explicitly with ::{}",
+            cast.to
+        ))
+    }
+}
+
+/// Renders diagnostics the way rustc does, quoting the lines of source they
+/// point at:
+///
+/// ```text
+/// error: Expected `BY`, found `b`
+///  --> query.sql:1:23
+///   |
+/// 1 | SELECT b FROM u GROUP b
+///   |                       ^ expected `BY`
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Renderer {
+    color: bool,
+}
+
+impl Renderer {
+    pub fn new(color: bool) -> Self {
+        Renderer { color }
+    }
+
+    /// A renderer for `stream`, in color when it's a terminal and `NO_COLOR`
+    /// isn't set.
+    pub fn for_stream(stream: &impl IsTerminal) -> Self {
+        Renderer::new(stream.is_terminal() && std::env::var_os("NO_COLOR").is_none())
+    }
+
+    /// Renders `diagnostic` for the file `file_name` whose content is `source`.
+    pub fn render(&self, diagnostic: &Diagnostic, file_name: &str, source: &str) -> String {
+        let (name, color) = match diagnostic.severity {
+            Severity::Error => ("error", RED),
+            Severity::Warning => ("warning", YELLOW),
+        };
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{}{}",
+            self.paint(color, name),
+            self.paint(BOLD, &format!(": {}", diagnostic.message))
+        );
+
+        let mut labels: Vec<(usize, &Label, Location)> = diagnostic
+            .labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (i, label, Location::of(source, label.span.start)))
+            .collect();
+        labels.sort_by_key(|(_, _, location)| (location.line, location.column));
+
+        let gutter = labels
+            .iter()
+            .map(|(_, _, location)| location.line.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let pad = " ".repeat(gutter);
+
+        match diagnostic.labels.first() {
+            Some(primary) => {
+                let location = Location::of(source, primary.span.start);
+                let _ = writeln!(
+                    out,
+                    "{}{} {}:{}:{}",
+                    pad,
+                    self.paint(BLUE, "-->"),
+                    file_name,
+                    location.line,
+                    location.column
+                );
+            }
+            None => {
+                let _ = writeln!(out, "{}{} {}", pad, self.paint(BLUE, "-->"), file_name);
+            }
+        }
+
+        if !labels.is_empty() {
+            let _ = writeln!(out, "{} {}", pad, self.paint(BLUE, "|"));
+        }
+        let mut previous_line = None;
+        for (i, label, location) in &labels {
+            let start = label.span.start.min(source.len());
+            let line_start = source[..start].rfind('\n').map_or(0, |newline| newline + 1);
+            let line_text = source[line_start..].lines().next().unwrap_or_default();
+            if previous_line != Some(location.line) {
+                if previous_line.is_some_and(|previous| location.line > previous + 1) {
+                    let _ = writeln!(out, "{}", self.paint(BLUE, "..."));
+                }
+                let _ = writeln!(
+                    out,
+                    "{} {} {}",
+                    self.paint(BLUE, &format!("{:>gutter$}", location.line)),
+                    self.paint(BLUE, "|"),
+                    expand_tabs(line_text).trim_end()
+                );
+                previous_line = Some(location.line);
+            }
+
+            // Underline the label up to the end of its first line
+            let before = &source[line_start..start];
+            let covered = &source[start..label.span.end.clamp(start, source.len())];
+            let covered = covered.lines().next().unwrap_or_default();
+            let (marker, color) = if *i == 0 { ('^', color) } else { ('-', BLUE) };
+            let underline = marker
+                .to_string()
+                .repeat(expand_tabs(covered).chars().count().max(1));
+            let mut line = format!(
+                "{}{}",
+                " ".repeat(expand_tabs(before).chars().count()),
+                underline
+            );
+            if !label.message.is_empty() {
+                line = format!("{} {}", line, label.message);
+            }
+            let _ = writeln!(
+                out,
+                "{} {} {}",
+                pad,
+                self.paint(BLUE, "|"),
+                self.paint(color, &line)
+            );
+        }
+
+        if !diagnostic.notes.is_empty() || !diagnostic.help.is_empty() {
+            let _ = writeln!(out, "{} {}", pad, self.paint(BLUE, "|"));
+        }
+        for note in &diagnostic.notes {
+            let _ = writeln!(
+                out,
+                "{} {} {}: {}",
+                pad,
+                self.paint(BLUE, "="),
+                self.paint(BOLD, "note"),
+                note
+            );
+        }
+        for help in &diagnostic.help {
+            let _ = writeln!(
+                out,
+                "{} {} {}: {}",
+                pad,
+                self.paint(BLUE, "="),
+                self.paint(GREEN, "help"),
+                help
+            );
+        }
+
+        out
+    }
+
+    fn paint(&self, color: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", color, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Diagnostic, Renderer};
+    use crate::lexer::lexer::Span;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn renders_source_snippets() {
+        let source = "SELECT a\nFROM t\nWHERE\tcafé = 1 AND = 2";
+        let diagnostic = Diagnostic::error("Expected an expression")
+            .with_label(Span { start: 36, end: 37 }, "expected an expression")
+            .with_label(Span { start: 0, end: 6 }, "in this query")
+            .with_note("AND needs an operand on each side")
+            .with_help("remove the trailing AND");
+
+        assert_eq!(
+            Renderer::new(false).render(&diagnostic, "query.sql", source),
+            "\
+error: Expected an expression
+ --> query.sql:3:20
+  |
+1 | SELECT a
+  | ------ in this query
+...
+3 | WHERE    café = 1 AND = 2
+  |                       ^ expected an expression
+  |
+  = note: AND needs an operand on each side
+  = help: remove the trailing AND
+"
+        );
+
+        let colored = Renderer::new(true).render(&diagnostic, "query.sql", source);
+        assert!(colored.starts_with("\x1b[1;31merror\x1b[0m"));
+    }
+
+    #[test]
+    fn renders_parse_errors() -> anyhow::Result<()> {
+        let source = "SELECT b FROM u GROUP b;\nSELECT 'oops";
+        let (_, errors) = Parser::from_sql(source).parse_statements_with_recovery();
+        let rendered: Vec<String> = errors
+            .iter()
+            .map(|error| Renderer::new(false).render(&error.into(), "q.sql", source))
+            .collect();
+
+        assert!(rendered[0].contains(" --> q.sql:1:23\n"));
+        assert!(rendered[0].contains(
+            "\n1 | SELECT b FROM u GROUP b;\n  |                       ^ expected `BY`\n"
+        ));
+        assert!(rendered[1].starts_with("error: Unterminated string literal\n --> q.sql:2:8\n"));
+        assert!(rendered[1].ends_with("2 | SELECT 'oops\n  |        ^^^^^\n"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,424 @@
+//! Renders table dependencies and column lineage as Graphviz DOT, Mermaid
+//! flowcharts or JSON.
+//!
+//! A [`Graph`] is built from a [`DependencyGraph`] or from
+//! [`StatementLineage`]s, optionally narrowed to what's upstream or
+//! downstream of one node, and then written out in any of the formats.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use crate::graph::graph::{DependencyGraph, Input};
+use crate::json::json::Json;
+use crate::lineage::lineage::StatementLineage;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    Table,
+    Stage,
+    Cte,
+    Column,
+}
+
+impl NodeKind {
+    fn name(self) -> &'static str {
+        match self {
+            NodeKind::Table => "table",
+            NodeKind::Stage => "stage",
+            NodeKind::Cte => "cte",
+            NodeKind::Column => "column",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    /// Unique within the graph, e.g. `RAW.ORDERS`, `ANALYTICS.ACTIVE::RECENT`
+    /// for a CTE, or `RAW.ORDERS.ID` for a column.
+    pub id: String,
+    pub label: String,
+    pub kind: NodeKind,
+}
+
+/// Data flowing from one node into another.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    /// The lineage kind for column edges, e.g. `direct`.
+    pub label: Option<String>,
+}
+
+/// Nodes and edges kept sorted by id, so every rendering is stable.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Graph {
+    nodes: BTreeMap<String, Node>,
+    edges: BTreeSet<Edge>,
+}
+
+/// The version of the [`Graph::to_json`] schema, raised on incompatible
+/// changes.
+pub const JSON_SCHEMA_VERSION: usize = 1;
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The objects of `graph` with an edge from each object to those built
+    /// from it. Unless `collapse_ctes` is set, the CTEs of each statement
+    /// appear as nodes between the objects they read and the statement's
+    /// outputs.
+    pub fn from_dependencies(graph: &DependencyGraph, collapse_ctes: bool) -> Self {
+        let mut result = Graph::new();
+        for (object, dependencies) in graph.dependencies() {
+            result.add_object(&object);
+            if collapse_ctes {
+                for dependency in dependencies {
+                    result.add_object(&dependency);
+                    result.add_edge(&dependency, &object, None);
+                }
+            }
+        }
+        if collapse_ctes {
+            return result;
+        }
+
+        let files = graph.statement_accesses();
+        for access in files.iter().flat_map(|(_, statements)| statements) {
+            let outputs: Vec<_> = access.outputs().collect();
+            let Some(first) = outputs.first() else {
+                continue;
+            };
+            let node = |input: &Input| match input {
+                Input::Object(name) => name.clone(),
+                Input::Cte(name) => format!("{}::{}", first, name),
+            };
+
+            for cte in access.ctes.keys() {
+                result.add_node(&node(&Input::Cte(cte.clone())), cte, NodeKind::Cte);
+            }
+            for (cte, inputs) in &access.ctes {
+                let id = node(&Input::Cte(cte.clone()));
+                for input in inputs {
+                    result.add_edge(&node(input), &id, None);
+                }
+            }
+            for output in &outputs {
+                for input in &access.inputs {
+                    let from = node(input);
+                    if &&from != output {
+                        result.add_edge(&from, output, None);
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// The columns of `lineages` with an edge from each source column to
+    /// the output columns derived from it, labelled with how. Lineage is
+    /// already traced through CTEs to base tables, so CTEs never appear.
+    pub fn from_lineage<'a>(lineages: impl IntoIterator<Item = &'a StatementLineage>) -> Self {
+        let mut result = Graph::new();
+        for lineage in lineages {
+            let target = lineage.target.as_ref().map(|target| target.join("."));
+            for (i, column) in lineage.columns.iter().flatten().enumerate() {
+                let name = match &column.name {
+                    Some(name) => name.clone(),
+                    None => format!("COLUMN{}", i + 1),
+                };
+                let id = match &target {
+                    Some(target) => format!("{}.{}", target, name),
+                    None => name,
+                };
+                result.add_node(&id, &id, NodeKind::Column);
+                for source in &column.sources {
+                    let from = source.to_string();
+                    result.add_node(&from, &from, NodeKind::Column);
+                    let label = format!("{:?}", source.kind).to_lowercase();
+                    result.add_edge(&from, &id, Some(label));
+                }
+            }
+        }
+        result
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.values()
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = &Edge> {
+        self.edges.iter()
+    }
+
+    /// Adds a node, keeping the existing one if `id` is already present.
+    pub fn add_node(&mut self, id: &str, label: &str, kind: NodeKind) {
+        self.nodes.entry(id.to_string()).or_insert_with(|| Node {
+            id: id.to_string(),
+            label: label.to_string(),
+            kind,
+        });
+    }
+
+    /// Adds an edge, adding its endpoints as objects if they're missing.
+    pub fn add_edge(&mut self, from: &str, to: &str, label: Option<String>) {
+        self.add_object(from);
+        self.add_object(to);
+        self.edges.insert(Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            label,
+        });
+    }
+
+    /// Adds the nodes and edges of `other`.
+    pub fn extend(&mut self, other: Graph) {
+        for (id, node) in other.nodes {
+            self.nodes.entry(id).or_insert(node);
+        }
+        self.edges.extend(other.edges);
+    }
+
+    /// `id` and everything it's derived from.
+    pub fn upstream(&self, id: &str) -> Graph {
+        self.reachable(id, |edge| (&edge.to, &edge.from))
+    }
+
+    /// `id` and everything derived from it.
+    pub fn downstream(&self, id: &str) -> Graph {
+        self.reachable(id, |edge| (&edge.from, &edge.to))
+    }
+
+    /// The subgraph of the nodes reachable from `id` along `direction`,
+    /// which gives the end an edge is followed from and the end it leads to.
+    fn reachable(&self, id: &str, direction: fn(&Edge) -> (&String, &String)) -> Graph {
+        let mut seen = BTreeSet::new();
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            if !self.nodes.contains_key(id) || !seen.insert(id) {
+                continue;
+            }
+            for edge in &self.edges {
+                let (from, to) = direction(edge);
+                if from == id {
+                    pending.push(to);
+                }
+            }
+        }
+
+        Graph {
+            nodes: self
+                .nodes
+                .iter()
+                .filter(|(id, _)| seen.contains(id.as_str()))
+                .map(|(id, node)| (id.clone(), node.clone()))
+                .collect(),
+            edges: self
+                .edges
+                .iter()
+                .filter(|edge| seen.contains(edge.from.as_str()) && seen.contains(edge.to.as_str()))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph lineage {\n    rankdir=LR;\n");
+        for node in self.nodes() {
+            let shape = match node.kind {
+                NodeKind::Table => "shape=box",
+                NodeKind::Stage => "shape=folder",
+                NodeKind::Cte => "shape=box, style=dashed",
+                NodeKind::Column => "shape=ellipse",
+            };
+            let _ = writeln!(
+                out,
+                "    {} [label={}, {}];",
+                dot_string(&node.id),
+                dot_string(&node.label),
+                shape
+            );
+        }
+        for edge in self.edges() {
+            let _ = write!(
+                out,
+                "    {} -> {}",
+                dot_string(&edge.from),
+                dot_string(&edge.to)
+            );
+            if let Some(label) = &edge.label {
+                let _ = write!(out, " [label={}]", dot_string(label));
+            }
+            out.push_str(";\n");
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// A `flowchart LR` for embedding in Markdown. Mermaid ids can't contain
+    /// dots, so nodes are numbered in id order and the ids become labels.
+    pub fn to_mermaid(&self) -> String {
+        let ids: BTreeMap<&str, String> = self
+            .nodes
+            .keys()
+            .enumerate()
+            .map(|(i, id)| (id.as_str(), format!("n{}", i)))
+            .collect();
+
+        let mut out = String::from("flowchart LR\n");
+        for node in self.nodes() {
+            let label = mermaid_string(&node.label);
+            let id = &ids[node.id.as_str()];
+            let _ = match node.kind {
+                NodeKind::Table => writeln!(out, "    {}[{}]", id, label),
+                NodeKind::Stage => writeln!(out, "    {}[/{}/]", id, label),
+                NodeKind::Cte => writeln!(out, "    {}({})", id, label),
+                NodeKind::Column => writeln!(out, "    {}([{}])", id, label),
+            };
+        }
+        for edge in self.edges() {
+            let (from, to) = (&ids[edge.from.as_str()], &ids[edge.to.as_str()]);
+            let _ = match &edge.label {
+                Some(label) => writeln!(out, "    {} -->|{}| {}", from, mermaid_string(label), to),
+                None => writeln!(out, "    {} --> {}", from, to),
+            };
+        }
+        out
+    }
+
+    /// `{"version": 1, "nodes": [{"id", "label", "kind"}], "edges": [{"from",
+    /// "to", "label"}]}`, with `label` null on unlabelled edges.
+    pub fn to_json(&self) -> Json {
+        let nodes = self.nodes().map(|node| {
+            Json::object([
+                ("id", Json::from(node.id.as_str())),
+                ("label", Json::from(node.label.as_str())),
+                ("kind", Json::from(node.kind.name())),
+            ])
+        });
+        let edges = self.edges().map(|edge| {
+            Json::object([
+                ("from", Json::from(edge.from.as_str())),
+                ("to", Json::from(edge.to.as_str())),
+                ("label", Json::from(edge.label.as_deref())),
+            ])
+        });
+        Json::object([
+            ("version", Json::from(JSON_SCHEMA_VERSION)),
+            ("nodes", Json::Array(nodes.collect())),
+            ("edges", Json::Array(edges.collect())),
+        ])
+    }
+
+    /// Stages are named with a leading `@`, every other object is a table.
+    fn add_object(&mut self, name: &str) {
+        let kind = match name.starts_with('@') {
+            true => NodeKind::Stage,
+            false => NodeKind::Table,
+        };
+        self.add_node(name, name, kind);
+    }
+}
+
+fn dot_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn mermaid_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "#quot;"))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::Graph;
+    use crate::binder::binder::Binder;
+    use crate::graph::graph::DependencyGraph;
+    use crate::parser::parser::Parser;
+
+    fn dependencies() -> Result<DependencyGraph> {
+        let mut graph = DependencyGraph::new();
+        graph.add_file(
+            "views.sql",
+            "
+CREATE VIEW analytics.active AS
+WITH recent AS (SELECT * FROM raw.orders), paid AS (SELECT * FROM recent WHERE paid)
+SELECT * FROM paid JOIN raw.customers c ON c.id = paid.customer_id;
+CREATE VIEW analytics.report AS SELECT * FROM analytics.active;
+COPY INTO raw.orders FROM @raw.landing",
+        );
+        Ok(graph)
+    }
+
+    #[test]
+    fn renders_dependencies_with_and_without_ctes() -> Result<()> {
+        let graph = dependencies()?;
+
+        let collapsed = Graph::from_dependencies(&graph, true);
+        assert_eq!(
+            collapsed.to_mermaid(),
+            "\
+flowchart LR
+    n0[/\"@RAW.LANDING\"/]
+    n1[\"ANALYTICS.ACTIVE\"]
+    n2[\"ANALYTICS.REPORT\"]
+    n3[\"RAW.CUSTOMERS\"]
+    n4[\"RAW.ORDERS\"]
+    n0 --> n4
+    n1 --> n2
+    n3 --> n1
+    n4 --> n1
+"
+        );
+
+        let expanded = Graph::from_dependencies(&graph, false).upstream("ANALYTICS.ACTIVE");
+        assert_eq!(
+            expanded.to_dot(),
+            "\
+digraph lineage {
+    rankdir=LR;
+    \"@RAW.LANDING\" [label=\"@RAW.LANDING\", shape=folder];
+    \"ANALYTICS.ACTIVE\" [label=\"ANALYTICS.ACTIVE\", shape=box];
+    \"ANALYTICS.ACTIVE::PAID\" [label=\"PAID\", shape=box, style=dashed];
+    \"ANALYTICS.ACTIVE::RECENT\" [label=\"RECENT\", shape=box, style=dashed];
+    \"RAW.CUSTOMERS\" [label=\"RAW.CUSTOMERS\", shape=box];
+    \"RAW.ORDERS\" [label=\"RAW.ORDERS\", shape=box];
+    \"@RAW.LANDING\" -> \"RAW.ORDERS\";
+    \"ANALYTICS.ACTIVE::PAID\" -> \"ANALYTICS.ACTIVE\";
+    \"ANALYTICS.ACTIVE::RECENT\" -> \"ANALYTICS.ACTIVE::PAID\";
+    \"RAW.CUSTOMERS\" -> \"ANALYTICS.ACTIVE\";
+    \"RAW.ORDERS\" -> \"ANALYTICS.ACTIVE::RECENT\";
+}
+"
+        );
+
+        let downstream = Graph::from_dependencies(&graph, true).downstream("ANALYTICS.ACTIVE");
+        let ids: Vec<_> = downstream.nodes().map(|node| node.id.as_str()).collect();
+        assert_eq!(ids, vec!["ANALYTICS.ACTIVE", "ANALYTICS.REPORT"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn exports_column_lineage_as_json() -> Result<()> {
+        let sql = "INSERT INTO totals (total) SELECT SUM(amount) FROM orders WHERE paid";
+        let statement = Parser::from_sql(sql).parse_statement()?;
+        let lineage = Binder::new().lineage(&statement).unwrap();
+
+        assert_eq!(
+            Graph::from_lineage([&lineage]).to_json().to_string(),
+            concat!(
+                r#"{"version":1,"nodes":["#,
+                r#"{"id":"ORDERS.AMOUNT","label":"ORDERS.AMOUNT","kind":"column"},"#,
+                r#"{"id":"ORDERS.PAID","label":"ORDERS.PAID","kind":"column"},"#,
+                r#"{"id":"TOTALS.TOTAL","label":"TOTALS.TOTAL","kind":"column"}],"edges":["#,
+                r#"{"from":"ORDERS.AMOUNT","to":"TOTALS.TOTAL","label":"aggregation"},"#,
+                r#"{"from":"ORDERS.PAID","to":"TOTALS.TOTAL","label":"filter"}]}"#
+            )
+        );
+
+        Ok(())
+    }
+}
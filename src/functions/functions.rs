@@ -0,0 +1,357 @@
+use crate::ast::data_type::{DataType, TimestampTimeZone};
+
+use Arity as A;
+use FunctionKind::{Aggregate, Scalar, Table, Window};
+use ReturnType::{Argument, Fixed};
+
+/// What a built-in function computes, which decides where it may be used.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FunctionKind {
+    Scalar,
+    /// Aggregates may also be used as window functions with `OVER`.
+    Aggregate,
+    /// Functions that require an `OVER` clause, such as `ROW_NUMBER`.
+    Window,
+    /// Table functions used in `FROM TABLE(...)` or `LATERAL`.
+    Table,
+}
+
+/// The number of arguments a function accepts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Arity {
+    pub min: usize,
+    /// `None` for variadic functions such as `COALESCE`.
+    pub max: Option<usize>,
+}
+
+impl Arity {
+    const fn exactly(count: usize) -> Self {
+        Arity {
+            min: count,
+            max: Some(count),
+        }
+    }
+
+    const fn between(min: usize, max: usize) -> Self {
+        Arity {
+            min,
+            max: Some(max),
+        }
+    }
+
+    const fn at_least(min: usize) -> Self {
+        Arity { min, max: None }
+    }
+
+    pub fn accepts(&self, count: usize) -> bool {
+        count >= self.min && self.max.is_none_or(|max| count <= max)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReturnType {
+    Fixed(DataType),
+    /// The type of the argument at this index, e.g. `MAX(x)` or `IFF(c, a, b)`.
+    Argument(usize),
+    /// The columns produced by a table function.
+    Table(&'static [&'static str]),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuiltinFunction {
+    pub name: &'static str,
+    pub kind: FunctionKind,
+    pub arity: Arity,
+    pub return_type: ReturnType,
+}
+
+const fn function(
+    name: &'static str,
+    kind: FunctionKind,
+    arity: Arity,
+    return_type: ReturnType,
+) -> BuiltinFunction {
+    BuiltinFunction {
+        name,
+        kind,
+        arity,
+        return_type,
+    }
+}
+
+const INTEGER: ReturnType = Fixed(DataType::Number {
+    precision: Some(38),
+    scale: Some(0),
+});
+const NUMBER: ReturnType = Fixed(DataType::Number {
+    precision: None,
+    scale: None,
+});
+const FLOAT: ReturnType = Fixed(DataType::Float);
+const VARCHAR: ReturnType = Fixed(DataType::Varchar(None));
+const BOOLEAN: ReturnType = Fixed(DataType::Boolean);
+const DATE: ReturnType = Fixed(DataType::Date);
+const VARIANT: ReturnType = Fixed(DataType::Variant);
+const ARRAY: ReturnType = Fixed(DataType::Array(None));
+const OBJECT: ReturnType = Fixed(DataType::Object(Vec::new()));
+
+const fn timestamp(time_zone: Option<TimestampTimeZone>) -> ReturnType {
+    Fixed(DataType::Timestamp {
+        time_zone,
+        precision: None,
+    })
+}
+
+const FLATTEN_COLUMNS: &[&str] = &["SEQ", "KEY", "PATH", "INDEX", "VALUE", "THIS"];
+const SPLIT_TO_TABLE_COLUMNS: &[&str] = &["SEQ", "INDEX", "VALUE"];
+
+static BUILTIN_FUNCTIONS: &[BuiltinFunction] = &[
+    // Aggregates
+    function("ANY_VALUE", Aggregate, A::exactly(1), Argument(0)),
+    function("APPROX_COUNT_DISTINCT", Aggregate, A::at_least(1), INTEGER),
+    function("ARRAY_AGG", Aggregate, A::exactly(1), ARRAY),
+    function("AVG", Aggregate, A::exactly(1), NUMBER),
+    function("BOOLAND_AGG", Aggregate, A::exactly(1), BOOLEAN),
+    function("BOOLOR_AGG", Aggregate, A::exactly(1), BOOLEAN),
+    function("COUNT", Aggregate, A::at_least(1), INTEGER),
+    function("COUNT_IF", Aggregate, A::exactly(1), INTEGER),
+    function("LISTAGG", Aggregate, A::between(1, 2), VARCHAR),
+    function("MAX", Aggregate, A::exactly(1), Argument(0)),
+    function("MAX_BY", Aggregate, A::between(2, 3), Argument(0)),
+    function("MEDIAN", Aggregate, A::exactly(1), Argument(0)),
+    function("MIN", Aggregate, A::exactly(1), Argument(0)),
+    function("MIN_BY", Aggregate, A::between(2, 3), Argument(0)),
+    function("MODE", Aggregate, A::exactly(1), Argument(0)),
+    function("OBJECT_AGG", Aggregate, A::exactly(2), OBJECT),
+    function("PERCENTILE_CONT", Aggregate, A::exactly(1), NUMBER),
+    function("PERCENTILE_DISC", Aggregate, A::exactly(1), NUMBER),
+    function("STDDEV", Aggregate, A::exactly(1), FLOAT),
+    function("SUM", Aggregate, A::exactly(1), Argument(0)),
+    function("VARIANCE", Aggregate, A::exactly(1), FLOAT),
+    // Window functions
+    function("CUME_DIST", Window, A::exactly(0), FLOAT),
+    function("DENSE_RANK", Window, A::exactly(0), INTEGER),
+    function("FIRST_VALUE", Window, A::exactly(1), Argument(0)),
+    function("LAG", Window, A::between(1, 3), Argument(0)),
+    function("LAST_VALUE", Window, A::exactly(1), Argument(0)),
+    function("LEAD", Window, A::between(1, 3), Argument(0)),
+    function("NTH_VALUE", Window, A::exactly(2), Argument(0)),
+    function("NTILE", Window, A::exactly(1), INTEGER),
+    function("PERCENT_RANK", Window, A::exactly(0), FLOAT),
+    function("RANK", Window, A::exactly(0), INTEGER),
+    function("RATIO_TO_REPORT", Window, A::exactly(1), NUMBER),
+    function("ROW_NUMBER", Window, A::exactly(0), INTEGER),
+    // Conditional expressions
+    function("COALESCE", Scalar, A::at_least(1), Argument(0)),
+    function("DECODE", Scalar, A::at_least(3), Argument(2)),
+    function("EQUAL_NULL", Scalar, A::exactly(2), BOOLEAN),
+    function("GREATEST", Scalar, A::at_least(1), Argument(0)),
+    function("IFF", Scalar, A::exactly(3), Argument(1)),
+    function("IFNULL", Scalar, A::exactly(2), Argument(0)),
+    function("LEAST", Scalar, A::at_least(1), Argument(0)),
+    function("NULLIF", Scalar, A::exactly(2), Argument(0)),
+    function("NVL", Scalar, A::exactly(2), Argument(0)),
+    function("NVL2", Scalar, A::exactly(3), Argument(1)),
+    function("ZEROIFNULL", Scalar, A::exactly(1), Argument(0)),
+    // Numeric functions
+    function("ABS", Scalar, A::exactly(1), Argument(0)),
+    function("CEIL", Scalar, A::between(1, 2), Argument(0)),
+    function("DIV0", Scalar, A::exactly(2), NUMBER),
+    function("EXP", Scalar, A::exactly(1), FLOAT),
+    function("FLOOR", Scalar, A::between(1, 2), Argument(0)),
+    function("LN", Scalar, A::exactly(1), FLOAT),
+    function("LOG", Scalar, A::exactly(2), FLOAT),
+    function("MOD", Scalar, A::exactly(2), Argument(0)),
+    function("POWER", Scalar, A::exactly(2), FLOAT),
+    function("ROUND", Scalar, A::between(1, 3), Argument(0)),
+    function("SQRT", Scalar, A::exactly(1), FLOAT),
+    function("TRUNC", Scalar, A::between(1, 2), Argument(0)),
+    // String functions
+    function("CONCAT", Scalar, A::at_least(1), VARCHAR),
+    function("CONCAT_WS", Scalar, A::at_least(2), VARCHAR),
+    function("CONTAINS", Scalar, A::exactly(2), BOOLEAN),
+    function("ENDSWITH", Scalar, A::exactly(2), BOOLEAN),
+    function("HASH", Scalar, A::at_least(1), INTEGER),
+    function("INITCAP", Scalar, A::between(1, 2), VARCHAR),
+    function("LEFT", Scalar, A::exactly(2), VARCHAR),
+    function("LEN", Scalar, A::exactly(1), INTEGER),
+    function("LENGTH", Scalar, A::exactly(1), INTEGER),
+    function("LOWER", Scalar, A::exactly(1), VARCHAR),
+    function("LPAD", Scalar, A::between(2, 3), VARCHAR),
+    function("LTRIM", Scalar, A::between(1, 2), VARCHAR),
+    function("MD5", Scalar, A::exactly(1), VARCHAR),
+    function("REGEXP_LIKE", Scalar, A::between(2, 3), BOOLEAN),
+    function("REGEXP_REPLACE", Scalar, A::between(2, 6), VARCHAR),
+    function("REGEXP_SUBSTR", Scalar, A::between(2, 6), VARCHAR),
+    function("REPLACE", Scalar, A::between(2, 3), VARCHAR),
+    function("RIGHT", Scalar, A::exactly(2), VARCHAR),
+    function("RPAD", Scalar, A::between(2, 3), VARCHAR),
+    function("RTRIM", Scalar, A::between(1, 2), VARCHAR),
+    function("SHA2", Scalar, A::between(1, 2), VARCHAR),
+    function("SPLIT", Scalar, A::exactly(2), ARRAY),
+    function("SPLIT_PART", Scalar, A::exactly(3), VARCHAR),
+    function("STARTSWITH", Scalar, A::exactly(2), BOOLEAN),
+    function("SUBSTR", Scalar, A::between(2, 3), VARCHAR),
+    function("SUBSTRING", Scalar, A::between(2, 3), VARCHAR),
+    function("TRIM", Scalar, A::between(1, 2), VARCHAR),
+    function("UPPER", Scalar, A::exactly(1), VARCHAR),
+    function("UUID_STRING", Scalar, A::between(0, 2), VARCHAR),
+    // Conversion functions
+    function("TO_BOOLEAN", Scalar, A::exactly(1), BOOLEAN),
+    function("TO_CHAR", Scalar, A::between(1, 2), VARCHAR),
+    function("TO_DATE", Scalar, A::between(1, 2), DATE),
+    function("TO_DECIMAL", Scalar, A::between(1, 4), NUMBER),
+    function("TO_DOUBLE", Scalar, A::between(1, 2), FLOAT),
+    function("TO_NUMBER", Scalar, A::between(1, 4), NUMBER),
+    function("TO_NUMERIC", Scalar, A::between(1, 4), NUMBER),
+    function(
+        "TO_TIME",
+        Scalar,
+        A::between(1, 2),
+        Fixed(DataType::Time(None)),
+    ),
+    function("TO_TIMESTAMP", Scalar, A::between(1, 2), timestamp(None)),
+    function(
+        "TO_TIMESTAMP_LTZ",
+        Scalar,
+        A::between(1, 2),
+        timestamp(Some(TimestampTimeZone::Local)),
+    ),
+    function(
+        "TO_TIMESTAMP_NTZ",
+        Scalar,
+        A::between(1, 2),
+        timestamp(Some(TimestampTimeZone::None)),
+    ),
+    function(
+        "TO_TIMESTAMP_TZ",
+        Scalar,
+        A::between(1, 2),
+        timestamp(Some(TimestampTimeZone::WithTimeZone)),
+    ),
+    function("TO_VARCHAR", Scalar, A::between(1, 2), VARCHAR),
+    function("TRY_TO_BOOLEAN", Scalar, A::exactly(1), BOOLEAN),
+    function("TRY_TO_DATE", Scalar, A::between(1, 2), DATE),
+    function("TRY_TO_NUMBER", Scalar, A::between(1, 4), NUMBER),
+    function(
+        "TRY_TO_TIMESTAMP",
+        Scalar,
+        A::between(1, 2),
+        timestamp(None),
+    ),
+    // Date and time functions
+    function(
+        "CONVERT_TIMEZONE",
+        Scalar,
+        A::between(2, 3),
+        timestamp(None),
+    ),
+    function("CURRENT_DATE", Scalar, A::exactly(0), DATE),
+    function(
+        "CURRENT_TIME",
+        Scalar,
+        A::between(0, 1),
+        Fixed(DataType::Time(None)),
+    ),
+    function(
+        "CURRENT_TIMESTAMP",
+        Scalar,
+        A::between(0, 1),
+        timestamp(Some(TimestampTimeZone::Local)),
+    ),
+    function("DATE_PART", Scalar, A::exactly(2), INTEGER),
+    function("DATE_TRUNC", Scalar, A::exactly(2), Argument(1)),
+    function("DATEADD", Scalar, A::exactly(3), Argument(2)),
+    function("DATEDIFF", Scalar, A::exactly(3), INTEGER),
+    function("DAY", Scalar, A::exactly(1), INTEGER),
+    function("DAYOFWEEK", Scalar, A::exactly(1), INTEGER),
+    function("EXTRACT", Scalar, A::exactly(2), INTEGER),
+    function(
+        "GETDATE",
+        Scalar,
+        A::exactly(0),
+        timestamp(Some(TimestampTimeZone::Local)),
+    ),
+    function("HOUR", Scalar, A::exactly(1), INTEGER),
+    function("LAST_DAY", Scalar, A::between(1, 2), DATE),
+    function("MONTH", Scalar, A::exactly(1), INTEGER),
+    function("QUARTER", Scalar, A::exactly(1), INTEGER),
+    function(
+        "SYSDATE",
+        Scalar,
+        A::exactly(0),
+        timestamp(Some(TimestampTimeZone::None)),
+    ),
+    function("TIMESTAMPADD", Scalar, A::exactly(3), Argument(2)),
+    function("TIMESTAMPDIFF", Scalar, A::exactly(3), INTEGER),
+    function("YEAR", Scalar, A::exactly(1), INTEGER),
+    // Semi-structured data functions
+    function("ARRAY_APPEND", Scalar, A::exactly(2), ARRAY),
+    function("ARRAY_CAT", Scalar, A::exactly(2), ARRAY),
+    function("ARRAY_CONSTRUCT", Scalar, A::at_least(0), ARRAY),
+    function("ARRAY_CONTAINS", Scalar, A::exactly(2), BOOLEAN),
+    function("ARRAY_SIZE", Scalar, A::exactly(1), INTEGER),
+    function("ARRAY_TO_STRING", Scalar, A::exactly(2), VARCHAR),
+    function("FILTER", Scalar, A::exactly(2), ARRAY),
+    function("GET", Scalar, A::exactly(2), VARIANT),
+    function("GET_PATH", Scalar, A::exactly(2), VARIANT),
+    function("OBJECT_CONSTRUCT", Scalar, A::at_least(0), OBJECT),
+    function("OBJECT_CONSTRUCT_KEEP_NULL", Scalar, A::at_least(0), OBJECT),
+    function("OBJECT_INSERT", Scalar, A::between(3, 4), OBJECT),
+    function("PARSE_JSON", Scalar, A::exactly(1), VARIANT),
+    function("TO_ARRAY", Scalar, A::exactly(1), ARRAY),
+    function("TO_OBJECT", Scalar, A::exactly(1), OBJECT),
+    function("TO_VARIANT", Scalar, A::exactly(1), VARIANT),
+    function("TRANSFORM", Scalar, A::exactly(2), ARRAY),
+    function("TRY_PARSE_JSON", Scalar, A::exactly(1), VARIANT),
+    function("TYPEOF", Scalar, A::exactly(1), VARCHAR),
+    // Context functions
+    function("CURRENT_DATABASE", Scalar, A::exactly(0), VARCHAR),
+    function("CURRENT_ROLE", Scalar, A::exactly(0), VARCHAR),
+    function("CURRENT_SCHEMA", Scalar, A::exactly(0), VARCHAR),
+    function("CURRENT_USER", Scalar, A::exactly(0), VARCHAR),
+    function("CURRENT_WAREHOUSE", Scalar, A::exactly(0), VARCHAR),
+    // Table functions
+    function(
+        "FLATTEN",
+        Table,
+        A::between(1, 6),
+        ReturnType::Table(FLATTEN_COLUMNS),
+    ),
+    function("GENERATOR", Table, A::between(1, 2), ReturnType::Table(&[])),
+    function("RESULT_SCAN", Table, A::exactly(1), ReturnType::Table(&[])),
+    function(
+        "SPLIT_TO_TABLE",
+        Table,
+        A::exactly(2),
+        ReturnType::Table(SPLIT_TO_TABLE_COLUMNS),
+    ),
+];
+
+/// Finds a built-in function by name, ignoring case.
+pub fn lookup(name: &str) -> Option<&'static BuiltinFunction> {
+    BUILTIN_FUNCTIONS
+        .iter()
+        .find(|function| function.name.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lookup, FunctionKind, ReturnType};
+
+    #[test]
+    fn looks_up_builtin_functions() {
+        let iff = lookup("iff").expect("IFF is a built-in");
+        assert_eq!(iff.kind, FunctionKind::Scalar);
+        assert_eq!(iff.return_type, ReturnType::Argument(1));
+        assert!(iff.arity.accepts(3));
+        assert!(!iff.arity.accepts(2));
+
+        assert_eq!(
+            lookup("Row_Number").map(|f| f.kind),
+            Some(FunctionKind::Window)
+        );
+        assert!(lookup("COALESCE").is_some_and(|f| f.arity.accepts(10)));
+        assert!(lookup("my_udf").is_none());
+    }
+}
@@ -0,0 +1,783 @@
+//! Table-level dependencies across SQL files.
+//!
+//! [`DependencyGraph`] records the objects each statement of each file reads
+//! and writes, links every object to the ones it's built from, and orders
+//! objects and files so each comes after what it depends on.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::ast::expression::ObjectName;
+use crate::ast::query::{Cte, Query, TableFactor};
+use crate::ast::stage::{CopyInto, CopyLocation, Stage, StageRef};
+use crate::ast::statement::{Merge, Statement};
+use crate::ast::visitor::{self, Visitor};
+use crate::binder::binder::normalize;
+use crate::parser::error::ParseError;
+use crate::parser::parser::Parser;
+
+/// The objects a statement reads and writes, by qualified name. Stages are
+/// named with a leading `@`, e.g. `@RAW.LANDING`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ObjectAccess {
+    pub reads: BTreeSet<String>,
+    /// Objects whose rows are changed, by `INSERT`, `UPDATE`, `DELETE`,
+    /// `MERGE`, `TRUNCATE` or `COPY INTO`.
+    pub writes: BTreeSet<String>,
+    /// Objects defined by `CREATE`.
+    pub creates: BTreeSet<String>,
+    /// The CTEs of the statement with what each reads.
+    pub ctes: BTreeMap<String, BTreeSet<Input>>,
+    /// What the statement reads outside its CTEs.
+    pub inputs: BTreeSet<Input>,
+    /// Procedures run by `CALL`.
+    pub calls: BTreeSet<String>,
+    /// What the body of a procedure or function created by the statement
+    /// accesses. That happens on each call rather than when the routine is
+    /// created, so it's kept apart from the statement's own access.
+    pub body: Option<Box<ObjectAccess>>,
+}
+
+/// Something a statement or CTE reads rows from.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Input {
+    Object(String),
+    Cte(String),
+}
+
+impl ObjectAccess {
+    pub fn of(statement: &Statement) -> Self {
+        let mut collector = AccessCollector::default();
+        collector.visit_statement(statement);
+        collector.access
+    }
+
+    /// The objects written or created.
+    pub fn outputs(&self) -> impl Iterator<Item = &String> {
+        self.writes.iter().chain(&self.creates)
+    }
+}
+
+/// A parsed SQL file and the objects its statements access.
+#[derive(Clone, Debug)]
+pub struct SqlFile {
+    pub path: PathBuf,
+    pub source: String,
+    pub statements: Vec<ObjectAccess>,
+    pub errors: Vec<ParseError>,
+}
+
+impl SqlFile {
+    pub fn reads(&self) -> BTreeSet<&String> {
+        self.statements
+            .iter()
+            .flat_map(|access| &access.reads)
+            .collect()
+    }
+
+    pub fn outputs(&self) -> BTreeSet<&String> {
+        self.statements
+            .iter()
+            .flat_map(ObjectAccess::outputs)
+            .collect()
+    }
+}
+
+/// Objects or files that depend on each other in a loop, so no order
+/// deploys them. The first entry is repeated at the end.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cycle(pub Vec<String>);
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Dependency cycle: {}", self.0.join(" -> "))
+    }
+}
+
+impl std::error::Error for Cycle {}
+
+#[derive(Clone, Debug, Default)]
+pub struct DependencyGraph {
+    pub files: Vec<SqlFile>,
+}
+
+impl DependencyGraph {
+    pub fn new() -> Self {
+        DependencyGraph::default()
+    }
+
+    /// Adds every `.sql` file under `dir`, in path order.
+    pub fn from_directory(dir: impl AsRef<Path>) -> Result<Self> {
+        let mut graph = DependencyGraph::new();
+        graph.add_directory(dir)?;
+        Ok(graph)
+    }
+
+    pub fn add_directory(&mut self, dir: impl AsRef<Path>) -> Result<()> {
+        let mut paths = Vec::new();
+        collect_sql_files(dir.as_ref(), &mut paths)?;
+        paths.sort();
+        for path in paths {
+            let source = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            self.add_file(path, &source);
+        }
+        Ok(())
+    }
+
+    /// Parses `source`, recovering from syntax errors so the statements
+    /// around them are still recorded.
+    pub fn add_file(&mut self, path: impl Into<PathBuf>, source: &str) {
+        let (statements, errors) = Parser::from_sql(source).parse_statements_with_recovery();
+        self.files.push(SqlFile {
+            path: path.into(),
+            source: source.to_string(),
+            statements: statements.iter().map(ObjectAccess::of).collect(),
+            errors,
+        });
+    }
+
+    /// The objects each statement of each file accesses, where a `CALL` also
+    /// accesses what the body of the procedure it runs does. Partially
+    /// qualified names are resolved to the one object created in the files
+    /// whose name ends with them, e.g. `ORDERS` to `RAW.ORDERS`.
+    pub fn statement_accesses(&self) -> Vec<(&Path, Vec<ObjectAccess>)> {
+        let created: BTreeSet<&String> = self
+            .files
+            .iter()
+            .flat_map(|file| &file.statements)
+            .flat_map(|access| &access.creates)
+            .collect();
+        let files: Vec<(&Path, Vec<ObjectAccess>)> = self
+            .files
+            .iter()
+            .map(|file| {
+                let statements = file
+                    .statements
+                    .iter()
+                    .map(|access| resolve_names(access, &created))
+                    .collect();
+                (file.path.as_path(), statements)
+            })
+            .collect();
+
+        let mut routines: BTreeMap<&String, &ObjectAccess> = BTreeMap::new();
+        for access in files.iter().flat_map(|(_, statements)| statements) {
+            if let Some(body) = &access.body {
+                routines.extend(access.creates.iter().map(|name| (name, body.as_ref())));
+            }
+        }
+
+        files
+            .iter()
+            .map(|(path, statements)| {
+                let statements = statements
+                    .iter()
+                    .map(|access| with_calls(access, &routines))
+                    .collect();
+                (*path, statements)
+            })
+            .collect()
+    }
+
+    /// Every object, with the objects it's built from.
+    pub fn dependencies(&self) -> BTreeMap<String, BTreeSet<String>> {
+        let mut graph: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let files = self.statement_accesses();
+        for access in files.iter().flat_map(|(_, statements)| statements) {
+            for read in &access.reads {
+                graph.entry(read.clone()).or_default();
+            }
+            for output in access.outputs() {
+                let dependencies = graph.entry(output.clone()).or_default();
+                dependencies.extend(access.reads.iter().filter(|read| *read != output).cloned());
+            }
+        }
+        graph
+    }
+
+    /// The objects in an order where each comes after those it's built from.
+    pub fn object_order(&self) -> Result<Vec<String>, Cycle> {
+        topological_order(&self.dependencies()).map_err(Cycle)
+    }
+
+    /// The files in deployment order: after the files creating the objects
+    /// they use, and after the files writing the objects they read.
+    pub fn file_order(&self) -> Result<Vec<&Path>, Cycle> {
+        let files = self.statement_accesses();
+        let mut creators: BTreeMap<&String, BTreeSet<&Path>> = BTreeMap::new();
+        let mut writers: BTreeMap<&String, BTreeSet<&Path>> = BTreeMap::new();
+        for (path, statements) in &files {
+            for access in statements {
+                for object in &access.creates {
+                    creators.entry(object).or_default().insert(path);
+                }
+                for object in &access.writes {
+                    writers.entry(object).or_default().insert(path);
+                }
+            }
+        }
+
+        let mut graph: BTreeMap<&Path, BTreeSet<&Path>> = BTreeMap::new();
+        for (path, statements) in &files {
+            let mut dependencies: BTreeSet<&Path> = BTreeSet::new();
+            for access in statements {
+                let used = access.reads.iter().chain(&access.writes);
+                dependencies.extend(used.filter_map(|object| creators.get(object)).flatten());
+                dependencies.extend(
+                    access
+                        .reads
+                        .iter()
+                        .filter_map(|object| writers.get(object))
+                        .flatten(),
+                );
+            }
+            dependencies.remove(path);
+            graph.entry(path).or_default().extend(dependencies);
+        }
+
+        topological_order(&graph).map_err(|cycle| {
+            Cycle(
+                cycle
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect(),
+            )
+        })
+    }
+
+    /// Every group of objects that depend on each other, sorted by name.
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        let graph = self.dependencies();
+        let mut tarjan = Tarjan {
+            graph: &graph,
+            index: BTreeMap::new(),
+            low_link: BTreeMap::new(),
+            stack: Vec::new(),
+            components: Vec::new(),
+        };
+        for node in graph.keys() {
+            if !tarjan.index.contains_key(node) {
+                tarjan.connect(node);
+            }
+        }
+
+        let mut cycles: Vec<Vec<String>> = tarjan
+            .components
+            .into_iter()
+            .filter(|component| component.len() > 1)
+            .map(|component| component.into_iter().cloned().collect())
+            .collect();
+        for cycle in &mut cycles {
+            cycle.sort();
+        }
+        cycles.sort();
+        cycles
+    }
+}
+
+/// `access` with the names it uses resolved against the `created` objects.
+fn resolve_names(access: &ObjectAccess, created: &BTreeSet<&String>) -> ObjectAccess {
+    let resolve = |name: &String| resolve_name(name, created);
+    let resolve_input = |input: &Input| match input {
+        Input::Object(name) => Input::Object(resolve(name)),
+        Input::Cte(name) => Input::Cte(name.clone()),
+    };
+    ObjectAccess {
+        reads: access.reads.iter().map(resolve).collect(),
+        writes: access.writes.iter().map(resolve).collect(),
+        creates: access.creates.clone(),
+        ctes: access
+            .ctes
+            .iter()
+            .map(|(cte, inputs)| (cte.clone(), inputs.iter().map(resolve_input).collect()))
+            .collect(),
+        inputs: access.inputs.iter().map(resolve_input).collect(),
+        calls: access.calls.iter().map(resolve).collect(),
+        body: access
+            .body
+            .as_ref()
+            .map(|body| Box::new(resolve_names(body, created))),
+    }
+}
+
+/// The one created object whose qualified name ends with `name`, the way
+/// [`Catalog::table`](crate::catalog::catalog::Catalog::table) finds tables,
+/// or `name` itself when there's no such object or more than one.
+fn resolve_name(name: &String, created: &BTreeSet<&String>) -> String {
+    if created.contains(name) {
+        return name.clone();
+    }
+    let suffix = format!(".{}", name);
+    let mut matches = created.iter().filter(|object| object.ends_with(&suffix));
+    match (matches.next(), matches.next()) {
+        (Some(object), None) => (*object).clone(),
+        _ => name.clone(),
+    }
+}
+
+/// `access` along with what the bodies of the procedures it calls access,
+/// and those they call in turn.
+fn with_calls(access: &ObjectAccess, routines: &BTreeMap<&String, &ObjectAccess>) -> ObjectAccess {
+    let mut result = access.clone();
+    let mut pending: Vec<&String> = access.calls.iter().collect();
+    let mut called = BTreeSet::new();
+    while let Some(name) = pending.pop() {
+        let Some(body) = routines.get(name).filter(|_| called.insert(name)) else {
+            continue;
+        };
+        result.reads.extend(body.reads.iter().cloned());
+        result.writes.extend(body.writes.iter().cloned());
+        result.creates.extend(body.creates.iter().cloned());
+        result
+            .inputs
+            .extend(body.reads.iter().cloned().map(Input::Object));
+        pending.extend(&body.calls);
+    }
+    result
+}
+
+fn collect_sql_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    let entries =
+        std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_sql_files(&path, paths)?;
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("sql"))
+        {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Orders the nodes of `graph` after their dependencies, breaking ties by
+/// node order, or returns a cycle when there's no such order.
+fn topological_order<T: Ord + Clone>(graph: &BTreeMap<T, BTreeSet<T>>) -> Result<Vec<T>, Vec<T>> {
+    let mut remaining: BTreeMap<&T, usize> = BTreeMap::new();
+    let mut dependents: BTreeMap<&T, Vec<&T>> = BTreeMap::new();
+    for (node, dependencies) in graph {
+        remaining.insert(node, dependencies.len());
+        for dependency in dependencies {
+            dependents.entry(dependency).or_default().push(node);
+        }
+    }
+
+    let mut ready: BTreeSet<&T> = remaining
+        .iter()
+        .filter(|(_, count)| **count == 0)
+        .map(|(node, _)| *node)
+        .collect();
+    let mut order = Vec::with_capacity(graph.len());
+    while let Some(node) = ready.pop_first() {
+        order.push(node.clone());
+        for dependent in dependents.get(node).into_iter().flatten() {
+            let count = remaining.get_mut(dependent).expect("dependents are nodes");
+            *count -= 1;
+            if *count == 0 {
+                ready.insert(dependent);
+            }
+        }
+    }
+    if order.len() == graph.len() {
+        return Ok(order);
+    }
+
+    // Every node left has a dependency left, so following them from any of
+    // them must come back around
+    let blocked = |node: &T| remaining.get(node).is_some_and(|count| *count > 0);
+    let mut path: Vec<&T> = vec![remaining.keys().find(|node| blocked(node)).unwrap()];
+    loop {
+        let last = path.last().unwrap();
+        let next = graph[*last]
+            .iter()
+            .find(|dependency| blocked(dependency))
+            .expect("blocked nodes have blocked dependencies");
+        if let Some(start) = path.iter().position(|node| *node == next) {
+            let mut cycle: Vec<T> = path[start..].iter().map(|node| (*node).clone()).collect();
+            cycle.push(next.clone());
+            return Err(cycle);
+        }
+        path.push(next);
+    }
+}
+
+/// Tarjan's strongly connected components over a dependency graph.
+struct Tarjan<'a> {
+    graph: &'a BTreeMap<String, BTreeSet<String>>,
+    index: BTreeMap<&'a String, usize>,
+    low_link: BTreeMap<&'a String, usize>,
+    stack: Vec<&'a String>,
+    components: Vec<Vec<&'a String>>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn connect(&mut self, node: &'a String) {
+        let index = self.index.len();
+        self.index.insert(node, index);
+        self.low_link.insert(node, index);
+        self.stack.push(node);
+
+        for dependency in &self.graph[node] {
+            if !self.index.contains_key(dependency) {
+                self.connect(dependency);
+                let low_link = self.low_link[node].min(self.low_link[dependency]);
+                self.low_link.insert(node, low_link);
+            } else if self.stack.contains(&dependency) {
+                let low_link = self.low_link[node].min(self.index[dependency]);
+                self.low_link.insert(node, low_link);
+            }
+        }
+
+        if self.low_link[node] == index {
+            let start = self
+                .stack
+                .iter()
+                .rposition(|member| *member == node)
+                .expect("the node is on the stack");
+            self.components.push(self.stack.split_off(start));
+        }
+    }
+}
+
+#[derive(Default)]
+struct AccessCollector {
+    access: ObjectAccess,
+    /// The CTE names in scope, which aren't objects.
+    ctes: Vec<String>,
+    /// The CTE whose query is being visited, if any.
+    cte: Option<String>,
+    /// Whether the next table visited is the target of a MERGE.
+    merge_target: bool,
+}
+
+impl AccessCollector {
+    fn read(&mut self, name: &ObjectName) {
+        self.read_object(object_name(name));
+    }
+
+    fn read_object(&mut self, name: String) {
+        self.access.reads.insert(name.clone());
+        self.input(Input::Object(name));
+    }
+
+    fn input(&mut self, input: Input) {
+        match &self.cte {
+            Some(cte) => self.access.ctes.entry(cte.clone()).or_default(),
+            None => &mut self.access.inputs,
+        }
+        .insert(input);
+    }
+
+    fn write(&mut self, name: &ObjectName) {
+        self.access.writes.insert(object_name(name));
+    }
+
+    fn create(&mut self, name: &ObjectName) {
+        self.access.creates.insert(object_name(name));
+    }
+
+    fn stage_name(stage: &StageRef) -> Option<String> {
+        match &stage.stage {
+            Stage::Named(name) => Some(format!("@{}", object_name(name))),
+            Stage::User | Stage::Table(_) => None,
+        }
+    }
+}
+
+impl Visitor for AccessCollector {
+    fn visit_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Insert { table_name, .. }
+            | Statement::Delete { table_name, .. }
+            | Statement::Truncate { table_name, .. } => self.write(table_name),
+            Statement::CreateTable { name, copy_of, .. } => {
+                self.create(name);
+                if let Some(copy_of) = copy_of {
+                    self.read(copy_of);
+                }
+            }
+            Statement::CreateView { name, .. } => self.create(name),
+            Statement::CreateStage { name, .. } => {
+                self.access
+                    .creates
+                    .insert(format!("@{}", object_name(name)));
+            }
+            Statement::CreateRoutine(routine) => {
+                self.create(&routine.name);
+                let mut body = AccessCollector::default();
+                visitor::walk_create_routine(&mut body, routine);
+                self.access.body = Some(Box::new(body.access));
+            }
+            Statement::Call { name, .. } => {
+                self.read(name);
+                self.access.calls.insert(object_name(name));
+            }
+            Statement::Put { stage, .. } => {
+                self.access.writes.extend(Self::stage_name(stage));
+            }
+            _ => {}
+        }
+
+        // The target of UPDATE is written, not read
+        match statement {
+            Statement::Update {
+                table,
+                assignments,
+                from,
+                selection,
+            } => {
+                if let TableFactor::Table { name, .. } = &table.relation {
+                    self.write(name);
+                }
+                for table in from {
+                    self.visit_table_with_joins(table);
+                }
+                for expr in assignments
+                    .iter()
+                    .map(|assignment| &assignment.value)
+                    .chain(selection)
+                {
+                    self.visit_expr(expr);
+                }
+            }
+            // The body is already in `access.body`
+            Statement::CreateRoutine(_) => {}
+            _ => visitor::walk_statement(self, statement),
+        }
+    }
+
+    /// The target of MERGE, visited first, is written rather than read.
+    fn visit_merge(&mut self, merge: &Merge) {
+        self.merge_target = true;
+        visitor::walk_merge(self, merge);
+    }
+
+    fn visit_query(&mut self, query: &Query) {
+        let len = self.ctes.len();
+        if let Some(with) = &query.with {
+            self.ctes
+                .extend(with.ctes.iter().map(|cte| normalize(&cte.alias)));
+        }
+        visitor::walk_query(self, query);
+        self.ctes.truncate(len);
+    }
+
+    fn visit_cte(&mut self, cte: &Cte) {
+        let name = normalize(&cte.alias);
+        self.access.ctes.entry(name.clone()).or_default();
+        let outer = self.cte.replace(name);
+        visitor::walk_cte(self, cte);
+        self.cte = outer;
+    }
+
+    fn visit_table_factor(&mut self, factor: &TableFactor) {
+        if std::mem::take(&mut self.merge_target) {
+            if let TableFactor::Table { name, .. } = factor {
+                return self.write(name);
+            }
+        }
+
+        match factor {
+            TableFactor::Table { name, .. } => match name.0.as_slice() {
+                [base] if self.ctes.contains(&normalize(base)) => {
+                    self.input(Input::Cte(normalize(base)))
+                }
+                _ => self.read(name),
+            },
+            TableFactor::Stage { stage, .. } => {
+                if let Some(stage) = Self::stage_name(stage) {
+                    self.read_object(stage);
+                }
+            }
+            _ => visitor::walk_table_factor(self, factor),
+        }
+    }
+
+    fn visit_copy_into(&mut self, copy: &CopyInto) {
+        match &copy.into {
+            CopyLocation::Table { name, .. } => self.write(name),
+            CopyLocation::Stage(stage) => self.access.writes.extend(Self::stage_name(stage)),
+            CopyLocation::External(_) | CopyLocation::Query(_) => {}
+        }
+        match &copy.from {
+            CopyLocation::Table { name, .. } => self.read(name),
+            CopyLocation::Stage(stage) => {
+                if let Some(stage) = Self::stage_name(stage) {
+                    self.read_object(stage);
+                }
+            }
+            CopyLocation::External(_) => {}
+            CopyLocation::Query(query) => self.visit_query(query),
+        }
+    }
+}
+
+/// The qualified name of an object, with its parts joined by `.`. Parts with
+/// a `.` or `"` in them are quoted, so that [`name_parts`] can tell the parts
+/// apart again.
+fn object_name(name: &ObjectName) -> String {
+    let parts = name.0.iter().map(|part| {
+        let part = normalize(part);
+        match part.contains(['.', '"']) {
+            true => format!("\"{}\"", part.replace('"', "\"\"")),
+            false => part,
+        }
+    });
+    parts.collect::<Vec<_>>().join(".")
+}
+
+/// The parts of a name of [`ObjectAccess`], e.g. `["RAW", "my.t"]` for
+/// `RAW."my.t"`.
+pub fn name_parts(name: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = name.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                parts.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            '.' if !quoted => parts.push(String::new()),
+            c => parts.last_mut().unwrap().push(c),
+        }
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use anyhow::Result;
+
+    use super::{name_parts, Cycle, DependencyGraph, ObjectAccess};
+    use crate::parser::parser::Parser;
+
+    fn names(objects: &std::collections::BTreeSet<String>) -> Vec<&str> {
+        objects.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn records_reads_and_writes() -> Result<()> {
+        let sql = "
+CREATE VIEW analytics.active AS
+WITH recent AS (SELECT * FROM raw.orders WHERE ts > CURRENT_DATE - 7)
+SELECT * FROM recent JOIN raw.customers c ON c.id = recent.customer_id;
+MERGE INTO dim.customers t USING (SELECT * FROM staging.customers) s ON t.id = s.id
+WHEN MATCHED AND s.deleted THEN DELETE
+WHEN MATCHED THEN UPDATE SET t.name = s.name
+WHEN NOT MATCHED THEN INSERT (id, name) VALUES (s.id, s.name);
+COPY INTO raw.orders FROM @raw.landing/orders/ FILE_FORMAT = (TYPE = CSV)";
+        let statements = Parser::from_sql(sql).parse_statements()?;
+        let [view, merge, copy] = [0, 1, 2].map(|i| ObjectAccess::of(&statements[i]));
+
+        assert_eq!(names(&view.reads), vec!["RAW.CUSTOMERS", "RAW.ORDERS"]);
+        assert_eq!(names(&view.creates), vec!["ANALYTICS.ACTIVE"]);
+        assert_eq!(names(&merge.reads), vec!["STAGING.CUSTOMERS"]);
+        assert_eq!(names(&merge.writes), vec!["DIM.CUSTOMERS"]);
+        assert_eq!(names(&copy.reads), vec!["@RAW.LANDING"]);
+        assert_eq!(names(&copy.writes), vec!["RAW.ORDERS"]);
+
+        let statement = Parser::from_sql("SELECT * FROM raw.\"my.t\"").parse_statement()?;
+        let access = ObjectAccess::of(&statement);
+        assert_eq!(names(&access.reads), vec!["RAW.\"my.t\""]);
+        assert_eq!(name_parts("RAW.\"my.t\""), vec!["RAW", "my.t"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn applies_routine_bodies_at_calls() -> Result<()> {
+        let mut graph = DependencyGraph::new();
+        graph.add_file(
+            "run.sql",
+            "SELECT * FROM b; CALL load(); CREATE VIEW v AS SELECT * FROM b;",
+        );
+        graph.add_file(
+            "procs.sql",
+            "CREATE PROCEDURE load() RETURNS INT LANGUAGE SQL AS $$\nBEGIN\n  INSERT INTO b SELECT * FROM a;\n  RETURN 1;\nEND;\n$$;",
+        );
+
+        // Creating the procedure writes nothing
+        let create = &graph.files[1].statements[0];
+        assert_eq!(names(&create.creates), vec!["LOAD"]);
+        assert!(create.reads.is_empty() && create.writes.is_empty());
+        let body = create.body.as_deref().expect("a parsed body");
+        assert_eq!(names(&body.writes), vec!["B"]);
+
+        // Calling it does
+        let dependencies = graph.dependencies();
+        assert_eq!(dependencies["B"], ["A", "LOAD"].map(String::from).into());
+        assert!(dependencies["LOAD"].is_empty());
+        assert_eq!(
+            graph.file_order()?,
+            vec![Path::new("procs.sql"), Path::new("run.sql")]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn resolves_partial_names_and_copies() -> Result<()> {
+        let mut graph = DependencyGraph::new();
+        graph.add_file(
+            "a.sql",
+            "CREATE VIEW analytics.v AS SELECT * FROM orders; CREATE TABLE c CLONE raw.orders;",
+        );
+        graph.add_file("b.sql", "CREATE TABLE raw.orders (id INT);");
+
+        assert_eq!(
+            graph.file_order()?,
+            vec![Path::new("b.sql"), Path::new("a.sql")]
+        );
+        assert_eq!(
+            graph.object_order()?,
+            vec!["RAW.ORDERS", "ANALYTICS.V", "C"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn orders_files_and_detects_cycles() -> Result<()> {
+        let mut graph = DependencyGraph::new();
+        graph.add_file(
+            "views.sql",
+            "CREATE VIEW v AS SELECT * FROM t JOIN u ON t.id = u.id;",
+        );
+        graph.add_file("load.sql", "INSERT INTO t SELECT * FROM staging;");
+        graph.add_file(
+            "tables.sql",
+            "CREATE TABLE t (id INT); CREATE TABLE u AS SELECT 1 AS id;",
+        );
+
+        assert_eq!(
+            graph.file_order()?,
+            vec![
+                Path::new("tables.sql"),
+                Path::new("load.sql"),
+                Path::new("views.sql")
+            ]
+        );
+        assert_eq!(graph.object_order()?, vec!["STAGING", "T", "U", "V"]);
+        assert!(graph.cycles().is_empty());
+
+        graph.add_file("loop.sql", "CREATE VIEW t AS SELECT * FROM v;");
+        assert_eq!(graph.cycles(), vec![vec!["T", "V"]]);
+        assert_eq!(
+            graph.object_order(),
+            Err(Cycle(vec!["T".into(), "V".into(), "T".into()]))
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,356 @@
+//! A small JSON document model for the machine-readable outputs and inputs.
+//!
+//! `{}` writes a document on one line and `{:#}` indents it by two spaces.
+//! Object keys keep their insertion order so output is stable.
+
+use std::fmt::{self, Write};
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use anyhow::{anyhow, bail, Result};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn object<K: Into<String>>(entries: impl IntoIterator<Item = (K, Json)>) -> Self {
+        Json::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect(),
+        )
+    }
+
+    pub fn array<T: Into<Json>>(values: impl IntoIterator<Item = T>) -> Self {
+        Json::Array(values.into_iter().map(Into::into).collect())
+    }
+
+    /// Parses one JSON document, which may be surrounded by whitespace.
+    pub fn parse(text: &str) -> Result<Json> {
+        let mut reader = Reader {
+            text,
+            chars: text.char_indices().peekable(),
+        };
+        let json = reader.read_value()?;
+        reader.skip_whitespace();
+        match reader.chars.next() {
+            Some((position, c)) => bail!("Unexpected {:?} at offset {}", c, position),
+            None => Ok(json),
+        }
+    }
+
+    /// The value of `key` when this is an object with it.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter, indent: Option<usize>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(value) if !value.is_finite() => f.write_str("null"),
+            Json::Number(value) => write!(f, "{}", value),
+            Json::String(value) => write_string(f, value),
+            Json::Array(values) if values.is_empty() => f.write_str("[]"),
+            Json::Object(entries) if entries.is_empty() => f.write_str("{}"),
+            Json::Array(values) => {
+                f.write_char('[')?;
+                for (i, value) in values.iter().enumerate() {
+                    separator(f, i, indent.map(|indent| indent + 1))?;
+                    value.write(f, indent.map(|indent| indent + 1))?;
+                }
+                separator(f, usize::MAX, indent)?;
+                f.write_char(']')
+            }
+            Json::Object(entries) => {
+                f.write_char('{')?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    separator(f, i, indent.map(|indent| indent + 1))?;
+                    write_string(f, key)?;
+                    f.write_str(if indent.is_some() { ": " } else { ":" })?;
+                    value.write(f, indent.map(|indent| indent + 1))?;
+                }
+                separator(f, usize::MAX, indent)?;
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, f.alternate().then_some(0))
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(value.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Json::String(value)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Json::Bool(value)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Self {
+        Json::Number(value as f64)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+/// Writes the comma before the `i`th element, `usize::MAX` meaning the
+/// closing bracket, and the line break when indenting.
+fn separator(f: &mut fmt::Formatter, i: usize, indent: Option<usize>) -> fmt::Result {
+    if i != 0 && i != usize::MAX {
+        f.write_char(',')?;
+    }
+    if let Some(indent) = indent {
+        write!(f, "\n{:width$}", "", width = indent * 2)?;
+    }
+    Ok(())
+}
+
+fn write_string(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c < ' ' => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+struct Reader<'a> {
+    text: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl Reader<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn offset(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map_or(self.text.len(), |(position, _)| *position)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some((_, c)) if c == expected => Ok(()),
+            Some((position, c)) => bail!(
+                "Expected {:?} at offset {}, found {:?}",
+                expected,
+                position,
+                c
+            ),
+            None => bail!("Expected {:?}, found the end of input", expected),
+        }
+    }
+
+    fn read_value(&mut self) -> Result<Json> {
+        self.skip_whitespace();
+        let start = self.offset();
+        match self.chars.peek().map(|(_, c)| *c) {
+            Some('{') => {
+                self.chars.next();
+                let mut entries = Vec::new();
+                if self.read_end('}') {
+                    return Ok(Json::Object(entries));
+                }
+                loop {
+                    self.expect('"')?;
+                    let key = self.read_string()?;
+                    self.expect(':')?;
+                    entries.push((key, self.read_value()?));
+                    if self.read_end('}') {
+                        return Ok(Json::Object(entries));
+                    }
+                    self.expect(',')?;
+                }
+            }
+            Some('[') => {
+                self.chars.next();
+                let mut values = Vec::new();
+                if self.read_end(']') {
+                    return Ok(Json::Array(values));
+                }
+                loop {
+                    values.push(self.read_value()?);
+                    if self.read_end(']') {
+                        return Ok(Json::Array(values));
+                    }
+                    self.expect(',')?;
+                }
+            }
+            Some('"') => {
+                self.chars.next();
+                Ok(Json::String(self.read_string()?))
+            }
+            Some(_) => {
+                while self
+                    .chars
+                    .next_if(|(_, c)| c.is_ascii_alphanumeric() || "+-.".contains(*c))
+                    .is_some()
+                {}
+                let word = &self.text[start..self.offset()];
+                match word {
+                    "null" => Ok(Json::Null),
+                    "true" => Ok(Json::Bool(true)),
+                    "false" => Ok(Json::Bool(false)),
+                    _ => word
+                        .parse()
+                        .map(Json::Number)
+                        .map_err(|_| anyhow!("Unexpected {:?} at offset {}", word, start)),
+                }
+            }
+            None => bail!("Expected a value, found the end of input"),
+        }
+    }
+
+    /// Consumes `end` if it's next, after any whitespace.
+    fn read_end(&mut self, end: char) -> bool {
+        self.skip_whitespace();
+        self.chars.next_if(|(_, c)| *c == end).is_some()
+    }
+
+    /// Reads the rest of a string whose opening quote was consumed.
+    fn read_string(&mut self) -> Result<String> {
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(value),
+                Some((_, '\\')) => {
+                    let escaped = match self.chars.next() {
+                        Some((_, 'n')) => '\n',
+                        Some((_, 't')) => '\t',
+                        Some((_, 'r')) => '\r',
+                        Some((_, 'b')) => '\u{8}',
+                        Some((_, 'f')) => '\u{c}',
+                        Some((_, 'u')) => self.read_unicode_escape()?,
+                        Some((_, c @ ('"' | '\\' | '/'))) => c,
+                        Some((position, c)) => {
+                            bail!("Invalid escape {:?} at offset {}", c, position)
+                        }
+                        None => break,
+                    };
+                    value.push(escaped);
+                }
+                Some((_, c)) => value.push(c),
+                None => break,
+            }
+        }
+        bail!("Unterminated string")
+    }
+
+    /// Reads the digits of `\uXXXX`, and of the low surrogate following a
+    /// high one.
+    fn read_unicode_escape(&mut self) -> Result<char> {
+        let start = self.offset();
+        let mut code = self.read_hex()?;
+        if (0xd800..0xdc00).contains(&code) {
+            self.expect('\\')?;
+            self.expect('u')?;
+            let low = self.read_hex()?;
+            code = 0x10000 + ((code - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff);
+        }
+        char::from_u32(code).ok_or_else(|| anyhow!("Invalid escape at offset {}", start))
+    }
+
+    fn read_hex(&mut self) -> Result<u32> {
+        let start = self.offset();
+        let digits: String = (0..4)
+            .filter_map(|_| self.chars.next())
+            .map(|(_, c)| c)
+            .collect();
+        u32::from_str_radix(&digits, 16)
+            .map_err(|_| anyhow!("Invalid escape {:?} at offset {}", digits, start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Json;
+
+    #[test]
+    fn writes_compact_and_indented_documents() {
+        let json = Json::object([
+            ("name", Json::from("a \"quoted\"\n\u{1}name")),
+            ("count", Json::from(3)),
+            ("ratio", Json::Number(0.5)),
+            ("tags", Json::array(["x", "y"])),
+            ("empty", Json::Array(vec![])),
+            ("missing", Json::from(None::<&str>)),
+        ]);
+
+        assert_eq!(
+            json.to_string(),
+            r#"{"name":"a \"quoted\"\n\u0001name","count":3,"ratio":0.5,"tags":["x","y"],"empty":[],"missing":null}"#
+        );
+        assert_eq!(
+            format!("{:#}", Json::object([("tags", Json::array(["x"]))])),
+            "{\n  \"tags\": [\n    \"x\"\n  ]\n}"
+        );
+        assert_eq!(Json::parse(&format!("{:#}", json)).unwrap(), json);
+    }
+
+    #[test]
+    fn parses_documents() {
+        let json = Json::parse(
+            r#" {"a": [1, -2.5e1, true, null], "b": {}, "c": "\u00e9\ud83d\ude00\/"} "#,
+        )
+        .unwrap();
+        assert_eq!(
+            json.get("a"),
+            Some(&Json::Array(vec![
+                Json::Number(1.0),
+                Json::Number(-25.0),
+                Json::Bool(true),
+                Json::Null
+            ]))
+        );
+        assert_eq!(json.get("b"), Some(&Json::Object(vec![])));
+        assert_eq!(json.get("c"), Some(&Json::from("é😀/")));
+
+        for invalid in ["", "[1,]", "{\"a\" 1}", "\"open", "[1] 2", "nul"] {
+            assert!(Json::parse(invalid).is_err(), "{:?} should fail", invalid);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+/// Snowflake's reserved keywords, which can't be used as unquoted identifiers,
+/// in alphabetical order. Every other keyword is contextual and may name a
+/// column, table or alias.
+pub const RESERVED_KEYWORDS: [&str; 91] = [
+    "ACCOUNT",
+    "ALL",
+    "ALTER",
+    "AND",
+    "ANY",
+    "AS",
+    "BETWEEN",
+    "BY",
+    "CASE",
+    "CAST",
+    "CHECK",
+    "COLUMN",
+    "CONNECT",
+    "CONNECTION",
+    "CONSTRAINT",
+    "CREATE",
+    "CROSS",
+    "CURRENT",
+    "CURRENT_DATE",
+    "CURRENT_TIME",
+    "CURRENT_TIMESTAMP",
+    "CURRENT_USER",
+    "DATABASE",
+    "DELETE",
+    "DISTINCT",
+    "DROP",
+    "ELSE",
+    "EXISTS",
+    "FALSE",
+    "FOLLOWING",
+    "FOR",
+    "FROM",
+    "FULL",
+    "GRANT",
+    "GROUP",
+    "GSCLUSTER",
+    "HAVING",
+    "ILIKE",
+    "IN",
+    "INCREMENT",
+    "INNER",
+    "INSERT",
+    "INTERSECT",
+    "INTO",
+    "IS",
+    "ISSUE",
+    "JOIN",
+    "LATERAL",
+    "LEFT",
+    "LIKE",
+    "LOCALTIME",
+    "LOCALTIMESTAMP",
+    "MINUS",
+    "NATURAL",
+    "NOT",
+    "NULL",
+    "OF",
+    "ON",
+    "OR",
+    "ORDER",
+    "ORGANIZATION",
+    "QUALIFY",
+    "REGEXP",
+    "REVOKE",
+    "RIGHT",
+    "RLIKE",
+    "ROW",
+    "ROWS",
+    "SAMPLE",
+    "SCHEMA",
+    "SELECT",
+    "SET",
+    "SOME",
+    "START",
+    "TABLE",
+    "TABLESAMPLE",
+    "THEN",
+    "TO",
+    "TRIGGER",
+    "TRUE",
+    "TRY_CAST",
+    "UNION",
+    "UNIQUE",
+    "UPDATE",
+    "USING",
+    "VALUES",
+    "VIEW",
+    "WHEN",
+    "WHENEVER",
+    "WHERE",
+    "WITH",
+];
+
+/// Whether `word` is reserved, looked up once per word by the lexer.
+pub fn is_reserved(word: &str) -> bool {
+    RESERVED_KEYWORDS
+        .binary_search_by(|keyword| {
+            let keyword = keyword.bytes();
+            keyword.cmp(word.bytes().map(|byte| byte.to_ascii_uppercase()))
+        })
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_reserved, RESERVED_KEYWORDS};
+
+    #[test]
+    fn looks_up_reserved_keywords() {
+        assert!(RESERVED_KEYWORDS.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(RESERVED_KEYWORDS.iter().all(|keyword| is_reserved(keyword)));
+        assert!(is_reserved("Natural") && is_reserved("current_date"));
+        assert!(!is_reserved("date") && !is_reserved("currents"));
+    }
+}
@@ -1,59 +1,89 @@
-use anyhow::Result;
+use std::borrow::Cow;
 
-#[allow(dead_code)]
+use anyhow::{bail, Result};
+
+use crate::lexer::keywords;
+
+#[allow(dead_code, clippy::upper_case_acronyms)]
 #[derive(Clone, Debug, PartialEq)]
-pub enum Token {
+pub enum Token<'a> {
     Illegal,
-    Ident(String),
+    /// An identifier or keyword. Whether a keyword is reserved in a given
+    /// position is up to the parser.
+    Word(Word<'a>),
 
     // Literals
-    Int(i64),
+    /// An integer literal. `i128` covers every NUMBER(38, 0) value.
+    Int(i128),
+    /// An exact decimal literal such as `1.50` or `.5`, kept as written so no
+    /// precision is lost. Integers too large for `i128` also end up here.
+    Decimal(Cow<'a, str>),
+    /// A literal in scientific notation such as `1.5E-3`.
     Float(f64),
     Bool(bool),
-    // Date(String),
-    Varchar(String),
+    Varchar(StringLiteral<'a>),
+    /// A hex binary literal such as `X'48656C6C6F'`.
+    HexString(HexLiteral<'a>),
+    /// The content of a `$$ ... $$` literal, without the delimiters. When the
+    /// parser knows the content is SQL (e.g. a `LANGUAGE SQL` procedure body),
+    /// it lexes it again on its own.
+    DollarString(Cow<'a, str>),
     Null,
 
-    // Data Language
-    DDL(DDLKeyword),
-    DML(DMLKeyword),
-
-    // Data Types
-    DataType(DataType),
-
-    // Join Types
-    JoinType(JoinType),
+    // Variables
+    /// A positional column reference such as `$1`, used when querying stages.
+    Positional(u32),
+    /// A session variable such as `$my_var`.
+    SessionVariable(Cow<'a, str>),
 
-    // Functions
-    ColumnFunction(Function),
-
-    // Logical
-    Logical(Logical),
+    // Stages
+    /// A stage reference without its `@`, such as `my_stage/path/`, `~/` or
+    /// `%my_table`.
+    StageRef(Cow<'a, str>),
+    /// A local file URI in `PUT`/`GET`, such as `file:///tmp/data.csv`.
+    FileUri(Cow<'a, str>),
 
     // Operators
+    Ampersand,
+    /// `->`, the arrow of a lambda in higher-order functions
+    Arrow,
     Assign,
     Asterisk,
+    Caret,
+    DoubleColon,
+    /// `=>`, used for named arguments
+    FatArrow,
+    /// `->>`, Snowflake's flow operator chaining statements
+    Flow,
     Minus,
     Modulo,
+    Pipe,
     Plus,
+    /// `?`, a bind parameter
+    QuestionMark,
     Slash,
+    StringConcat,
+    Tilde,
 
     // Delimiters
     OpenParen,
     CloseParen,
+    OpenBracket,
+    CloseBracket,
+    OpenBrace,
+    CloseBrace,
+    At,
     Comma,
     Period,
     Semicolon,
     Colon,
     Dollar,
-    DollarDelimiter,
     SingleQuote,
-    DoubleQuote,
     ExclamationPoint,
 
     // Comments
-    BlockComment(String),
-    InlineComment(String),
+    BlockComment(Cow<'a, str>),
+    InlineComment(Cow<'a, str>),
 
     // Comparisons
     Equal,
@@ -63,34 +93,266 @@ pub enum Token {
     LessThanEqual,
     NotEqual,
 
-    // Keywords
+    // End of file
+    EOF,
+}
+
+impl Token<'_> {
+    /// Copies whatever the token borrows from the source, so it can outlive it.
+    pub fn into_owned(self) -> Token<'static> {
+        match self {
+            Token::Illegal => Token::Illegal,
+            Token::Word(word) => Token::Word(word.into_owned()),
+            Token::Int(value) => Token::Int(value),
+            Token::Decimal(value) => Token::Decimal(owned(value)),
+            Token::Float(value) => Token::Float(value),
+            Token::Bool(value) => Token::Bool(value),
+            Token::Varchar(literal) => Token::Varchar(StringLiteral {
+                raw: owned(literal.raw),
+                value: owned(literal.value),
+            }),
+            Token::HexString(literal) => Token::HexString(HexLiteral {
+                raw: owned(literal.raw),
+                value: literal.value,
+            }),
+            Token::DollarString(value) => Token::DollarString(owned(value)),
+            Token::Null => Token::Null,
+            Token::Positional(position) => Token::Positional(position),
+            Token::SessionVariable(name) => Token::SessionVariable(owned(name)),
+            Token::StageRef(stage) => Token::StageRef(owned(stage)),
+            Token::FileUri(uri) => Token::FileUri(owned(uri)),
+            Token::Ampersand => Token::Ampersand,
+            Token::Arrow => Token::Arrow,
+            Token::Assign => Token::Assign,
+            Token::Asterisk => Token::Asterisk,
+            Token::Caret => Token::Caret,
+            Token::DoubleColon => Token::DoubleColon,
+            Token::FatArrow => Token::FatArrow,
+            Token::Flow => Token::Flow,
+            Token::Minus => Token::Minus,
+            Token::Modulo => Token::Modulo,
+            Token::Pipe => Token::Pipe,
+            Token::Plus => Token::Plus,
+            Token::QuestionMark => Token::QuestionMark,
+            Token::Slash => Token::Slash,
+            Token::StringConcat => Token::StringConcat,
+            Token::Tilde => Token::Tilde,
+            Token::OpenParen => Token::OpenParen,
+            Token::CloseParen => Token::CloseParen,
+            Token::OpenBracket => Token::OpenBracket,
+            Token::CloseBracket => Token::CloseBracket,
+            Token::OpenBrace => Token::OpenBrace,
+            Token::CloseBrace => Token::CloseBrace,
+            Token::At => Token::At,
+            Token::Comma => Token::Comma,
+            Token::Period => Token::Period,
+            Token::Semicolon => Token::Semicolon,
+            Token::Colon => Token::Colon,
+            Token::Dollar => Token::Dollar,
+            Token::SingleQuote => Token::SingleQuote,
+            Token::ExclamationPoint => Token::ExclamationPoint,
+            Token::BlockComment(comment) => Token::BlockComment(owned(comment)),
+            Token::InlineComment(comment) => Token::InlineComment(owned(comment)),
+            Token::Equal => Token::Equal,
+            Token::GreaterThan => Token::GreaterThan,
+            Token::GreaterThanEqual => Token::GreaterThanEqual,
+            Token::LessThan => Token::LessThan,
+            Token::LessThanEqual => Token::LessThanEqual,
+            Token::NotEqual => Token::NotEqual,
+            Token::EOF => Token::EOF,
+        }
+    }
+}
+
+/// Describes the token for error messages, e.g. `` `FROM` `` or `end of input`.
+impl std::fmt::Display for Token<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Token::Illegal => return write!(f, "an illegal token"),
+            Token::Word(Word {
+                value,
+                quote_style: Some(quote),
+                ..
+            }) => return write!(f, "`{quote}{value}{quote}`"),
+            Token::Word(word) => return write!(f, "`{}`", word.value),
+            Token::Int(value) => return write!(f, "`{value}`"),
+            Token::Decimal(value) => return write!(f, "`{value}`"),
+            Token::Float(value) => return write!(f, "`{value:E}`"),
+            Token::Bool(value) => return write!(f, "`{}`", value.to_string().to_uppercase()),
+            Token::Varchar(literal) => return write!(f, "`{}`", literal.raw),
+            Token::HexString(literal) => return write!(f, "`{}`", literal.raw),
+            Token::DollarString(_) => return write!(f, "a `$$` string"),
+            Token::Null => "NULL",
+            Token::Positional(position) => return write!(f, "`${position}`"),
+            Token::SessionVariable(name) => return write!(f, "`${name}`"),
+            Token::StageRef(stage) => return write!(f, "`@{stage}`"),
+            Token::FileUri(uri) => return write!(f, "`{uri}`"),
+            Token::BlockComment(_) | Token::InlineComment(_) => return write!(f, "a comment"),
+            Token::EOF => return write!(f, "end of input"),
+            Token::Ampersand => "&",
+            Token::Arrow => "->",
+            Token::Assign => ":=",
+            Token::Asterisk => "*",
+            Token::Caret => "^",
+            Token::DoubleColon => "::",
+            Token::FatArrow => "=>",
+            Token::Flow => "->>",
+            Token::Minus => "-",
+            Token::Modulo => "%",
+            Token::Pipe => "|",
+            Token::Plus => "+",
+            Token::QuestionMark => "?",
+            Token::Slash => "/",
+            Token::StringConcat => "||",
+            Token::Tilde => "~",
+            Token::OpenParen => "(",
+            Token::CloseParen => ")",
+            Token::OpenBracket => "[",
+            Token::CloseBracket => "]",
+            Token::OpenBrace => "{",
+            Token::CloseBrace => "}",
+            Token::At => "@",
+            Token::Comma => ",",
+            Token::Period => ".",
+            Token::Semicolon => ";",
+            Token::Colon => ":",
+            Token::Dollar => "$",
+            Token::SingleQuote => "'",
+            Token::ExclamationPoint => "!",
+            Token::Equal => "=",
+            Token::GreaterThan => ">",
+            Token::GreaterThanEqual => ">=",
+            Token::LessThan => "<",
+            Token::LessThanEqual => "<=",
+            Token::NotEqual => "!=",
+        };
+        write!(f, "`{text}`")
+    }
+}
+
+fn owned(value: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(value.into_owned())
+}
+
+/// An identifier or keyword, as written in the source.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Word<'a> {
+    /// The word without its quotes, with `""` resolved in quoted identifiers.
+    pub value: Cow<'a, str>,
+    /// `Some('"')` for a quoted identifier such as `"My Column"`.
+    pub quote_style: Option<char>,
+    /// The keyword an unquoted word matches, reserved or not.
+    pub keyword: Option<Keyword>,
+    /// Whether the word is one of Snowflake's reserved keywords, which can
+    /// only be used as an identifier when quoted. Many aren't a [`Keyword`].
+    pub reserved: bool,
+}
+
+impl<'a> Word<'a> {
+    /// An unquoted word, classified the way the lexer would.
+    pub fn new(value: impl Into<Cow<'a, str>>) -> Self {
+        let value = value.into();
+        Word {
+            keyword: Lexer::string_to_keyword(&value),
+            reserved: keywords::is_reserved(&value),
+            value,
+            quote_style: None,
+        }
+    }
+
+    pub fn into_owned(self) -> Word<'static> {
+        Word {
+            value: owned(self.value),
+            quote_style: self.quote_style,
+            keyword: self.keyword,
+            reserved: self.reserved,
+        }
+    }
+
+    /// Whether the word is one of Snowflake's reserved keywords, which can only
+    /// be used as an identifier when quoted.
+    pub fn is_reserved(&self) -> bool {
+        self.reserved
+    }
+
+    /// Whether the word can name a column, which join keywords such as `LEFT`
+    /// can despite being reserved. They still can't name a table or alias.
+    pub fn is_column_name(&self) -> bool {
+        !self.reserved
+            || matches!(
+                self.keyword,
+                Some(
+                    Keyword::Cross
+                        | Keyword::Full
+                        | Keyword::Join
+                        | Keyword::Lateral
+                        | Keyword::JoinType(_)
+                )
+            )
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Keyword {
+    // Data Language
+    DDL(DDLKeyword),
+    DML(DMLKeyword),
+
+    // Data Types
+    DataType(DataTypeKeyword),
+
+    // Join Types
+    JoinType(JoinType),
+
+    // Logical
+    Logical(Logical),
+
     All,
     As,
     Between,
     By,
     Caller,
     Case,
+    Cast,
+    Cross,
     Distinct,
+    Else,
+    End,
     Except,
     Execute,
+    Exists,
+    For,
     From,
+    Full,
     Function,
     Group,
     Having,
+    If,
+    Ilike,
+    In,
+    Intersect,
+    Into,
+    Is,
     Join,
     Language,
+    Lateral,
     Like,
     Limit,
     On,
     Order,
     Over,
     Procedure,
+    Qualify,
     Return,
     Returns,
     Set,
     Temporary,
+    Then,
     Top,
     Union,
+    Using,
+    Values,
     When,
     Where,
 
@@ -98,13 +360,99 @@ pub enum Token {
     View,
     Materialized,
     Table,
+}
 
-    // End of file
-    EOF,
+/// Writes the keyword as it's written in SQL, e.g. `` `BY` ``.
+impl std::fmt::Display for Keyword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Keyword::DDL(keyword) => format!("{:?}", keyword),
+            Keyword::DML(keyword) => format!("{:?}", keyword),
+            Keyword::DataType(keyword) => format!("{:?}", keyword),
+            Keyword::JoinType(keyword) => format!("{:?}", keyword),
+            Keyword::Logical(keyword) => format!("{:?}", keyword),
+            keyword => format!("{:?}", keyword),
+        };
+        write!(f, "`{}`", name.to_uppercase())
+    }
 }
 
-#[allow(dead_code)]
+/// A byte range in the source.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A token along with where it was found in the source.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpannedToken<'a> {
+    pub token: Token<'a>,
+    pub span: Span,
+}
+
+/// A 1-based line and column in the source. Columns count characters, not
+/// bytes, so they line up with what an editor shows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    /// The location of a byte offset in `source`.
+    pub fn of(source: &str, offset: usize) -> Self {
+        let before = &source[..source.floor_char_boundary(offset)];
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        Location {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// A token that could not be lexed, such as an unterminated string. The span
+/// runs from the start of the token to where lexing gave up, and `location` is
+/// where it starts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LexError {
+    pub message: String,
+    pub span: Span,
+    pub location: Location,
+}
+
+impl std::fmt::Display for LexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", self.message, self.location)
+    }
+}
+
+impl std::error::Error for LexError {}
+
+/// A single-quoted string literal, optionally prefixed with `E`.
 #[derive(Clone, Debug, PartialEq)]
+pub struct StringLiteral<'a> {
+    /// The literal as written in the source, including quotes and prefix.
+    pub raw: Cow<'a, str>,
+    /// The value with doubled quotes and backslash escapes resolved. It only
+    /// needs its own copy when there was something to resolve.
+    pub value: Cow<'a, str>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HexLiteral<'a> {
+    pub raw: Cow<'a, str>,
+    pub value: Vec<u8>,
+}
+
+#[allow(dead_code, clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DDLKeyword {
     Alter,
     Create,
@@ -115,8 +463,8 @@ pub enum DDLKeyword {
     With,
 }
 
-#[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq)]
+#[allow(dead_code, clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DMLKeyword {
     Call,
     Delete,
@@ -126,25 +474,7 @@ pub enum DMLKeyword {
 }
 
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq)]
-pub enum Function {
-    Avg,
-    Cast,
-    Concat,
-    Count,
-    Max,
-    Min,
-    Sum,
-
-    DenseRank,
-    Lag,
-    Lead,
-    Rank,
-    RowNumber,
-}
-
-#[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum JoinType {
     Inner,
     Left,
@@ -154,7 +484,7 @@ pub enum JoinType {
 }
 
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Logical {
     And,
     Or,
@@ -162,8 +492,8 @@ pub enum Logical {
 }
 
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq)]
-pub enum DataType {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataTypeKeyword {
     Number,
     Int,
     BigInt,
@@ -175,327 +505,788 @@ pub enum DataType {
     Char,    // can be called with CHAR, CHARACTER, NCHAR
     Binary,  // can be called with BINARY, VARBINARY
     Boolean,
-    // Date, Time, TimestampLTZ, TimestampTZ, TimestampNTZ - TODO: Add date/time types
 }
 
-pub struct Lexer {
-    input: Vec<u8>,
+/// Lexes a borrowed source, so that tokens can point into it rather than copy
+/// it. Positions are byte offsets, always on character boundaries.
+/// [`Token::into_owned`] detaches a token when it has to outlive the source.
+///
+/// As an [`Iterator`], it lexes one token at a time, so callers can stop
+/// early without reading the whole input. Wrap it in [`std::iter::Peekable`]
+/// to look ahead.
+pub struct Lexer<'a> {
+    source: &'a str,
+    input: &'a [u8],
     position: usize,
     read_position: usize,
-    ch: u8,
+    ch: char,
+    /// Set once EOF or an error has been yielded, so that iteration stops.
+    done: bool,
 }
 
-impl Lexer {
-    pub fn new(input: String) -> Self {
+impl<'a> Lexer<'a> {
+    pub fn new(source: &'a str) -> Self {
         let mut lexer = Lexer {
             position: 0,
             read_position: 0,
-            ch: 0,
-            input: input.into_bytes(),
+            ch: '\0',
+            done: false,
+            source,
+            input: source.as_bytes(),
         };
 
         lexer.read_char();
-        return lexer;
+        lexer
     }
 
-    fn next_token(&mut self) -> Result<Token> {
+    fn next_token(&mut self) -> Result<Token<'a>> {
         self.skip_whitespace();
 
         let token = match self.ch {
-            b'+' => Token::Plus,
-            b'%' => Token::Modulo,
-            b'<' => {
-                if self.peek_char() == b'=' {
+            '+' => Token::Plus,
+            '%' => Token::Modulo,
+            '&' => Token::Ampersand,
+            '^' => Token::Caret,
+            '~' => Token::Tilde,
+            '?' => Token::QuestionMark,
+            '|' => {
+                if self.peek_char() == '|' {
                     self.read_char();
-                    Token::LessThanEqual
+                    Token::StringConcat
                 } else {
-                    Token::LessThan
+                    Token::Pipe
                 }
             }
-            b'>' => {
-                if self.peek_char() == b'=' {
+            '<' => match self.peek_char() {
+                '=' => {
+                    self.read_char();
+                    Token::LessThanEqual
+                }
+                '>' => {
+                    self.read_char();
+                    Token::NotEqual
+                }
+                _ => Token::LessThan,
+            },
+            '>' => {
+                if self.peek_char() == '=' {
                     self.read_char();
                     Token::GreaterThanEqual
                 } else {
                     Token::GreaterThan
                 }
             }
-            b'-' => {
-                if self.peek_char() == b'-' {
-                    Token::InlineComment(self.read_inline_comment())
-                } else {
-                    Token::Minus
+            '-' => match self.peek_char() {
+                '-' => Token::InlineComment(self.read_inline_comment().into()),
+                '>' => {
+                    self.read_char();
+                    if self.peek_char() == '>' {
+                        self.read_char();
+                        Token::Flow
+                    } else {
+                        Token::Arrow
+                    }
                 }
-            }
-            b'!' => {
-                if self.peek_char() == b'=' {
+                _ => Token::Minus,
+            },
+            '!' => {
+                if self.peek_char() == '=' {
                     self.read_char();
                     Token::NotEqual
                 } else {
                     Token::ExclamationPoint
                 }
             }
-            b'*' => Token::Asterisk,
-            b'=' => Token::Equal,
-            b'/' => {
-                if self.peek_char() == b'*' {
-                    Token::BlockComment(self.read_block_comment())
-                } else if self.peek_char() == b'/' {
-                    Token::InlineComment(self.read_inline_comment())
+            '*' => Token::Asterisk,
+            '=' => {
+                if self.peek_char() == '>' {
+                    self.read_char();
+                    Token::FatArrow
+                } else {
+                    Token::Equal
+                }
+            }
+            '/' => {
+                if self.peek_char() == '*' {
+                    return Ok(Token::BlockComment(self.read_block_comment()?.into()));
+                } else if self.peek_char() == '/' {
+                    Token::InlineComment(self.read_inline_comment().into())
                 } else {
                     Token::Slash
                 }
             }
 
-            b'(' => Token::OpenParen,
-            b')' => Token::CloseParen,
-            b',' => Token::Comma,
-            b'.' => Token::Period,
-            b';' => Token::Semicolon,
-            b'$' => {
-                if self.peek_char() == b'$' {
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            '[' => Token::OpenBracket,
+            ']' => Token::CloseBracket,
+            '{' => Token::OpenBrace,
+            '}' => Token::CloseBrace,
+            '@' => match self.peek_char() {
+                ch if is_ident_start(ch) || matches!(ch, '~' | '%' | '"') => {
                     self.read_char();
-                    Token::DollarDelimiter
-                } else {
-                    Token::Dollar
+                    return Ok(Token::StageRef(self.read_stage_ref()?.into()));
                 }
-            }
-            b':' => {
-                if self.peek_char() == b'=' {
+                _ => Token::At,
+            },
+            ',' => Token::Comma,
+            '.' if self.peek_char().is_ascii_digit() => return self.read_number(),
+            '.' => Token::Period,
+            ';' => Token::Semicolon,
+            '$' => match self.peek_char() {
+                '$' => Token::DollarString(self.read_dollar_string()?.into()),
+                '0'..='9' => {
+                    self.read_char();
+                    let position = self.read_digits();
+                    return match position.parse() {
+                        Ok(position) => Ok(Token::Positional(position)),
+                        Err(_) => bail!("Invalid positional reference ${}", position),
+                    };
+                }
+                ch if is_ident_start(ch) => {
+                    self.read_char();
+                    return Ok(Token::SessionVariable(self.read_ident().into()));
+                }
+                _ => Token::Dollar,
+            },
+            ':' => match self.peek_char() {
+                '=' => {
                     self.read_char();
                     Token::Assign
-                } else {
-                    Token::Colon
                 }
+                ':' => {
+                    self.read_char();
+                    Token::DoubleColon
+                }
+                _ => Token::Colon,
+            },
+            '\'' => {
+                let start = self.position;
+                return Ok(Token::Varchar(self.read_varchar(start)?));
             }
-            b'\'' => Token::Varchar(self.read_varchar()),
-            b'"' => Token::DoubleQuote,
-            b'a'..=b'z' | b'A'..=b'Z' | b'_' => {
-                let ident = self.read_ident();
-                let lower_ident = ident.clone().to_lowercase();
-                let ident_type = self.string_to_token(&lower_ident);
-                return match ident_type {
-                    Some(ident_type) => Ok(ident_type),
-                    None => Ok(Token::Ident(ident)),
-                };
+            '"' => return Ok(Token::Word(self.read_quoted_ident()?)),
+            'x' | 'X' if self.peek_char() == '\'' => return self.read_hex_string(),
+            'e' | 'E' if self.peek_char() == '\'' => {
+                let start = self.position;
+                self.read_char();
+                return Ok(Token::Varchar(self.read_varchar(start)?));
             }
-            b'0'..=b'9' => {
-                let potential_number = self.read_number();
-                let ret_tok = match &potential_number.chars().filter(|ch| ch == &'.').count() {
-                    0 => Token::Int(potential_number.parse().expect("Expected an int value")),
-                    1 => Token::Float(potential_number.parse().expect("Expected an float value")),
-                    _ => Token::Illegal,
-                };
-
-                return Ok(ret_tok);
+            ch if is_ident_start(ch) => {
+                let start = self.position;
+                let ident = self.read_ident();
+                if ident.eq_ignore_ascii_case("file")
+                    && self.input[self.position..].starts_with(b"://")
+                {
+                    return Ok(Token::FileUri(self.read_file_uri(start).into()));
+                }
+                return Ok(if ident.eq_ignore_ascii_case("true") {
+                    Token::Bool(true)
+                } else if ident.eq_ignore_ascii_case("false") {
+                    Token::Bool(false)
+                } else if ident.eq_ignore_ascii_case("null") {
+                    Token::Null
+                } else {
+                    Token::Word(Word::new(ident))
+                });
             }
-            0 => Token::EOF,
-            _ => todo!(),
+            '0'..='9' => return self.read_number(),
+            '\0' if self.position >= self.input.len() => Token::EOF,
+            ch => bail!("Unexpected character {:?}", ch),
         };
 
         self.read_char();
-        return Ok(token);
+        Ok(token)
     }
 
     fn read_char(&mut self) {
-        if self.read_position >= self.input.len() {
-            self.ch = 0;
-        } else {
-            self.ch = self.input[self.read_position];
-        }
         self.position = self.read_position;
-        self.read_position += 1;
+        self.ch = self.char_at(self.position);
+        self.read_position = self.position + self.ch.len_utf8();
+    }
+
+    /// The character starting at byte `position`, or `\0` past the end.
+    fn char_at(&self, position: usize) -> char {
+        match self.input.get(position) {
+            None => '\0',
+            Some(byte) if byte.is_ascii() => char::from(*byte),
+            Some(_) => self.source[position..].chars().next().unwrap_or('\0'),
+        }
     }
 
     fn skip_whitespace(&mut self) {
-        while self.ch.is_ascii_whitespace() {
+        while self.ch.is_whitespace() {
             self.read_char();
         }
     }
 
-    fn read_ident(&mut self) -> String {
+    /// The source between two positions.
+    fn slice(&self, start: usize, end: usize) -> &'a str {
+        &self.source[start..end.min(self.source.len())]
+    }
+
+    fn read_ident(&mut self) -> &'a str {
         let start = self.position;
-        while self.ch.is_ascii_alphanumeric() || self.ch == b'_' {
+        // Unquoted identifiers may contain `$` after the first character, as
+        // in `SYSTEM$WAIT`
+        while is_ident_char(self.ch) || self.ch == '$' {
             self.read_char();
         }
-        return String::from_utf8_lossy(&self.input[start..self.position]).to_string();
+        self.slice(start, self.position)
     }
 
-    fn read_number(&mut self) -> String {
+    /// Reads a double-quoted identifier, in which `""` stands for a quote.
+    fn read_quoted_ident(&mut self) -> Result<Word<'a>> {
+        self.read_char();
         let start = self.position;
-        while self.ch.is_ascii_digit() || self.ch == b'.' {
+        let mut escaped = false;
+        loop {
+            match self.ch {
+                '\0' if self.position >= self.input.len() => {
+                    bail!("Unterminated quoted identifier")
+                }
+                '"' if self.peek_char() == '"' => {
+                    escaped = true;
+                    self.read_char();
+                }
+                '"' => break,
+                _ => {}
+            }
             self.read_char();
         }
+        let value = self.slice(start, self.position);
+        self.read_char();
 
-        return String::from_utf8_lossy(&self.input[start..self.position]).to_string();
+        Ok(Word {
+            value: if escaped {
+                Cow::Owned(value.replace("\"\"", "\""))
+            } else {
+                Cow::Borrowed(value)
+            },
+            quote_style: Some('"'),
+            keyword: None,
+            reserved: false,
+        })
     }
 
-    fn read_inline_comment(&mut self) -> String {
+    fn read_digits(&mut self) -> &'a str {
         let start = self.position;
-        while self.ch != b'\n' {
+        while self.ch.is_ascii_digit() {
             self.read_char();
         }
-        return String::from_utf8_lossy(&self.input[start..self.position]).to_string();
+
+        self.slice(start, self.position)
     }
 
-    fn read_block_comment(&mut self) -> String {
+    /// Reads `123`, `1.5`, `.5`, `1.`, `1e10`, `1.5E-3` or `0x1F`.
+    fn read_number(&mut self) -> Result<Token<'a>> {
         let start = self.position;
-        loop {
-            if self.ch == b'*' && self.peek_char() == b'/' {
+
+        if self.ch == '0' && matches!(self.peek_char(), 'x' | 'X') {
+            self.read_char();
+            self.read_char();
+            let digits_start = self.position;
+            while self.ch.is_ascii_hexdigit() {
                 self.read_char();
+            }
+            let digits = self.slice(digits_start, self.position);
+            let raw = self.read_number_end(start)?;
+            if digits.is_empty() {
+                bail!("Malformed number literal {}", raw);
+            }
+
+            return match i128::from_str_radix(digits, 16) {
+                Ok(value) => Ok(Token::Int(value)),
+                Err(_) => bail!("Hex literal {} is out of range", raw),
+            };
+        }
+
+        self.read_digits();
+        let mut is_decimal = false;
+        if self.ch == '.' {
+            is_decimal = true;
+            self.read_char();
+            self.read_digits();
+        }
+
+        let mut is_float = false;
+        if matches!(self.ch, 'e' | 'E') {
+            is_float = true;
+            self.read_char();
+            if matches!(self.ch, '+' | '-') {
                 self.read_char();
-                break;
             }
+            if !self.ch.is_ascii_digit() {
+                let raw = self.slice(start, self.position);
+                bail!("Malformed number literal {}, expected exponent digits", raw);
+            }
+            self.read_digits();
+        }
+
+        let raw = self.read_number_end(start)?;
+        if is_float {
+            return match raw.parse() {
+                Ok(value) => Ok(Token::Float(value)),
+                Err(_) => bail!("Malformed number literal {}", raw),
+            };
+        }
+
+        if is_decimal {
+            return Ok(Token::Decimal(raw.into()));
+        }
 
+        Ok(match raw.parse() {
+            Ok(value) => Token::Int(value),
+            Err(_) => Token::Decimal(raw.into()),
+        })
+    }
+
+    /// Returns the text of the number starting at `start`, failing if it runs
+    /// straight into letters or another decimal point, as in `12abc` or `1.2.3`.
+    fn read_number_end(&mut self, start: usize) -> Result<&'a str> {
+        if is_ident_char(self.ch) || self.ch == '.' {
+            while is_ident_char(self.ch) || self.ch == '.' {
+                self.read_char();
+            }
+            bail!(
+                "Malformed number literal {}",
+                self.slice(start, self.position)
+            );
+        }
+
+        Ok(self.slice(start, self.position))
+    }
+
+    fn read_inline_comment(&mut self) -> &'a str {
+        let start = self.position;
+        while self.ch != '\n' && self.ch != '\0' {
             self.read_char();
         }
-        return String::from_utf8_lossy(&self.input[start..self.position]).to_string();
+        self.slice(start, self.position)
     }
 
-    fn string_to_token(&self, ident: &str) -> Option<Token> {
-        let returned_tok = match ident {
+    /// Reads a comment from its `/*` through its `*/`, leaving the lexer on
+    /// the character after it.
+    fn read_block_comment(&mut self) -> Result<&'a str> {
+        let start = self.position;
+        // Skip the `/*`, so that `/*/` doesn't end the comment
+        self.read_char();
+        self.read_char();
+        loop {
+            match self.ch {
+                '*' if self.peek_char() == '/' => {
+                    self.read_char();
+                    self.read_char();
+                    return Ok(self.slice(start, self.position));
+                }
+                '\0' if self.position >= self.input.len() => bail!("Unterminated block comment"),
+                _ => self.read_char(),
+            }
+        }
+    }
+
+    /// Classifies a word of any case as a keyword.
+    fn string_to_keyword(ident: &str) -> Option<Keyword> {
+        // Keywords are short, so lowercase them on the stack rather than
+        // allocating for every word
+        let mut buffer = [0; 16];
+        let lower = buffer.get_mut(..ident.len())?;
+        lower.copy_from_slice(ident.as_bytes());
+        lower.make_ascii_lowercase();
+
+        match std::str::from_utf8(lower).ok()? {
             // DDL
-            "alter" => Some(Token::DDL(DDLKeyword::Alter)),
-            "create" => Some(Token::DDL(DDLKeyword::Create)),
-            "drop" => Some(Token::DDL(DDLKeyword::Drop)),
-            "replace" => Some(Token::DDL(DDLKeyword::Replace)),
-            "rename" => Some(Token::DDL(DDLKeyword::Rename)),
-            "truncate" => Some(Token::DDL(DDLKeyword::Truncate)),
-            "with" => Some(Token::DDL(DDLKeyword::With)),
+            "alter" => Some(Keyword::DDL(DDLKeyword::Alter)),
+            "create" => Some(Keyword::DDL(DDLKeyword::Create)),
+            "drop" => Some(Keyword::DDL(DDLKeyword::Drop)),
+            "replace" => Some(Keyword::DDL(DDLKeyword::Replace)),
+            "rename" => Some(Keyword::DDL(DDLKeyword::Rename)),
+            "truncate" => Some(Keyword::DDL(DDLKeyword::Truncate)),
+            "with" => Some(Keyword::DDL(DDLKeyword::With)),
 
             // DML
-            "call" => Some(Token::DML(DMLKeyword::Call)),
-            "delete" => Some(Token::DML(DMLKeyword::Delete)),
-            "insert" => Some(Token::DML(DMLKeyword::Insert)),
-            "select" => Some(Token::DML(DMLKeyword::Select)),
-            "update" => Some(Token::DML(DMLKeyword::Update)),
-
-            // Column Functions
-            "avg" => Some(Token::ColumnFunction(Function::Avg)),
-            "cast" => Some(Token::ColumnFunction(Function::Cast)),
-            "concat" => Some(Token::ColumnFunction(Function::Concat)),
-            "count" => Some(Token::ColumnFunction(Function::Count)),
-            "dense_rank" => Some(Token::ColumnFunction(Function::DenseRank)),
-            "lag" => Some(Token::ColumnFunction(Function::Lag)),
-            "lead" => Some(Token::ColumnFunction(Function::Lead)),
-            "max" => Some(Token::ColumnFunction(Function::Max)),
-            "min" => Some(Token::ColumnFunction(Function::Min)),
-            "rank" => Some(Token::ColumnFunction(Function::Rank)),
-            "row_number" => Some(Token::ColumnFunction(Function::RowNumber)),
-            "sum" => Some(Token::ColumnFunction(Function::Sum)),
+            "call" => Some(Keyword::DML(DMLKeyword::Call)),
+            "delete" => Some(Keyword::DML(DMLKeyword::Delete)),
+            "insert" => Some(Keyword::DML(DMLKeyword::Insert)),
+            "select" => Some(Keyword::DML(DMLKeyword::Select)),
+            "update" => Some(Keyword::DML(DMLKeyword::Update)),
 
             // Keywords
-            "all" => Some(Token::All),
-            "as" => Some(Token::As),
-            "between" => Some(Token::Between),
-            "by" => Some(Token::By),
-            "caller" => Some(Token::Caller),
-            "case" => Some(Token::Case),
-            "distinct" => Some(Token::Distinct),
-            "except" => Some(Token::Except),
-            "execute" => Some(Token::Execute),
-            "from" => Some(Token::From),
-            "function" => Some(Token::Function),
-            "group" => Some(Token::Group),
-            "having" => Some(Token::Having),
-            "join" => Some(Token::Join),
-            "language" => Some(Token::Language),
-            "like" => Some(Token::Like),
-            "limit" => Some(Token::Limit),
-            "on" => Some(Token::On),
-            "order" => Some(Token::Order),
-            "over" => Some(Token::Over),
-            "procedure" => Some(Token::Procedure),
-            "return" => Some(Token::Return),
-            "returns" => Some(Token::Returns),
-            "set" => Some(Token::Set),
-            "temp" | "temporary" => Some(Token::Temporary),
-            "top" => Some(Token::Top),
-            "union" => Some(Token::Union),
-            "when" => Some(Token::When),
-            "where" => Some(Token::Where),
+            "all" => Some(Keyword::All),
+            "as" => Some(Keyword::As),
+            "between" => Some(Keyword::Between),
+            "by" => Some(Keyword::By),
+            "caller" => Some(Keyword::Caller),
+            "case" => Some(Keyword::Case),
+            "cast" => Some(Keyword::Cast),
+            "cross" => Some(Keyword::Cross),
+            "distinct" => Some(Keyword::Distinct),
+            "else" => Some(Keyword::Else),
+            "end" => Some(Keyword::End),
+            "except" => Some(Keyword::Except),
+            "execute" => Some(Keyword::Execute),
+            "exists" => Some(Keyword::Exists),
+            "for" => Some(Keyword::For),
+            "from" => Some(Keyword::From),
+            "full" => Some(Keyword::Full),
+            "function" => Some(Keyword::Function),
+            "group" => Some(Keyword::Group),
+            "having" => Some(Keyword::Having),
+            "if" => Some(Keyword::If),
+            "ilike" => Some(Keyword::Ilike),
+            "in" => Some(Keyword::In),
+            "intersect" => Some(Keyword::Intersect),
+            "into" => Some(Keyword::Into),
+            "is" => Some(Keyword::Is),
+            "join" => Some(Keyword::Join),
+            "language" => Some(Keyword::Language),
+            "lateral" => Some(Keyword::Lateral),
+            "like" => Some(Keyword::Like),
+            "limit" => Some(Keyword::Limit),
+            "on" => Some(Keyword::On),
+            "order" => Some(Keyword::Order),
+            "over" => Some(Keyword::Over),
+            "procedure" => Some(Keyword::Procedure),
+            "qualify" => Some(Keyword::Qualify),
+            "return" => Some(Keyword::Return),
+            "returns" => Some(Keyword::Returns),
+            "set" => Some(Keyword::Set),
+            "temp" | "temporary" => Some(Keyword::Temporary),
+            "then" => Some(Keyword::Then),
+            "top" => Some(Keyword::Top),
+            "union" => Some(Keyword::Union),
+            "using" => Some(Keyword::Using),
+            "values" => Some(Keyword::Values),
+            "when" => Some(Keyword::When),
+            "where" => Some(Keyword::Where),
 
             // Objects
-            "materialized" => Some(Token::View),
-            "table" => Some(Token::Table),
-            "view" => Some(Token::View),
-
-            // Booleans
-            "true" => Some(Token::Bool(true)),
-            "false" => Some(Token::Bool(false)),
+            "materialized" => Some(Keyword::Materialized),
+            "table" => Some(Keyword::Table),
+            "view" => Some(Keyword::View),
 
             // Join types
-            "inner" => Some(Token::JoinType(JoinType::Inner)),
-            "left" => Some(Token::JoinType(JoinType::Left)),
-            "right" => Some(Token::JoinType(JoinType::Right)),
-            "outer" => Some(Token::JoinType(JoinType::Outer)),
-            "natural" => Some(Token::JoinType(JoinType::Natural)),
+            "inner" => Some(Keyword::JoinType(JoinType::Inner)),
+            "left" => Some(Keyword::JoinType(JoinType::Left)),
+            "right" => Some(Keyword::JoinType(JoinType::Right)),
+            "outer" => Some(Keyword::JoinType(JoinType::Outer)),
+            "natural" => Some(Keyword::JoinType(JoinType::Natural)),
 
             // Logicals
-            "and" => Some(Token::Logical(Logical::And)),
-            "or" => Some(Token::Logical(Logical::Or)),
-            "not" => Some(Token::Logical(Logical::Not)),
+            "and" => Some(Keyword::Logical(Logical::And)),
+            "or" => Some(Keyword::Logical(Logical::Or)),
+            "not" => Some(Keyword::Logical(Logical::Not)),
 
             // Data types
-            "int" | "integer" => Some(Token::DataType(DataType::Int)),
-            "bigint" => Some(Token::DataType(DataType::BigInt)),
-            "smallint" => Some(Token::DataType(DataType::SmallInt)),
-            "tinyint" => Some(Token::DataType(DataType::TinyInt)),
-            "byteint" => Some(Token::DataType(DataType::ByteInt)),
-            "number" => Some(Token::DataType(DataType::Number)),
+            "int" | "integer" => Some(Keyword::DataType(DataTypeKeyword::Int)),
+            "bigint" => Some(Keyword::DataType(DataTypeKeyword::BigInt)),
+            "smallint" => Some(Keyword::DataType(DataTypeKeyword::SmallInt)),
+            "tinyint" => Some(Keyword::DataType(DataTypeKeyword::TinyInt)),
+            "byteint" => Some(Keyword::DataType(DataTypeKeyword::ByteInt)),
+            "number" => Some(Keyword::DataType(DataTypeKeyword::Number)),
             "float" | "float4" | "float8" | "double" | "real" => {
-                Some(Token::DataType(DataType::Float))
+                Some(Keyword::DataType(DataTypeKeyword::Float))
             }
             "varchar" | "string" | "text" | "nvarchar" | "nvarchar2" => {
-                Some(Token::DataType(DataType::Varchar))
+                Some(Keyword::DataType(DataTypeKeyword::Varchar))
             }
-            "char" | "character" | "nchar" => Some(Token::DataType(DataType::Char)),
-            "binary" | "varbinary" => Some(Token::DataType(DataType::Binary)),
-            "boolean" => Some(Token::DataType(DataType::Boolean)),
-
-            // Null
-            "null" => Some(Token::Null),
+            "char" | "character" | "nchar" => Some(Keyword::DataType(DataTypeKeyword::Char)),
+            "binary" | "varbinary" => Some(Keyword::DataType(DataTypeKeyword::Binary)),
+            "boolean" => Some(Keyword::DataType(DataTypeKeyword::Boolean)),
 
             _ => None,
-        };
+        }
+    }
 
-        return returned_tok;
+    /// Reads the stage name and path following an `@`, stopping at whitespace
+    /// or punctuation that can't be part of a path. Double-quoted parts of the
+    /// name may contain anything.
+    fn read_stage_ref(&mut self) -> Result<&'a str> {
+        let start = self.position;
+        loop {
+            match self.ch {
+                '"' => {
+                    self.read_char();
+                    while self.ch != '"' {
+                        if self.ch == '\0' {
+                            bail!("Unterminated quoted stage name");
+                        }
+                        self.read_char();
+                    }
+                    self.read_char();
+                }
+                '\0' | ';' | ',' | '(' | ')' | '\'' => break,
+                ch if ch.is_whitespace() => break,
+                _ => self.read_char(),
+            }
+        }
+
+        Ok(self.slice(start, self.position))
+    }
+
+    /// Reads the rest of a `file://` URI starting at `start`, up to whitespace
+    /// or a `;`.
+    fn read_file_uri(&mut self, start: usize) -> &'a str {
+        while self.ch != '\0' && self.ch != ';' && !self.ch.is_whitespace() {
+            self.read_char();
+        }
+
+        self.slice(start, self.position)
     }
 
-    fn read_varchar(&mut self) -> String {
+    /// Reads a `$$ ... $$` literal, leaving the lexer on its closing `$`.
+    fn read_dollar_string(&mut self) -> Result<&'a str> {
+        self.read_char();
+        self.read_char();
         let start = self.position;
+        while !(self.ch == '$' && self.peek_char() == '$') {
+            if self.ch == '\0' {
+                bail!("Unterminated $$ string literal");
+            }
+            self.read_char();
+        }
+
+        let content = self.slice(start, self.position);
         self.read_char();
-        while self.ch != b'\'' {
+        Ok(content)
+    }
+
+    /// Reads a single-quoted string starting at the opening quote, resolving
+    /// `''` and backslash escapes. `start` is where the literal's prefix, if
+    /// any, begins.
+    fn read_varchar(&mut self, start: usize) -> Result<StringLiteral<'a>> {
+        self.read_char();
+        let content_start = self.position;
+        let input = self.source;
+        // Only copied once there's an escape to resolve
+        let mut value: Option<String> = None;
+        loop {
+            match self.ch {
+                '\0' if self.position >= self.input.len() => {
+                    bail!("Unterminated string literal")
+                }
+                '\'' if self.peek_char() == '\'' => {
+                    value
+                        .get_or_insert_with(|| input[content_start..self.position].to_owned())
+                        .push('\'');
+                    self.read_char();
+                }
+                '\'' => break,
+                '\\' => {
+                    let value =
+                        value.get_or_insert_with(|| input[content_start..self.position].to_owned());
+                    self.read_char();
+                    self.read_escape(value)?;
+                }
+                ch => {
+                    if let Some(value) = &mut value {
+                        value.push(ch);
+                    }
+                }
+            }
+            self.read_char();
+        }
+        let content = self.slice(content_start, self.position);
+        self.read_char();
+
+        Ok(StringLiteral {
+            raw: self.slice(start, self.position).into(),
+            value: match value {
+                Some(value) => Cow::Owned(value),
+                None => Cow::Borrowed(content),
+            },
+        })
+    }
+
+    /// Resolves the escape sequence whose first character follows the
+    /// backslash, leaving the lexer on its last character.
+    fn read_escape(&mut self, value: &mut String) -> Result<()> {
+        let escaped = match self.ch {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            '0'..='7' => {
+                let mut code = self.ch.to_digit(8).unwrap_or_default();
+                for _ in 0..2 {
+                    if !('0'..='7').contains(&self.peek_char()) {
+                        break;
+                    }
+                    self.read_char();
+                    code = code * 8 + self.ch.to_digit(8).unwrap_or_default();
+                }
+                char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+            }
+            'x' => self.read_hex_escape(2)?,
+            'u' => self.read_hex_escape(4)?,
+            '\0' if self.position >= self.input.len() => bail!("Unterminated string literal"),
+            // `\'`, `\\` and any other escaped character stand for themselves
+            ch => {
+                value.push(ch);
+                return Ok(());
+            }
+        };
+
+        value.push(escaped);
+        Ok(())
+    }
+
+    fn read_hex_escape(&mut self, digits: usize) -> Result<char> {
+        let mut code = 0;
+        for _ in 0..digits {
             self.read_char();
+            match self.ch.to_digit(16) {
+                Some(digit) => code = code * 16 + digit,
+                None => bail!("Invalid hex escape in string literal"),
+            }
+        }
+
+        match char::from_u32(code) {
+            Some(ch) => Ok(ch),
+            None => bail!("Invalid unicode escape \\u{:04X}", code),
         }
-        return String::from_utf8_lossy(&self.input[start..=self.position]).to_string();
     }
 
-    fn peek_char(&self) -> u8 {
-        if let Some(c) = self.input.get(self.read_position) {
-            return *c;
-        } else {
-            return 0;
+    /// Reads `X'...'`, starting on the `X`.
+    fn read_hex_string(&mut self) -> Result<Token<'a>> {
+        let start = self.position;
+        self.read_char();
+        self.read_char();
+
+        let digits_start = self.position;
+        while self.ch != '\'' {
+            if !self.ch.is_ascii_hexdigit() {
+                bail!("Invalid hex digit in binary literal");
+            }
+            self.read_char();
+        }
+        let digits = self.slice(digits_start, self.position);
+        if !digits.len().is_multiple_of(2) {
+            bail!("Binary literal has an odd number of hex digits");
         }
+
+        let value = (0..digits.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&digits[i..i + 2], 16))
+            .collect::<std::result::Result<Vec<u8>, _>>()?;
+        self.read_char();
+
+        Ok(Token::HexString(HexLiteral {
+            raw: self.slice(start, self.position).into(),
+            value,
+        }))
+    }
+
+    fn peek_char(&self) -> char {
+        self.char_at(self.read_position)
     }
 
-    pub fn get_tokens(mut self) -> Result<Vec<Token>> {
+    fn next_spanned_token(&mut self) -> Result<SpannedToken<'a>> {
+        self.skip_whitespace();
+        let start = self.position.min(self.input.len());
+        let token = self.next_token()?;
+
+        Ok(SpannedToken {
+            token,
+            span: Span {
+                start,
+                end: self.position.min(self.input.len()),
+            },
+        })
+    }
+
+    /// Like [`Lexer::get_tokens`], but keeps the span of every token and ends
+    /// with [`Token::EOF`]. Whatever lies between two spans is whitespace.
+    pub fn get_spanned_tokens(mut self) -> Result<Vec<SpannedToken<'a>>> {
         let mut tokens = Vec::new();
-        let mut next_token = self.next_token()?;
-        while next_token != Token::EOF {
-            tokens.push(next_token);
-            next_token = self.next_token()?;
+        loop {
+            let token = self.next_spanned_token()?;
+            let eof = token.token == Token::EOF;
+            tokens.push(token);
+            if eof {
+                return Ok(tokens);
+            }
         }
+    }
 
-        return Ok(tokens);
+    pub fn get_tokens(self) -> Result<Vec<Token<'a>>> {
+        Ok(self
+            .map(|token| token.map(|token| token.token))
+            .collect::<Result<_, _>>()?)
+    }
+}
+
+/// Whether an unquoted identifier can start with `ch`. Like Snowflake, this
+/// takes letters of any script, not only ASCII ones.
+fn is_ident_start(ch: char) -> bool {
+    ch.is_alphabetic() || ch == '_'
+}
+
+fn is_ident_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Result<SpannedToken<'a>, LexError>;
+
+    /// Yields the next token, comments included, and ends before
+    /// [`Token::EOF`] or right after the first error.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        self.skip_whitespace();
+        let start = self.position.min(self.input.len());
+        match self.next_spanned_token() {
+            Ok(token) if token.token == Token::EOF => {
+                self.done = true;
+                None
+            }
+            Ok(token) => Some(Ok(token)),
+            Err(error) => {
+                self.done = true;
+                Some(Err(LexError {
+                    message: error.to_string(),
+                    span: Span {
+                        start,
+                        end: self.position.min(self.input.len()),
+                    },
+                    location: Location::of(self.source, start),
+                }))
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::borrow::Cow;
+
     use anyhow::Result;
 
-    use super::{DDLKeyword, DMLKeyword, DataType, Function, Lexer, Logical, Token};
+    use super::{
+        DDLKeyword, DMLKeyword, DataTypeKeyword, HexLiteral, JoinType, Keyword, Lexer, Location,
+        Logical, Span, Token, Word,
+    };
+
+    fn ident(value: &str) -> Token<'_> {
+        Token::Word(Word {
+            value: value.into(),
+            quote_style: None,
+            keyword: None,
+            reserved: false,
+        })
+    }
+
+    fn keyword(value: &str, keyword: Keyword) -> Token<'_> {
+        Token::Word(Word {
+            value: value.into(),
+            quote_style: None,
+            keyword: Some(keyword),
+            reserved: super::keywords::is_reserved(value),
+        })
+    }
 
     #[test]
     fn assert_basic_string_match() -> Result<()> {
@@ -548,120 +1339,113 @@ SELECT *
 FROM TABLE(my_function(123));
             "#;
 
-        let mut lexer = Lexer::new(input.into());
+        let mut lexer = Lexer::new(input);
+        let procedure_body = "
+  -- SQL code goes here
+  -- ...
+  RETURN 'Stored procedure executed successfully';
+";
 
         let tokens = vec![
-            Token::InlineComment("-- Create a temporary table".to_string()),
-            Token::DDL(DDLKeyword::Create),
-            Token::Temporary,
-            Token::Table,
-            Token::Ident("temp_table".to_string()),
+            Token::InlineComment("-- Create a temporary table".into()),
+            keyword("CREATE", Keyword::DDL(DDLKeyword::Create)),
+            keyword("TEMPORARY", Keyword::Temporary),
+            keyword("TABLE", Keyword::Table),
+            ident("temp_table"),
             Token::OpenParen,
-            Token::Ident("id".to_string()),
-            Token::DataType(DataType::Int),
+            ident("id"),
+            keyword("INT", Keyword::DataType(DataTypeKeyword::Int)),
             Token::Comma,
-            Token::Ident("name".to_string()),
-            Token::DataType(DataType::Varchar),
+            ident("name"),
+            keyword("VARCHAR", Keyword::DataType(DataTypeKeyword::Varchar)),
             Token::Comma,
-            Token::Ident("age".to_string()),
-            Token::DataType(DataType::Int),
+            ident("age"),
+            keyword("INT", Keyword::DataType(DataTypeKeyword::Int)),
             Token::CloseParen,
             Token::Semicolon,
-            Token::InlineComment("-- Create a stored procedure".to_string()),
-            Token::DDL(DDLKeyword::Create),
-            Token::Logical(Logical::Or),
-            Token::DDL(DDLKeyword::Replace),
-            Token::Procedure,
-            Token::Ident("my_stored_procedure".to_string()),
+            Token::InlineComment("-- Create a stored procedure".into()),
+            keyword("CREATE", Keyword::DDL(DDLKeyword::Create)),
+            keyword("OR", Keyword::Logical(Logical::Or)),
+            keyword("REPLACE", Keyword::DDL(DDLKeyword::Replace)),
+            keyword("PROCEDURE", Keyword::Procedure),
+            ident("my_stored_procedure"),
             Token::OpenParen,
             Token::CloseParen,
-            Token::Returns,
-            Token::DataType(DataType::Varchar),
-            Token::Language,
-            Token::Ident("SQL".to_string()),
-            Token::As,
-            Token::DollarDelimiter,
-            Token::InlineComment("-- SQL code goes here".to_string()),
-            Token::InlineComment("-- ...".to_string()),
-            Token::Return,
-            Token::Varchar("'Stored procedure executed successfully'".to_string()),
-            Token::Semicolon,
-            Token::DollarDelimiter,
+            keyword("RETURNS", Keyword::Returns),
+            keyword("VARCHAR", Keyword::DataType(DataTypeKeyword::Varchar)),
+            keyword("LANGUAGE", Keyword::Language),
+            ident("SQL"),
+            keyword("AS", Keyword::As),
+            Token::DollarString(procedure_body.into()),
             Token::Semicolon,
-            Token::InlineComment("-- Create a function".to_string()),
-            Token::DDL(DDLKeyword::Create),
-            Token::Logical(Logical::Or),
-            Token::DDL(DDLKeyword::Replace),
-            Token::Function,
-            Token::Ident("my_function".to_string()),
+            Token::InlineComment("-- Create a function".into()),
+            keyword("CREATE", Keyword::DDL(DDLKeyword::Create)),
+            keyword("OR", Keyword::Logical(Logical::Or)),
+            keyword("REPLACE", Keyword::DDL(DDLKeyword::Replace)),
+            keyword("FUNCTION", Keyword::Function),
+            ident("my_function"),
             Token::OpenParen,
-            Token::Ident("arg".to_string()),
-            Token::DataType(DataType::Int),
+            ident("arg"),
+            keyword("INT", Keyword::DataType(DataTypeKeyword::Int)),
             Token::CloseParen,
-            Token::Returns,
-            Token::Table,
+            keyword("RETURNS", Keyword::Returns),
+            keyword("TABLE", Keyword::Table),
             Token::OpenParen,
-            Token::Ident("id".to_string()),
-            Token::DataType(DataType::Int),
+            ident("id"),
+            keyword("INT", Keyword::DataType(DataTypeKeyword::Int)),
             Token::Comma,
-            Token::Ident("name".to_string()),
-            Token::DataType(DataType::Varchar),
+            ident("name"),
+            keyword("VARCHAR", Keyword::DataType(DataTypeKeyword::Varchar)),
             Token::CloseParen,
-            Token::Language,
-            Token::Ident("SQL".to_string()),
-            Token::As,
-            Token::DollarDelimiter,
-            Token::InlineComment("-- SQL code goes here".to_string()),
-            Token::InlineComment("-- ...".to_string()),
-            Token::Return,
-            Token::Varchar("'Stored procedure executed successfully'".to_string()),
-            Token::Semicolon,
-            Token::DollarDelimiter,
+            keyword("LANGUAGE", Keyword::Language),
+            ident("SQL"),
+            keyword("AS", Keyword::As),
+            Token::DollarString(procedure_body.into()),
             Token::Semicolon,
-            Token::InlineComment("-- Use a CTE to query data".to_string()),
-            Token::DDL(DDLKeyword::With),
-            Token::Ident("cte".to_string()),
-            Token::As,
+            Token::InlineComment("-- Use a CTE to query data".into()),
+            keyword("WITH", Keyword::DDL(DDLKeyword::With)),
+            ident("cte"),
+            keyword("AS", Keyword::As),
             Token::OpenParen,
-            Token::DML(DMLKeyword::Select),
-            Token::Ident("id".to_string()),
+            keyword("SELECT", Keyword::DML(DMLKeyword::Select)),
+            ident("id"),
             Token::Comma,
-            Token::Ident("name".to_string()),
+            ident("name"),
             Token::Comma,
-            Token::Ident("salary".to_string()),
-            Token::From,
-            Token::Ident("temp_table".to_string()),
-            Token::Where,
-            Token::Ident("age".to_string()),
+            ident("salary"),
+            keyword("FROM", Keyword::From),
+            ident("temp_table"),
+            keyword("WHERE", Keyword::Where),
+            ident("age"),
             Token::GreaterThan,
             Token::Int(30),
             Token::CloseParen,
-            Token::DML(DMLKeyword::Select),
-            Token::Ident("name".to_string()),
+            keyword("SELECT", Keyword::DML(DMLKeyword::Select)),
+            ident("name"),
             Token::Comma,
-            Token::ColumnFunction(Function::Sum),
+            ident("sum"),
             Token::OpenParen,
-            Token::Ident("salary".to_string()),
+            ident("salary"),
             Token::CloseParen,
-            Token::From,
-            Token::Ident("cte".to_string()),
-            Token::Group,
-            Token::By,
-            Token::Ident("name".to_string()),
+            keyword("FROM", Keyword::From),
+            ident("cte"),
+            keyword("group", Keyword::Group),
+            keyword("by", Keyword::By),
+            ident("name"),
             Token::Semicolon,
-            Token::InlineComment("-- Call the stored procedure".to_string()),
-            Token::DML(DMLKeyword::Call),
-            Token::Ident("my_stored_procedure".to_string()),
+            Token::InlineComment("-- Call the stored procedure".into()),
+            keyword("CALL", Keyword::DML(DMLKeyword::Call)),
+            ident("my_stored_procedure"),
             Token::OpenParen,
             Token::CloseParen,
             Token::Semicolon,
-            Token::InlineComment("-- Call the function".to_string()),
-            Token::DML(DMLKeyword::Select),
+            Token::InlineComment("-- Call the function".into()),
+            keyword("SELECT", Keyword::DML(DMLKeyword::Select)),
             Token::Asterisk,
-            Token::From,
-            Token::Table,
+            keyword("FROM", Keyword::From),
+            keyword("TABLE", Keyword::Table),
             Token::OpenParen,
-            Token::Ident("my_function".to_string()),
+            ident("my_function"),
             Token::OpenParen,
             Token::Int(123),
             Token::CloseParen,
@@ -678,4 +1462,318 @@ FROM TABLE(my_function(123));
 
         Ok(())
     }
+
+    #[test]
+    fn assert_dollar_string_is_single_token() -> Result<()> {
+        let input = r#"
+CREATE PROCEDURE js_proc()
+RETURNS STRING
+LANGUAGE JAVASCRIPT
+AS $$
+  var rows = snowflake.execute({sqlText: 'SELECT 1'});
+  return "it's done";
+$$;
+SELECT $1, $2 FROM my_table WHERE id = $min_id;
+"#;
+
+        let tokens = Lexer::new(input).get_tokens()?;
+
+        assert_eq!(
+            tokens[10],
+            Token::DollarString(
+                r#"
+  var rows = snowflake.execute({sqlText: 'SELECT 1'});
+  return "it's done";
+"#
+                .into()
+            )
+        );
+        assert_eq!(
+            tokens[11..],
+            [
+                Token::Semicolon,
+                keyword("SELECT", Keyword::DML(DMLKeyword::Select)),
+                Token::Positional(1),
+                Token::Comma,
+                Token::Positional(2),
+                keyword("FROM", Keyword::From),
+                ident("my_table"),
+                keyword("WHERE", Keyword::Where),
+                ident("id"),
+                Token::Equal,
+                Token::SessionVariable("min_id".into()),
+                Token::Semicolon,
+            ]
+        );
+
+        assert!(Lexer::new("SELECT $$ unterminated").get_tokens().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn assert_string_literal_escapes() -> Result<()> {
+        let input = r#"'it''s' 'a\'b' 'tab\there\n' '\x41\u00e9\101\\' E'\q' X'48690A' 'héllo'"#;
+
+        let tokens = Lexer::new(input).get_tokens()?;
+        let literals: Vec<(&str, &str)> = tokens
+            .iter()
+            .filter_map(|token| match token {
+                Token::Varchar(literal) => Some((literal.raw.as_ref(), literal.value.as_ref())),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            literals,
+            vec![
+                ("'it''s'", "it's"),
+                (r#"'a\'b'"#, "a'b"),
+                (r#"'tab\there\n'"#, "tab\there\n"),
+                (r#"'\x41\u00e9\101\\'"#, "AéA\\"),
+                (r#"E'\q'"#, "q"),
+                ("'héllo'", "héllo"),
+            ]
+        );
+        assert_eq!(
+            tokens[5],
+            Token::HexString(HexLiteral {
+                raw: "X'48690A'".into(),
+                value: vec![0x48, 0x69, 0x0A],
+            })
+        );
+
+        assert!(Lexer::new("'unterminated").get_tokens().is_err());
+        assert!(Lexer::new("X'ABC'").get_tokens().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn assert_numeric_literals() -> Result<()> {
+        let input = "42 1.50 .5 7. 1e10 1.5E-3 2e+2 0x1F 99999999999999999999 \
+                     12345678901234567890123456789012345678901234567890";
+
+        let tokens = Lexer::new(input).get_tokens()?;
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Int(42),
+                Token::Decimal("1.50".into()),
+                Token::Decimal(".5".into()),
+                Token::Decimal("7.".into()),
+                Token::Float(1e10),
+                Token::Float(1.5e-3),
+                Token::Float(200.0),
+                Token::Int(31),
+                Token::Int(99999999999999999999),
+                Token::Decimal("12345678901234567890123456789012345678901234567890".into()),
+            ]
+        );
+
+        for malformed in ["1.2.3", "1e", "1e+", "12abc", "0x", "0xZZ"] {
+            assert!(
+                Lexer::new(malformed).get_tokens().is_err(),
+                "{} should not lex",
+                malformed
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn assert_operators() -> Result<()> {
+        let input = "|| | <> != <= >= :: : := => -> ->> ? ~ ^ & @ [ ] { } < > = -";
+
+        let tokens = Lexer::new(input).get_tokens()?;
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::StringConcat,
+                Token::Pipe,
+                Token::NotEqual,
+                Token::NotEqual,
+                Token::LessThanEqual,
+                Token::GreaterThanEqual,
+                Token::DoubleColon,
+                Token::Colon,
+                Token::Assign,
+                Token::FatArrow,
+                Token::Arrow,
+                Token::Flow,
+                Token::QuestionMark,
+                Token::Tilde,
+                Token::Caret,
+                Token::Ampersand,
+                Token::At,
+                Token::OpenBracket,
+                Token::CloseBracket,
+                Token::OpenBrace,
+                Token::CloseBrace,
+                Token::LessThan,
+                Token::GreaterThan,
+                Token::Equal,
+                Token::Minus,
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn assert_stage_references() -> Result<()> {
+        let input = r#"@my_stage/data/2024/ @db.raw."My Stage" @~/staged @%orders, @stage(
+            PUT file:///tmp/data.csv @~;"#;
+
+        let tokens = Lexer::new(input).get_tokens()?;
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::StageRef("my_stage/data/2024/".into()),
+                Token::StageRef(r#"db.raw."My Stage""#.into()),
+                Token::StageRef("~/staged".into()),
+                Token::StageRef("%orders".into()),
+                Token::Comma,
+                Token::StageRef("stage".into()),
+                Token::OpenParen,
+                ident("PUT"),
+                Token::FileUri("file:///tmp/data.csv".into()),
+                Token::StageRef("~".into()),
+                Token::Semicolon,
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn assert_words_keep_quotes_and_case() -> Result<()> {
+        let input = r#"Date "Date" "say ""hi""" left"#;
+
+        let tokens = Lexer::new(input).get_tokens()?;
+
+        assert_eq!(
+            tokens,
+            vec![
+                ident("Date"),
+                Token::Word(Word {
+                    value: "Date".into(),
+                    quote_style: Some('"'),
+                    keyword: None,
+                    reserved: false,
+                }),
+                Token::Word(Word {
+                    value: r#"say "hi""#.into(),
+                    quote_style: Some('"'),
+                    keyword: None,
+                    reserved: false,
+                }),
+                keyword("left", Keyword::JoinType(JoinType::Left)),
+            ]
+        );
+
+        assert!(Lexer::new(r#""unterminated"#).get_tokens().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn assert_tokens_borrow_the_source() -> Result<()> {
+        let tokens = {
+            let input = String::from(r#"name 'plain' 'it''s' "quoted" "a""b" 1.50"#);
+            let tokens = Lexer::new(&input).get_tokens()?;
+
+            let borrowed: Vec<bool> = tokens
+                .iter()
+                .map(|token| match token {
+                    Token::Word(word) => matches!(word.value, Cow::Borrowed(_)),
+                    Token::Varchar(literal) => matches!(literal.value, Cow::Borrowed(_)),
+                    Token::Decimal(value) => matches!(value, Cow::Borrowed(_)),
+                    _ => false,
+                })
+                .collect();
+            // Only values with escapes to resolve need their own copy
+            assert_eq!(borrowed, vec![true, true, false, true, false, true]);
+
+            tokens
+                .into_iter()
+                .map(Token::into_owned)
+                .collect::<Vec<Token<'static>>>()
+        };
+
+        assert_eq!(tokens[0], ident("name"));
+        assert!(matches!(&tokens[2], Token::Varchar(literal) if literal.value == "it's"));
+        assert!(matches!(&tokens[4], Token::Word(word) if word.value == "a\"b"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn assert_lexer_iterates_lazily() -> Result<()> {
+        let input = "-- nightly load\nINSERT INTO t SELECT 1; SELECT 'unterminated";
+
+        // Sniffing the statement type stops well before the error
+        let mut tokens = Lexer::new(input)
+            .filter(|token| {
+                !matches!(token, Ok(token) if matches!(token.token, Token::InlineComment(_)))
+            })
+            .peekable();
+        assert!(matches!(
+            tokens.peek(),
+            Some(Ok(token)) if token.token == keyword("INSERT", Keyword::DML(DMLKeyword::Insert))
+        ));
+
+        let error = Lexer::new(input)
+            .find_map(|token| token.err())
+            .expect("the string is unterminated");
+        let start = input.find('\'').unwrap_or_default();
+        assert_eq!(
+            error.span,
+            Span {
+                start,
+                end: input.len()
+            }
+        );
+
+        // Iteration ends after the error, and before EOF otherwise
+        let mut lexer = Lexer::new(input);
+        assert_eq!(lexer.by_ref().filter(|token| token.is_err()).count(), 1);
+        assert!(lexer.next().is_none());
+        assert_eq!(Lexer::new("SELECT").count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn assert_unicode_source() -> Result<()> {
+        let input =
+            "/* résumé ✓ */ SELECT straße, \"naïve col\" FROM 数据 -- 注释\nWHERE nom = 'Zoë\\t'";
+        let tokens = Lexer::new(input).get_tokens()?;
+
+        assert_eq!(tokens[0], Token::BlockComment("/* résumé ✓ */".into()));
+        assert_eq!(tokens[2], ident("straße"));
+        assert!(matches!(&tokens[4], Token::Word(word) if word.value == "naïve col"));
+        assert_eq!(tokens[6], ident("数据"));
+        assert_eq!(tokens[7], Token::InlineComment("-- 注释".into()));
+        assert!(matches!(&tokens[11], Token::Varchar(literal) if literal.value == "Zoë\t"));
+
+        // Columns count characters, not bytes
+        let error = Lexer::new("SELECT 'é', ¤")
+            .find_map(|token| token.err())
+            .expect("¤ is not a token");
+        assert_eq!(
+            error.to_string(),
+            "Unexpected character '¤' at line 1, column 13"
+        );
+        assert_eq!(
+            Location::of("--é\nSELECT '日本", 15),
+            Location { line: 2, column: 9 }
+        );
+
+        Ok(())
+    }
 }
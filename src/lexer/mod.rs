@@ -1 +1,3 @@
+pub mod keywords;
+#[allow(clippy::module_inception)]
 pub mod lexer;
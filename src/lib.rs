@@ -0,0 +1,15 @@
+pub mod ast;
+pub mod binder;
+pub mod catalog;
+pub mod cst;
+pub mod diagnostics;
+pub mod export;
+pub mod functions;
+pub mod graph;
+pub mod json;
+pub mod lexer;
+pub mod lineage;
+pub mod openlineage;
+pub mod parser;
+pub mod splitter;
+pub mod types;
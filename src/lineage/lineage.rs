@@ -0,0 +1,290 @@
+//! Column-level lineage: the source columns each output column of a
+//! statement derives from, and how.
+//!
+//! Lineage is tracked by the [`Binder`] as it resolves names, so references
+//! to CTEs, subqueries and table functions are followed down to the base
+//! tables they read.
+
+use std::fmt;
+
+use crate::ast::data_type::DataType;
+use crate::ast::expression::ObjectName;
+use crate::ast::query::TableFactor;
+use crate::ast::stage::CopyLocation;
+use crate::ast::statement::Statement;
+use crate::binder::binder::{normalize, Binder};
+
+/// How an output column derives from a source column, ordered from the most
+/// to the least direct.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LineageKind {
+    /// The value is copied unchanged.
+    Direct,
+    /// The value goes through an expression, e.g. `UPPER(name)`.
+    Transformation,
+    /// The value is aggregated, e.g. `SUM(amount)`.
+    Aggregation,
+    /// The column only decides which rows are returned or how they're
+    /// grouped, e.g. in `WHERE` or `JOIN ... ON`.
+    Filter,
+}
+
+/// A column of a base table an output column derives from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SourceColumn {
+    /// The qualified table name, e.g. `["DB", "RAW", "EMPLOYEES"]`.
+    pub table: Vec<String>,
+    pub column: String,
+    pub kind: LineageKind,
+}
+
+impl SourceColumn {
+    /// This column as seen through another derivation, e.g. a direct copy
+    /// of a CTE column that aggregates it is still an aggregation.
+    pub(crate) fn derived(self, kind: LineageKind) -> Self {
+        SourceColumn {
+            kind: self.kind.max(kind),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for SourceColumn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.table.join("."), self.column)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColumnLineage {
+    /// `None` for unnamed expressions such as `COUNT(*)` without an alias.
+    pub name: Option<String>,
+    pub sources: Vec<SourceColumn>,
+    /// The inferred type, `None` when it can't be told, e.g. for a column of
+    /// a table whose types weren't declared. See [`crate::types::types`].
+    pub data_type: Option<DataType>,
+}
+
+/// The lineage of the rows a statement returns or writes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatementLineage {
+    /// The table or view written, `None` for queries.
+    pub target: Option<Vec<String>>,
+    /// `None` when the output columns aren't known, e.g. for `SELECT *` from
+    /// a table that wasn't declared to the binder.
+    pub columns: Option<Vec<ColumnLineage>>,
+}
+
+impl Binder {
+    /// The lineage of each column `statement` returns or writes, or `None`
+    /// for statements that produce no rows, such as `DROP`.
+    pub fn lineage(&mut self, statement: &Statement) -> Option<StatementLineage> {
+        let (target, columns) = match statement {
+            Statement::Query(query) => (None, self.bind_query(query)),
+            Statement::Insert {
+                table_name,
+                columns,
+                source,
+                ..
+            } => {
+                let names = columns.iter().map(normalize).collect();
+                let lineage = self.bind_query(source);
+                (Some(table_name), renamed(lineage, names))
+            }
+            Statement::CreateTable {
+                name,
+                columns,
+                query: Some(query),
+                ..
+            } => {
+                let names = columns
+                    .iter()
+                    .map(|column| normalize(&column.name))
+                    .collect();
+                let lineage = self.bind_query(query);
+                (Some(name), renamed(lineage, names))
+            }
+            Statement::CreateView {
+                name,
+                columns,
+                query,
+                ..
+            } => {
+                let names = columns.iter().map(normalize).collect();
+                let lineage = self.bind_query(query);
+                (Some(name), renamed(lineage, names))
+            }
+            Statement::Update {
+                table,
+                assignments,
+                from,
+                selection,
+            } => {
+                let lineage = self.bind_update(table, assignments, from, selection.as_ref());
+                (table_name(&table.relation), Some(lineage))
+            }
+            Statement::Merge(merge) => {
+                let lineage = self.bind_merge(merge);
+                (table_name(&merge.target), Some(lineage))
+            }
+            Statement::CopyInto(copy) => match (&copy.into, &copy.from) {
+                (CopyLocation::Table { name, columns }, CopyLocation::Query(query)) => {
+                    let names = columns.iter().map(normalize).collect();
+                    let lineage = self.bind_query(query);
+                    (Some(name), renamed(lineage, names))
+                }
+                _ => return None,
+            },
+            _ => return None,
+        };
+        self.take_bindings();
+
+        Some(StatementLineage {
+            target: target.map(|name: &ObjectName| name.0.iter().map(normalize).collect()),
+            columns,
+        })
+    }
+}
+
+/// Names `columns` positionally, keeping the lineage and types of the
+/// columns that exist. Names beyond the known columns get neither.
+pub(crate) fn rename_columns(
+    columns: Option<Vec<ColumnLineage>>,
+    names: Vec<String>,
+) -> Vec<ColumnLineage> {
+    let mut columns = columns.unwrap_or_default().into_iter();
+    names
+        .into_iter()
+        .map(|name| match columns.next() {
+            Some(column) => ColumnLineage {
+                name: Some(name),
+                ..column
+            },
+            None => ColumnLineage {
+                name: Some(name),
+                sources: vec![],
+                data_type: None,
+            },
+        })
+        .collect()
+}
+
+fn table_name(factor: &TableFactor) -> Option<&ObjectName> {
+    match factor {
+        TableFactor::Table { name, .. } => Some(name),
+        _ => None,
+    }
+}
+
+/// The target's column list, when one is given, names the columns written.
+fn renamed(columns: Option<Vec<ColumnLineage>>, names: Vec<String>) -> Option<Vec<ColumnLineage>> {
+    match names.is_empty() {
+        true => columns,
+        false => Some(rename_columns(columns, names)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::StatementLineage;
+    use crate::binder::binder::Binder;
+    use crate::parser::parser::Parser;
+
+    /// Each column with its sources written as `TABLE.COLUMN Kind`.
+    fn describe(lineage: &StatementLineage) -> Vec<(String, Vec<String>)> {
+        lineage
+            .columns
+            .iter()
+            .flatten()
+            .map(|column| {
+                let sources = column.sources.iter();
+                (
+                    column.name.clone().unwrap_or_default(),
+                    sources
+                        .map(|source| format!("{} {:?}", source, source.kind))
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn traces_columns_through_ctes_to_base_tables() -> Result<()> {
+        let sql = std::fs::read_to_string("scripts/input.sql")?;
+        let statement = Parser::from_sql(&sql).parse_statement()?;
+        let lineage = Binder::new().lineage(&statement).unwrap();
+
+        let filters = ["RAW.EMPLOYEES.ID Filter", "RAW.SALARIES.ID Filter"];
+        let with_filters = |source: &str| {
+            std::iter::once(source)
+                .chain(filters)
+                .map(String::from)
+                .collect()
+        };
+        assert_eq!(lineage.target, Some(vec!["COMBINED_TABLE".to_string()]));
+        assert_eq!(
+            describe(&lineage),
+            vec![
+                ("ID".into(), with_filters("RAW.EMPLOYEES.ID Direct")),
+                ("NAME".into(), with_filters("RAW.EMPLOYEES.NAME Direct")),
+                (
+                    "SALARY_RANK".into(),
+                    with_filters("RAW.SALARIES.SALARY Transformation")
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn classifies_aggregations_and_assignments() -> Result<()> {
+        let sql = "
+INSERT INTO daily_totals (day, total)
+SELECT DATE_TRUNC(day, o.created_at), SUM(o.amount) FROM sales.orders o
+WHERE o.status = 'paid' GROUP BY 1;
+UPDATE customers SET email = LOWER(s.email) FROM staging s WHERE customers.id = s.id";
+        let mut binder =
+            Binder::new().with_table("DB.SALES.ORDERS", &["ID", "AMOUNT", "STATUS", "CREATED_AT"]);
+        let statements = Parser::from_sql(sql).parse_statements()?;
+
+        let insert = binder.lineage(&statements[0]).unwrap();
+        assert_eq!(insert.target, Some(vec!["DAILY_TOTALS".to_string()]));
+        assert_eq!(
+            describe(&insert),
+            vec![
+                (
+                    "DAY".into(),
+                    vec![
+                        "DB.SALES.ORDERS.CREATED_AT Transformation".into(),
+                        "DB.SALES.ORDERS.STATUS Filter".into(),
+                    ]
+                ),
+                (
+                    "TOTAL".into(),
+                    vec![
+                        "DB.SALES.ORDERS.AMOUNT Aggregation".into(),
+                        "DB.SALES.ORDERS.STATUS Filter".into(),
+                    ]
+                ),
+            ]
+        );
+
+        let update = binder.lineage(&statements[1]).unwrap();
+        assert_eq!(
+            describe(&update),
+            vec![(
+                "EMAIL".into(),
+                vec![
+                    "STAGING.EMAIL Transformation".into(),
+                    "CUSTOMERS.ID Filter".into(),
+                    "STAGING.ID Filter".into(),
+                ]
+            )]
+        );
+
+        Ok(())
+    }
+}
@@ -1,7 +1,382 @@
-mod ast;
-mod lexer;
-mod parser;
+use std::io::Write;
+use std::process::ExitCode;
 
-fn main() {
-    println!("Hello, world!");
+use snowflake_lexer::binder::binder::Binder;
+use snowflake_lexer::catalog::catalog::Catalog;
+use snowflake_lexer::diagnostics::diagnostics::{Diagnostic, Renderer};
+use snowflake_lexer::export::export::Graph;
+use snowflake_lexer::graph::graph::DependencyGraph;
+use snowflake_lexer::openlineage::openlineage::RunEvent;
+use snowflake_lexer::parser::parser::Parser;
+use snowflake_lexer::splitter::splitter::split_statements;
+
+const USAGE: &str = "\
+Usage: snowflake_lexer <command> <file>...
+
+Commands:
+  check    Report the syntax errors in each file
+  split    Print the statements of each file, one per line
+  deps     Print the files, including the .sql files in directories, in
+           deployment order, reporting dependency cycles
+  graph    Print the dependencies between the objects of the files, with
+           --format=dot|mermaid|json (default dot), --upstream=<object> or
+           --downstream=<object> to keep what it's built from or feeds, and
+           --collapse-ctes to hide the CTEs of each statement
+  openlineage
+           Print an OpenLineage RunEvent per file, one per line, with
+           --namespace=<dataset namespace> (default snowflake://default),
+           --job-namespace=<namespace> (default sql), and --database=<name>
+           and --schema=<name> qualifying partial table names
+  expand   Print each file with its SELECT * lists written out, knowing the
+           tables created in the files and in each --catalog=<file>, which
+           may also be an INFORMATION_SCHEMA.COLUMNS export in .csv or .json
+  types    Print the output columns of each statement with their inferred
+           types, and warn of implicit casts, knowing the tables created in
+           the files before it and in each --catalog=<file>";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, files)) = args.split_first() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let renderer = Renderer::for_stream(&std::io::stderr());
+    match command.as_str() {
+        "deps" => return deps(files, &renderer),
+        "graph" => return graph(files, &renderer),
+        "openlineage" => return openlineage(files, &renderer),
+        "expand" => return expand(files, &renderer),
+        "types" => return types(files, &renderer),
+        _ => {}
+    }
+
+    let run: fn(&str, &str, &Renderer) -> usize = match command.as_str() {
+        "check" => check,
+        "split" => split,
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut errors = 0;
+    for file in files {
+        match std::fs::read_to_string(file) {
+            Ok(source) => errors += run(file, &source, &renderer),
+            Err(error) => {
+                let diagnostic = Diagnostic::error(format!("Failed to read {}: {}", file, error));
+                eprint!("{}", renderer.render(&diagnostic, file, ""));
+                errors += 1;
+            }
+        }
+    }
+
+    if errors > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Prints every syntax error in `source`, returning how many there were.
+fn check(file: &str, source: &str, renderer: &Renderer) -> usize {
+    let (_, errors) = Parser::from_sql(source).parse_statements_with_recovery();
+    for error in &errors {
+        eprint!("{}", renderer.render(&error.into(), file, source));
+    }
+    errors.len()
+}
+
+/// Prints each statement of `source` on one line along with its kind.
+fn split(_file: &str, source: &str, _renderer: &Renderer) -> usize {
+    let mut stdout = std::io::stdout().lock();
+    for statement in split_statements(source) {
+        let text = statement
+            .text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = writeln!(stdout, "{:?}\t{}", statement.kind, text);
+    }
+    0
+}
+
+/// Loads the files in `paths`, reporting their syntax errors, along with
+/// whether there were any, or `None` if one can't be read.
+fn load_graph(paths: &[String], renderer: &Renderer) -> Option<(DependencyGraph, bool)> {
+    let mut graph = DependencyGraph::new();
+    for path in paths {
+        let added = match std::fs::metadata(path) {
+            Ok(metadata) if metadata.is_dir() => graph.add_directory(path),
+            _ => std::fs::read_to_string(path)
+                .map_err(|error| anyhow::anyhow!("Failed to read {}: {}", path, error))
+                .map(|source| graph.add_file(path, &source)),
+        };
+        if let Err(error) = added {
+            let diagnostic = Diagnostic::error(error.to_string());
+            eprint!("{}", renderer.render(&diagnostic, path, ""));
+            return None;
+        }
+    }
+
+    let mut failed = false;
+    for file in &graph.files {
+        let name = file.path.display().to_string();
+        for error in &file.errors {
+            eprint!("{}", renderer.render(&error.into(), &name, &file.source));
+            failed = true;
+        }
+    }
+    Some((graph, failed))
+}
+
+/// Prints the files in `paths` in the order they can be deployed.
+fn deps(paths: &[String], renderer: &Renderer) -> ExitCode {
+    let Some((graph, failed)) = load_graph(paths, renderer) else {
+        return ExitCode::FAILURE;
+    };
+
+    match graph.file_order() {
+        Ok(order) => {
+            let mut stdout = std::io::stdout().lock();
+            for path in order {
+                let _ = writeln!(stdout, "{}", path.display());
+            }
+            if failed {
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            }
+        }
+        Err(cycle) => {
+            let diagnostic = Diagnostic::error(cycle.to_string());
+            eprint!("{}", renderer.render(&diagnostic, &cycle.0[0], ""));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Prints the object dependencies of the files among `args` in the format
+/// the options ask for.
+fn graph(args: &[String], renderer: &Renderer) -> ExitCode {
+    let (options, paths): (Vec<_>, Vec<_>) =
+        args.iter().cloned().partition(|arg| arg.starts_with("--"));
+    let mut format = "dot".to_string();
+    let (mut upstream, mut downstream, mut collapse_ctes) = (None, None, false);
+    for option in &options {
+        match option.split_once('=') {
+            Some(("--format", value)) if ["dot", "mermaid", "json"].contains(&value) => {
+                format = value.to_string()
+            }
+            Some(("--upstream", value)) => upstream = Some(value.to_uppercase()),
+            Some(("--downstream", value)) => downstream = Some(value.to_uppercase()),
+            None if option == "--collapse-ctes" => collapse_ctes = true,
+            _ => {
+                eprintln!("Unknown option {}\n\n{}", option, USAGE);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let Some((dependencies, failed)) = load_graph(&paths, renderer) else {
+        return ExitCode::FAILURE;
+    };
+    let mut graph = Graph::from_dependencies(&dependencies, collapse_ctes);
+    if let Some(object) = &upstream {
+        graph = graph.upstream(object);
+    }
+    if let Some(object) = &downstream {
+        graph = graph.downstream(object);
+    }
+
+    let output = match format.as_str() {
+        "mermaid" => graph.to_mermaid(),
+        "json" => format!("{:#}\n", graph.to_json()),
+        _ => graph.to_dot(),
+    };
+    print!("{}", output);
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Prints a lineage event for each file among `args`, with the datasets
+/// named as the options ask.
+fn openlineage(args: &[String], renderer: &Renderer) -> ExitCode {
+    let (options, paths): (Vec<_>, Vec<_>) = args.iter().partition(|arg| arg.starts_with("--"));
+    let mut settings = RunEvent::new("sql", "", "snowflake://default");
+    for option in options {
+        match option.split_once('=') {
+            Some(("--namespace", value)) => settings.dataset_namespace = value.to_string(),
+            Some(("--job-namespace", value)) => settings.job_namespace = value.to_string(),
+            Some(("--database", value)) => settings.database = Some(value.to_uppercase()),
+            Some(("--schema", value)) => settings.schema = Some(value.to_uppercase()),
+            _ => {
+                eprintln!("Unknown option {}\n\n{}", option, USAGE);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let mut failed = false;
+    let mut stdout = std::io::stdout().lock();
+    for path in paths {
+        let statements = match std::fs::read_to_string(path) {
+            Ok(source) => {
+                let (statements, errors) =
+                    Parser::from_sql(&source).parse_statements_with_recovery();
+                for error in &errors {
+                    eprint!("{}", renderer.render(&error.into(), path, &source));
+                }
+                failed |= !errors.is_empty();
+                statements
+            }
+            Err(error) => {
+                let diagnostic = Diagnostic::error(format!("Failed to read {}: {}", path, error));
+                eprint!("{}", renderer.render(&diagnostic, path, ""));
+                failed = true;
+                continue;
+            }
+        };
+
+        let mut event = RunEvent::new(&settings.job_namespace, path, &settings.dataset_namespace);
+        event.database.clone_from(&settings.database);
+        event.schema.clone_from(&settings.schema);
+        let mut binder = Binder::new();
+        for statement in &statements {
+            event.add_statement(statement, &mut binder);
+        }
+        let _ = writeln!(stdout, "{}", event.to_json());
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Loads the tables of each `--catalog=<file>` among `args`, and reads the
+/// other files, adding their tables too when `add_inputs` is set. Reports
+/// why and returns `None` if a file can't be loaded.
+fn load_catalog(
+    args: &[String],
+    add_inputs: bool,
+    renderer: &Renderer,
+) -> Option<(Catalog, Vec<(String, String)>)> {
+    let mut catalog_paths = Vec::new();
+    let mut paths = Vec::new();
+    for arg in args {
+        match arg.split_once('=') {
+            Some(("--catalog", path)) => catalog_paths.push(path.to_string()),
+            _ if arg.starts_with("--") => {
+                eprintln!("Unknown option {}\n\n{}", arg, USAGE);
+                return None;
+            }
+            _ => paths.push(arg.clone()),
+        }
+    }
+
+    let mut catalog = Catalog::new();
+    let mut sources = Vec::new();
+    for (path, is_input) in catalog_paths
+        .into_iter()
+        .map(|path| (path, false))
+        .chain(paths.into_iter().map(|path| (path, true)))
+    {
+        let added = std::fs::read_to_string(&path)
+            .map_err(|error| anyhow::anyhow!("Failed to read {}: {}", path, error))
+            .and_then(|source| {
+                if is_input && !add_inputs {
+                    return Ok(source);
+                }
+                match std::path::Path::new(&path).extension() {
+                    Some(extension) if extension == "csv" => catalog.add_columns_csv(&source),
+                    Some(extension) if extension == "json" => catalog.add_columns_json(&source),
+                    _ => {
+                        catalog.add_sql(&source);
+                        Ok(())
+                    }
+                }
+                .map_err(|error| anyhow::anyhow!("Failed to load {}: {:#}", path, error))?;
+                Ok(source)
+            });
+        match added {
+            Ok(source) if is_input => sources.push((path, source)),
+            Ok(_) => {}
+            Err(error) => {
+                let diagnostic = Diagnostic::error(error.to_string());
+                eprint!("{}", renderer.render(&diagnostic, &path, ""));
+                return None;
+            }
+        }
+    }
+    Some((catalog, sources))
+}
+
+/// Prints the files among `args` with their wildcards expanded, using the
+/// tables the files and the `--catalog` files create.
+fn expand(args: &[String], renderer: &Renderer) -> ExitCode {
+    let Some((catalog, sources)) = load_catalog(args, true, renderer) else {
+        return ExitCode::FAILURE;
+    };
+
+    let mut stdout = std::io::stdout().lock();
+    for (path, source) in sources {
+        match catalog.expand_wildcards(&source) {
+            Ok(expanded) => {
+                let _ = write!(stdout, "{}", expanded);
+            }
+            Err(error) => {
+                let diagnostic = Diagnostic::error(error.to_string());
+                eprint!("{}", renderer.render(&diagnostic, &path, &source));
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+/// Prints the output columns of each statement of the files among `args`
+/// with their inferred types, warning of implicit casts. Each statement sees
+/// the tables of the `--catalog` files and of the statements before it.
+fn types(args: &[String], renderer: &Renderer) -> ExitCode {
+    let Some((mut catalog, sources)) = load_catalog(args, false, renderer) else {
+        return ExitCode::FAILURE;
+    };
+
+    let mut stdout = std::io::stdout().lock();
+    for (path, source) in sources {
+        let (statements, errors) = Parser::from_sql(&source).parse_statements_with_spans();
+        for error in &errors {
+            eprint!("{}", renderer.render(&error.into(), &path, &source));
+        }
+
+        for (statement, span) in &statements {
+            let mut binder = catalog.binder();
+            if let Some(lineage) = binder.lineage(statement) {
+                let target = lineage
+                    .target
+                    .map_or("(query)".to_string(), |name| name.join("."));
+                let _ = writeln!(stdout, "{}", target);
+                for column in lineage.columns.into_iter().flatten() {
+                    let data_type = column.data_type.map(|data_type| data_type.to_string());
+                    let _ = writeln!(
+                        stdout,
+                        "  {}\t{}",
+                        column.name.as_deref().unwrap_or("?"),
+                        data_type.as_deref().unwrap_or("?")
+                    );
+                }
+            }
+            for cast in binder.implicit_casts(statement, *span) {
+                eprint!("{}", renderer.render(&(&cast).into(), &path, &source));
+            }
+            catalog.add_statement(statement);
+        }
+    }
+    ExitCode::SUCCESS
 }
@@ -0,0 +1,321 @@
+//! OpenLineage `RunEvent`s built from static analysis, so lineage can be
+//! pushed to a catalog without running the SQL.
+//!
+//! Each statement's reads become input datasets and its writes output
+//! datasets, with the `columnLineage` facet on outputs whose columns are
+//! known. See <https://openlineage.io/docs/spec/object-model>.
+
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::{BuildHasher, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::ast::statement::Statement;
+use crate::binder::binder::Binder;
+use crate::graph::graph::{name_parts, ObjectAccess};
+use crate::json::json::Json;
+use crate::lineage::lineage::{LineageKind, SourceColumn};
+
+pub const PRODUCER: &str = concat!(
+    "https://github.com/culotta2/sql-snowflake-parser/tree/v",
+    env!("CARGO_PKG_VERSION")
+);
+const RUN_EVENT_SCHEMA: &str = "https://openlineage.io/spec/2-0-2/OpenLineage.json#/$defs/RunEvent";
+const COLUMN_LINEAGE_SCHEMA: &str = "https://openlineage.io/spec/facets/1-2-0/ColumnLineageDatasetFacet.json#/$defs/ColumnLineageDatasetFacet";
+
+/// A `COMPLETE` event for one job, e.g. one SQL file.
+#[derive(Clone, Debug)]
+pub struct RunEvent {
+    /// A UUID, random unless set.
+    pub run_id: String,
+    /// An ISO 8601 UTC timestamp, the creation time unless set.
+    pub event_time: String,
+    pub job_namespace: String,
+    pub job_name: String,
+    /// The namespace of every dataset, e.g. `snowflake://myorg-account1`.
+    pub dataset_namespace: String,
+    /// The database and schema qualifying partial table names, since
+    /// OpenLineage names Snowflake tables `DATABASE.SCHEMA.TABLE`.
+    pub database: Option<String>,
+    pub schema: Option<String>,
+    inputs: BTreeSet<Vec<String>>,
+    /// Each output's columns with the source columns each derives from.
+    outputs: BTreeMap<Vec<String>, BTreeMap<String, BTreeSet<SourceField>>>,
+}
+
+/// A column an output column derives from, with OpenLineage's name for how.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct SourceField {
+    table: Vec<String>,
+    column: String,
+    kind: (&'static str, &'static str),
+}
+
+impl RunEvent {
+    pub fn new(job_namespace: &str, job_name: &str, dataset_namespace: &str) -> Self {
+        RunEvent {
+            run_id: random_uuid(),
+            event_time: format_timestamp(SystemTime::now()),
+            job_namespace: job_namespace.to_string(),
+            job_name: job_name.to_string(),
+            dataset_namespace: dataset_namespace.to_string(),
+            database: None,
+            schema: None,
+            inputs: BTreeSet::new(),
+            outputs: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the datasets `statement` reads and writes, and the lineage of
+    /// the columns it writes as traced by `binder`. Stages aren't datasets
+    /// and are left out.
+    pub fn add_statement(&mut self, statement: &Statement, binder: &mut Binder) {
+        let access = ObjectAccess::of(statement);
+        let split = |name: &String| -> Option<Vec<String>> {
+            (!name.starts_with('@')).then(|| name_parts(name))
+        };
+        self.inputs.extend(access.reads.iter().filter_map(split));
+        for output in access.outputs().filter_map(split) {
+            self.outputs.entry(output).or_default();
+        }
+
+        let Some(lineage) = binder.lineage(statement) else {
+            return;
+        };
+        let Some(target) = lineage.target else {
+            return;
+        };
+        let fields = self.outputs.entry(target).or_default();
+        for column in lineage.columns.into_iter().flatten() {
+            let Some(name) = column.name else {
+                continue;
+            };
+            fields
+                .entry(name)
+                .or_default()
+                .extend(column.sources.into_iter().map(SourceField::from));
+        }
+    }
+
+    pub fn to_json(&self) -> Json {
+        let inputs = self.inputs.iter().map(|name| self.dataset(name, vec![]));
+        let outputs = self.outputs.iter().map(|(name, fields)| {
+            let facets = match fields.is_empty() {
+                true => vec![],
+                false => vec![("columnLineage", self.column_lineage(fields))],
+            };
+            self.dataset(name, facets)
+        });
+
+        Json::object([
+            ("eventType", Json::from("COMPLETE")),
+            ("eventTime", Json::from(self.event_time.as_str())),
+            (
+                "run",
+                Json::object([("runId", Json::from(self.run_id.as_str()))]),
+            ),
+            (
+                "job",
+                Json::object([
+                    ("namespace", Json::from(self.job_namespace.as_str())),
+                    ("name", Json::from(self.job_name.as_str())),
+                ]),
+            ),
+            ("inputs", Json::Array(inputs.collect())),
+            ("outputs", Json::Array(outputs.collect())),
+            ("producer", Json::from(PRODUCER)),
+            ("schemaURL", Json::from(RUN_EVENT_SCHEMA)),
+        ])
+    }
+
+    fn dataset(&self, name: &[String], facets: Vec<(&str, Json)>) -> Json {
+        let mut dataset = vec![
+            ("namespace", Json::from(self.dataset_namespace.as_str())),
+            ("name", Json::from(self.qualify(name))),
+        ];
+        if !facets.is_empty() {
+            dataset.push(("facets", Json::object(facets)));
+        }
+        Json::object(dataset)
+    }
+
+    fn column_lineage(&self, fields: &BTreeMap<String, BTreeSet<SourceField>>) -> Json {
+        let fields = fields.iter().map(|(name, sources)| {
+            let input_fields = sources.iter().map(|source| {
+                let (kind, subtype) = source.kind;
+                Json::object([
+                    ("namespace", Json::from(self.dataset_namespace.as_str())),
+                    ("name", Json::from(self.qualify(&source.table))),
+                    ("field", Json::from(source.column.as_str())),
+                    (
+                        "transformations",
+                        Json::Array(vec![Json::object([
+                            ("type", Json::from(kind)),
+                            ("subtype", Json::from(subtype)),
+                        ])]),
+                    ),
+                ])
+            });
+            (
+                name.clone(),
+                Json::object([("inputFields", Json::Array(input_fields.collect()))]),
+            )
+        });
+
+        Json::object([
+            ("_producer", Json::from(PRODUCER)),
+            ("_schemaURL", Json::from(COLUMN_LINEAGE_SCHEMA)),
+            ("fields", Json::object(fields)),
+        ])
+    }
+
+    /// `name` prefixed with the default database and schema it omits.
+    fn qualify(&self, name: &[String]) -> String {
+        let prefix = match (name.len(), &self.database, &self.schema) {
+            (1, Some(database), Some(schema)) => vec![database, schema],
+            (1, None, Some(schema)) => vec![schema],
+            (2, Some(database), _) => vec![database],
+            _ => vec![],
+        };
+        prefix
+            .into_iter()
+            .chain(name)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
+impl From<SourceColumn> for SourceField {
+    fn from(source: SourceColumn) -> Self {
+        let kind = match source.kind {
+            LineageKind::Direct => ("DIRECT", "IDENTITY"),
+            LineageKind::Transformation => ("DIRECT", "TRANSFORMATION"),
+            LineageKind::Aggregation => ("DIRECT", "AGGREGATION"),
+            LineageKind::Filter => ("INDIRECT", "FILTER"),
+        };
+        SourceField {
+            table: source.table,
+            column: source.column,
+            kind,
+        }
+    }
+}
+
+/// A version 4 UUID. The standard library has no random number generator,
+/// but each `RandomState` is seeded randomly.
+pub fn random_uuid() -> String {
+    let random = || RandomState::new().build_hasher().finish();
+    let high = random() & !0xf000 | 0x4000;
+    let low = random() & !(0xc << 60) | (0x8 << 60);
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        high >> 32,
+        (high >> 16) & 0xffff,
+        high & 0xffff,
+        low >> 48,
+        low & 0xffff_ffff_ffff
+    )
+}
+
+/// `time` as e.g. `2024-05-01T12:30:00.000Z`.
+pub fn format_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (days, seconds) = ((seconds / 86_400) as i64, seconds % 86_400);
+
+    // Converts days since 1970-01-01 to a civil date, after Howard Hinnant's
+    // `civil_from_days`
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use anyhow::Result;
+
+    use super::{format_timestamp, random_uuid, RunEvent};
+    use crate::binder::binder::Binder;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn formats_timestamps_and_uuids() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_709_210_096_789);
+        assert_eq!(format_timestamp(time), "2024-02-29T12:34:56.789Z");
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+
+        let uuid = random_uuid();
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+        assert!("89ab".contains(&uuid[19..20]));
+        assert_ne!(uuid, random_uuid());
+    }
+
+    #[test]
+    fn emits_datasets_and_column_lineage() -> Result<()> {
+        let sql = "
+INSERT INTO analytics.totals (customer, total)
+SELECT customer_id, SUM(amount) FROM raw.orders WHERE paid GROUP BY 1;
+COPY INTO raw.orders FROM @raw.landing";
+        let mut event = RunEvent::new("ci", "totals.sql", "snowflake://org-acct");
+        event.run_id = "00000000-0000-4000-8000-000000000000".into();
+        event.event_time = "2024-01-01T00:00:00.000Z".into();
+        event.database = Some("PROD".into());
+
+        let mut binder = Binder::new();
+        for statement in Parser::from_sql(sql).parse_statements()? {
+            event.add_statement(&statement, &mut binder);
+        }
+
+        let json = event.to_json().to_string();
+        let input = r#""inputs":[{"namespace":"snowflake://org-acct","name":"PROD.RAW.ORDERS"}]"#;
+        assert!(json.starts_with(concat!(
+            r#"{"eventType":"COMPLETE","eventTime":"2024-01-01T00:00:00.000Z","#,
+            r#""run":{"runId":"00000000-0000-4000-8000-000000000000"},"#,
+            r#""job":{"namespace":"ci","name":"totals.sql"},"#,
+        )));
+        assert!(json.contains(input));
+        assert!(json.contains(concat!(
+            r#""fields":{"CUSTOMER":{"inputFields":[{"namespace":"snowflake://org-acct","#,
+            r#""name":"PROD.RAW.ORDERS","field":"CUSTOMER_ID","transformations":"#,
+            r#"[{"type":"DIRECT","subtype":"IDENTITY"}]},"#,
+        )));
+        assert!(json.contains(
+            r#""field":"AMOUNT","transformations":[{"type":"DIRECT","subtype":"AGGREGATION"}]"#
+        ));
+        assert!(json.contains(
+            r#""field":"PAID","transformations":[{"type":"INDIRECT","subtype":"FILTER"}]"#
+        ));
+        // The COPY's output has no known columns, so no facet
+        assert!(json.contains(
+            r#"{"namespace":"snowflake://org-acct","name":"PROD.RAW.ORDERS"}],"producer""#
+        ));
+
+        Ok(())
+    }
+}
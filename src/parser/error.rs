@@ -0,0 +1,34 @@
+use std::fmt;
+
+use crate::lexer::lexer::{LexError, Span};
+
+/// A syntax error, located by the span of the token the parser stopped at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    /// What the parser was looking for, when it was looking for something
+    /// specific.
+    pub expected: Option<String>,
+    /// The token found instead.
+    pub found: String,
+    pub span: Span,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<LexError> for ParseError {
+    fn from(error: LexError) -> Self {
+        ParseError {
+            message: error.message,
+            expected: None,
+            found: "Illegal".to_string(),
+            span: error.span,
+        }
+    }
+}
@@ -0,0 +1,1138 @@
+use anyhow::{bail, Result};
+
+use crate::ast::data_type::{DataType, TimestampTimeZone};
+use crate::ast::expression::{
+    BinaryOperator, Expr, FunctionArg, FunctionCall, Ident, NullTreatment, ObjectName,
+    UnaryOperator, Value, WindowFrame, WindowFrameBound, WindowFrameUnits, WindowSpec,
+};
+use crate::lexer::lexer::{
+    DDLKeyword, DMLKeyword, DataTypeKeyword, JoinType, Keyword, Logical, Token, Word,
+};
+use crate::parser::parser::Parser;
+
+const OR_PRECEDENCE: u8 = 5;
+const AND_PRECEDENCE: u8 = 10;
+const NOT_PRECEDENCE: u8 = 15;
+const COMPARISON_PRECEDENCE: u8 = 20;
+const PLUS_MINUS_PRECEDENCE: u8 = 30;
+const MULTIPLY_PRECEDENCE: u8 = 40;
+const UNARY_PRECEDENCE: u8 = 50;
+const POSTFIX_PRECEDENCE: u8 = 60;
+
+/// Reserved functions that may be called without parentheses.
+const NILADIC_FUNCTIONS: [&str; 6] = [
+    "current_date",
+    "current_time",
+    "current_timestamp",
+    "current_user",
+    "localtime",
+    "localtimestamp",
+];
+
+/// Type names that aren't keywords, so they're lexed as identifiers.
+const TYPE_NAMES: [&str; 19] = [
+    "array",
+    "date",
+    "datetime",
+    "decimal",
+    "geography",
+    "geometry",
+    "map",
+    "numeric",
+    "object",
+    "time",
+    "timestamp",
+    "timestamp_ltz",
+    "timestamp_ntz",
+    "timestamp_tz",
+    "timestampltz",
+    "timestampntz",
+    "timestamptz",
+    "variant",
+    "vector",
+];
+
+impl<'a> Parser<'a> {
+    pub fn parse_expr(&mut self) -> Result<Expr> {
+        self.parse_subexpr(0)
+    }
+
+    fn parse_subexpr(&mut self, precedence: u8) -> Result<Expr> {
+        let mut expr = self.parse_prefix()?;
+        loop {
+            let next_precedence = self.next_precedence();
+            if precedence >= next_precedence {
+                break;
+            }
+
+            expr = self.parse_infix(expr, next_precedence)?;
+        }
+
+        Ok(expr)
+    }
+
+    fn next_precedence(&self) -> u8 {
+        if let Token::Word(_) = self.peek_token() {
+            return match self.peek_keyword() {
+                Some(Keyword::Logical(Logical::Or)) => OR_PRECEDENCE,
+                Some(Keyword::Logical(Logical::And)) => AND_PRECEDENCE,
+                Some(Keyword::Logical(Logical::Not)) => match self.peek_nth_keyword(1) {
+                    Some(Keyword::In | Keyword::Like | Keyword::Ilike | Keyword::Between) => {
+                        COMPARISON_PRECEDENCE
+                    }
+                    _ => 0,
+                },
+                Some(
+                    Keyword::Is | Keyword::In | Keyword::Like | Keyword::Ilike | Keyword::Between,
+                ) => COMPARISON_PRECEDENCE,
+                _ => 0,
+            };
+        }
+
+        match self.peek_token() {
+            Token::Equal
+            | Token::NotEqual
+            | Token::GreaterThan
+            | Token::GreaterThanEqual
+            | Token::LessThan
+            | Token::LessThanEqual => COMPARISON_PRECEDENCE,
+            Token::Plus | Token::Minus | Token::StringConcat => PLUS_MINUS_PRECEDENCE,
+            Token::Asterisk | Token::Slash | Token::Modulo => MULTIPLY_PRECEDENCE,
+            Token::DoubleColon | Token::Colon | Token::OpenBracket => POSTFIX_PRECEDENCE,
+            _ => 0,
+        }
+    }
+
+    fn parse_prefix(&mut self) -> Result<Expr> {
+        let expr = match self.next_token() {
+            Token::Int(value) => Expr::Value(Value::Int(value)),
+            Token::Decimal(value) => Expr::Value(Value::Decimal(value.into_owned())),
+            Token::Float(value) => Expr::Value(Value::Float(value)),
+            Token::Varchar(literal) => Expr::Value(Value::Varchar(literal.value.into_owned())),
+            Token::HexString(literal) => Expr::Value(Value::Binary(literal.value)),
+            Token::DollarString(value) => Expr::Value(Value::Varchar(value.into_owned())),
+            Token::Bool(value) => Expr::Value(Value::Bool(value)),
+            Token::Null => Expr::Value(Value::Null),
+            Token::Minus => Expr::UnaryOp {
+                op: UnaryOperator::Minus,
+                expr: Box::new(self.parse_subexpr(UNARY_PRECEDENCE)?),
+            },
+            Token::Plus => Expr::UnaryOp {
+                op: UnaryOperator::Plus,
+                expr: Box::new(self.parse_subexpr(UNARY_PRECEDENCE)?),
+            },
+            Token::Colon => Expr::Variable(self.parse_identifier()?),
+            Token::QuestionMark => Expr::Placeholder,
+            Token::OpenBracket => {
+                let elements = if self.peek_token() == &Token::CloseBracket {
+                    vec![]
+                } else {
+                    self.parse_comma_separated(Parser::parse_expr)?
+                };
+                self.expect_token(&Token::CloseBracket)?;
+                Expr::Array(elements)
+            }
+            Token::OpenBrace => {
+                let fields = if self.peek_token() == &Token::CloseBrace {
+                    vec![]
+                } else {
+                    self.parse_comma_separated(|parser| {
+                        // Keys are plain literals, so `:` isn't mistaken for
+                        // semi-structured access
+                        let key = parser.parse_prefix()?;
+                        parser.expect_token(&Token::Colon)?;
+                        Ok((key, parser.parse_expr()?))
+                    })?
+                };
+                self.expect_token(&Token::CloseBrace)?;
+                Expr::Object(fields)
+            }
+            Token::SessionVariable(name) => Expr::SessionVariable(Ident::new(name)),
+            Token::Positional(position) => Expr::Positional(position),
+            Token::Asterisk => Expr::Wildcard,
+            Token::OpenParen if self.peek_lambda_params() => {
+                let params = self.parse_comma_separated(Parser::parse_identifier)?;
+                self.expect_token(&Token::CloseParen)?;
+                self.expect_token(&Token::Arrow)?;
+                Expr::Lambda {
+                    params,
+                    body: Box::new(self.parse_expr()?),
+                }
+            }
+            Token::OpenParen => {
+                let expr = if self.peek_query_start() {
+                    Expr::Subquery(Box::new(self.parse_query()?))
+                } else {
+                    Expr::Nested(Box::new(self.parse_expr()?))
+                };
+                self.expect_token(&Token::CloseParen)?;
+                expr
+            }
+            Token::Word(word) => self.parse_word_expr(word)?,
+            token => return Err(self.unexpected("an expression", &token).into()),
+        };
+
+        Ok(expr)
+    }
+
+    /// Parses an expression starting with a word: a keyword construct such as
+    /// `CASE` or `DATE '2024-01-01'`, a lambda, a function call or a column.
+    fn parse_word_expr(&mut self, word: Word) -> Result<Expr> {
+        match word.keyword {
+            Some(Keyword::Logical(Logical::Not)) => {
+                if self.consume_keyword(Keyword::Exists) {
+                    return self.parse_exists(true);
+                }
+
+                return Ok(Expr::UnaryOp {
+                    op: UnaryOperator::Not,
+                    expr: Box::new(self.parse_subexpr(NOT_PRECEDENCE)?),
+                });
+            }
+            Some(Keyword::Case) => return self.parse_case_expr(),
+            Some(Keyword::Exists) => return self.parse_exists(false),
+            Some(Keyword::Cast) => return self.parse_cast(false),
+            _ => {}
+        }
+
+        if word.quote_style.is_none() {
+            let value = word.value.to_lowercase();
+            match (value.as_str(), self.peek_token()) {
+                ("try_cast", Token::OpenParen) => return self.parse_cast(true),
+                ("interval", Token::Varchar(_)) => {
+                    let Token::Varchar(literal) = self.next_token() else {
+                        unreachable!("checked by the match");
+                    };
+                    return Ok(Expr::Interval(literal.value.into_owned()));
+                }
+                (name, Token::Varchar(_)) if typed_literal_type(name).is_some() => {
+                    let (Some(data_type), Token::Varchar(literal)) =
+                        (typed_literal_type(name), self.next_token())
+                    else {
+                        unreachable!("checked by the match");
+                    };
+                    return Ok(Expr::TypedString {
+                        data_type,
+                        value: literal.value.into_owned(),
+                    });
+                }
+                _ => {}
+            }
+
+            // `CURRENT_DATE` and friends are reserved, and callable without
+            // parentheses
+            if NILADIC_FUNCTIONS.contains(&value.as_str()) {
+                let name = ObjectName(vec![Ident::new(word.value)]);
+                if self.peek_token() == &Token::OpenParen {
+                    return self.parse_function_call(name);
+                }
+
+                return Ok(Expr::Function(FunctionCall {
+                    name,
+                    args: vec![],
+                    distinct: false,
+                    null_treatment: None,
+                    within_group: vec![],
+                    over: None,
+                }));
+            }
+        }
+
+        if !word.is_column_name() {
+            // Reserved words that double as function names, e.g. `LEFT(name, 3)`
+            // or `RETURN TABLE(res)` in a procedure
+            if self.peek_token() == &Token::OpenParen && is_keyword_function(&word) {
+                return self.parse_function_call(ObjectName(vec![Ident::new(word.value)]));
+            }
+
+            return Err(self.unexpected("an expression", &Token::Word(word)).into());
+        }
+
+        let ident = Ident {
+            value: word.value.into_owned(),
+            quote_style: word.quote_style,
+        };
+        if self.consume_token(&Token::Arrow) {
+            return Ok(Expr::Lambda {
+                params: vec![ident],
+                body: Box::new(self.parse_expr()?),
+            });
+        }
+
+        if self.peek_token() == &Token::Period
+            && matches!(self.peek_nth_token(1), Token::Positional(_))
+        {
+            self.next_token();
+            let Token::Positional(position) = self.next_token() else {
+                unreachable!("checked by the guard");
+            };
+            return Ok(Expr::QualifiedPositional {
+                qualifier: ident,
+                position,
+            });
+        }
+
+        let mut idents = vec![ident];
+        while self.peek_token() == &Token::Period
+            && matches!(self.peek_nth_token(1), Token::Word(_))
+        {
+            self.next_token();
+            idents.push(self.parse_path_segment()?);
+        }
+
+        if self.peek_token() == &Token::OpenParen {
+            self.parse_function_call(ObjectName(idents))
+        } else if idents.len() == 1 {
+            Ok(Expr::Identifier(idents.remove(0)))
+        } else {
+            Ok(Expr::CompoundIdentifier(idents))
+        }
+    }
+
+    /// Parses a name following a `.` or `:`, where even reserved words are
+    /// unambiguous.
+    fn parse_path_segment(&mut self) -> Result<Ident> {
+        match self.next_token() {
+            Token::Word(word) => Ok(Ident {
+                value: word.value.into_owned(),
+                quote_style: word.quote_style,
+            }),
+            token => Err(self.unexpected("an identifier", &token).into()),
+        }
+    }
+
+    fn parse_infix(&mut self, expr: Expr, precedence: u8) -> Result<Expr> {
+        let token = self.next_token();
+        let op = match &token {
+            Token::Word(word) => match word.keyword {
+                Some(Keyword::Logical(Logical::Or)) => Some(BinaryOperator::Or),
+                Some(Keyword::Logical(Logical::And)) => Some(BinaryOperator::And),
+                _ => None,
+            },
+            Token::Equal => Some(BinaryOperator::Equal),
+            Token::NotEqual => Some(BinaryOperator::NotEqual),
+            Token::GreaterThan => Some(BinaryOperator::GreaterThan),
+            Token::GreaterThanEqual => Some(BinaryOperator::GreaterThanEqual),
+            Token::LessThan => Some(BinaryOperator::LessThan),
+            Token::LessThanEqual => Some(BinaryOperator::LessThanEqual),
+            Token::Plus => Some(BinaryOperator::Plus),
+            Token::Minus => Some(BinaryOperator::Minus),
+            Token::Asterisk => Some(BinaryOperator::Multiply),
+            Token::Slash => Some(BinaryOperator::Divide),
+            Token::Modulo => Some(BinaryOperator::Modulo),
+            Token::StringConcat => Some(BinaryOperator::StringConcat),
+            _ => None,
+        };
+
+        if let Some(op) = op {
+            return Ok(Expr::BinaryOp {
+                left: Box::new(expr),
+                op,
+                right: Box::new(self.parse_subexpr(precedence)?),
+            });
+        }
+
+        match token {
+            Token::DoubleColon => Ok(Expr::Cast {
+                expr: Box::new(expr),
+                data_type: self.parse_data_type()?,
+                try_cast: false,
+            }),
+            Token::Colon => {
+                let mut path = vec![self.parse_path_segment()?];
+                while self.peek_token() == &Token::Period
+                    && matches!(self.peek_nth_token(1), Token::Word(_))
+                {
+                    self.next_token();
+                    path.push(self.parse_path_segment()?);
+                }
+                Ok(Expr::JsonAccess {
+                    expr: Box::new(expr),
+                    path,
+                })
+            }
+            Token::OpenBracket => {
+                let index = self.parse_expr()?;
+                self.expect_token(&Token::CloseBracket)?;
+                Ok(Expr::Subscript {
+                    expr: Box::new(expr),
+                    index: Box::new(index),
+                })
+            }
+            Token::Word(word) => match word.keyword {
+                Some(Keyword::Is) => {
+                    let negated = self.consume_keyword(Keyword::Logical(Logical::Not));
+                    self.expect_token(&Token::Null)?;
+                    Ok(Expr::IsNull {
+                        expr: Box::new(expr),
+                        negated,
+                    })
+                }
+                Some(Keyword::Logical(Logical::Not)) => {
+                    let keyword = self.peek_keyword();
+                    self.next_token();
+                    self.parse_negatable_infix(expr, keyword, true)
+                }
+                keyword => self.parse_negatable_infix(expr, keyword, false),
+            },
+            token => Err(self.unexpected("an operator", &token).into()),
+        }
+    }
+
+    /// Parses the infix operators that may be preceded by `NOT`.
+    fn parse_negatable_infix(
+        &mut self,
+        expr: Expr,
+        keyword: Option<Keyword>,
+        negated: bool,
+    ) -> Result<Expr> {
+        match keyword {
+            Some(Keyword::In) => {
+                self.expect_token(&Token::OpenParen)?;
+                let in_expr = if self.peek_query_start() {
+                    Expr::InSubquery {
+                        expr: Box::new(expr),
+                        subquery: Box::new(self.parse_query()?),
+                        negated,
+                    }
+                } else {
+                    Expr::InList {
+                        expr: Box::new(expr),
+                        list: self.parse_comma_separated(Parser::parse_expr)?,
+                        negated,
+                    }
+                };
+                self.expect_token(&Token::CloseParen)?;
+                Ok(in_expr)
+            }
+            Some(Keyword::Between) => {
+                let low = self.parse_subexpr(COMPARISON_PRECEDENCE)?;
+                self.expect_keyword(Keyword::Logical(Logical::And))?;
+                let high = self.parse_subexpr(COMPARISON_PRECEDENCE)?;
+                Ok(Expr::Between {
+                    expr: Box::new(expr),
+                    low: Box::new(low),
+                    high: Box::new(high),
+                    negated,
+                })
+            }
+            Some(Keyword::Like | Keyword::Ilike) => Ok(Expr::Like {
+                expr: Box::new(expr),
+                pattern: Box::new(self.parse_subexpr(COMPARISON_PRECEDENCE)?),
+                case_insensitive: keyword == Some(Keyword::Ilike),
+                negated,
+            }),
+            keyword => bail!("Expected an operator, found {:?}", keyword),
+        }
+    }
+
+    fn parse_exists(&mut self, negated: bool) -> Result<Expr> {
+        self.expect_token(&Token::OpenParen)?;
+        let subquery = self.parse_query()?;
+        self.expect_token(&Token::CloseParen)?;
+        Ok(Expr::Exists {
+            subquery: Box::new(subquery),
+            negated,
+        })
+    }
+
+    fn parse_case_expr(&mut self) -> Result<Expr> {
+        let operand = if self.peek_keyword() != Some(Keyword::When) {
+            Some(Box::new(self.parse_expr()?))
+        } else {
+            None
+        };
+
+        let mut conditions = Vec::new();
+        while self.consume_keyword(Keyword::When) {
+            let condition = self.parse_expr()?;
+            self.expect_keyword(Keyword::Then)?;
+            conditions.push((condition, self.parse_expr()?));
+        }
+
+        let else_result = if self.consume_keyword(Keyword::Else) {
+            Some(Box::new(self.parse_expr()?))
+        } else {
+            None
+        };
+        self.expect_keyword(Keyword::End)?;
+
+        Ok(Expr::Case {
+            operand,
+            conditions,
+            else_result,
+        })
+    }
+
+    fn parse_cast(&mut self, try_cast: bool) -> Result<Expr> {
+        self.expect_token(&Token::OpenParen)?;
+        let expr = self.parse_expr()?;
+        self.expect_keyword(Keyword::As)?;
+        let data_type = self.parse_data_type()?;
+        self.expect_token(&Token::CloseParen)?;
+
+        Ok(Expr::Cast {
+            expr: Box::new(expr),
+            data_type,
+            try_cast,
+        })
+    }
+
+    pub(crate) fn parse_function_call(&mut self, name: ObjectName) -> Result<Expr> {
+        self.expect_token(&Token::OpenParen)?;
+        let distinct = self.consume_keyword(Keyword::Distinct);
+        if !distinct {
+            self.consume_keyword(Keyword::All);
+        }
+
+        let args = if self.peek_token() == &Token::CloseParen {
+            vec![]
+        } else {
+            self.parse_comma_separated(Parser::parse_function_arg)?
+        };
+        // `FIRST_VALUE(x IGNORE NULLS)` and `FIRST_VALUE(x) IGNORE NULLS` are
+        // both accepted
+        let mut null_treatment = self.parse_null_treatment()?;
+        self.expect_token(&Token::CloseParen)?;
+        if null_treatment.is_none() {
+            null_treatment = self.parse_null_treatment()?;
+        }
+
+        let within_group = if self.consume_word("within") {
+            self.expect_keyword(Keyword::Group)?;
+            self.expect_token(&Token::OpenParen)?;
+            self.expect_keyword(Keyword::Order)?;
+            self.expect_keyword(Keyword::By)?;
+            let order_by = self.parse_comma_separated(Parser::parse_order_by_expr)?;
+            self.expect_token(&Token::CloseParen)?;
+            order_by
+        } else {
+            vec![]
+        };
+
+        let over = if self.consume_keyword(Keyword::Over) {
+            Some(self.parse_window_spec()?)
+        } else {
+            None
+        };
+
+        Ok(Expr::Function(FunctionCall {
+            name,
+            args,
+            distinct,
+            null_treatment,
+            within_group,
+            over,
+        }))
+    }
+
+    /// Parses `expr` or a named `name => expr` argument.
+    pub(crate) fn parse_function_arg(&mut self) -> Result<FunctionArg> {
+        if self.peek_identifier() && self.peek_nth_token(1) == &Token::FatArrow {
+            let name = self.parse_identifier()?;
+            self.expect_token(&Token::FatArrow)?;
+            return Ok(FunctionArg::Named {
+                name,
+                value: self.parse_expr()?,
+            });
+        }
+
+        Ok(FunctionArg::Unnamed(self.parse_expr()?))
+    }
+
+    fn parse_null_treatment(&mut self) -> Result<Option<NullTreatment>> {
+        let null_treatment = if self.peek_word("ignore") && self.peek_nth_word(1, "nulls") {
+            NullTreatment::IgnoreNulls
+        } else if self.peek_word("respect") && self.peek_nth_word(1, "nulls") {
+            NullTreatment::RespectNulls
+        } else {
+            return Ok(None);
+        };
+
+        self.next_token();
+        self.next_token();
+        Ok(Some(null_treatment))
+    }
+
+    fn parse_window_spec(&mut self) -> Result<WindowSpec> {
+        self.expect_token(&Token::OpenParen)?;
+
+        let partition_by = if self.consume_word("partition") {
+            self.expect_keyword(Keyword::By)?;
+            self.parse_comma_separated(Parser::parse_expr)?
+        } else {
+            vec![]
+        };
+
+        let order_by = if self.consume_keyword(Keyword::Order) {
+            self.expect_keyword(Keyword::By)?;
+            self.parse_comma_separated(Parser::parse_order_by_expr)?
+        } else {
+            vec![]
+        };
+
+        let window_frame = if self.peek_word("rows") || self.peek_word("range") {
+            Some(self.parse_window_frame()?)
+        } else {
+            None
+        };
+
+        self.expect_token(&Token::CloseParen)?;
+
+        Ok(WindowSpec {
+            partition_by,
+            order_by,
+            window_frame,
+        })
+    }
+
+    fn parse_window_frame(&mut self) -> Result<WindowFrame> {
+        let units = if self.consume_word("rows") {
+            WindowFrameUnits::Rows
+        } else {
+            self.expect_word("range")?;
+            WindowFrameUnits::Range
+        };
+
+        if self.consume_keyword(Keyword::Between) {
+            let start_bound = self.parse_window_frame_bound()?;
+            self.expect_keyword(Keyword::Logical(Logical::And))?;
+            let end_bound = Some(self.parse_window_frame_bound()?);
+            Ok(WindowFrame {
+                units,
+                start_bound,
+                end_bound,
+            })
+        } else {
+            Ok(WindowFrame {
+                units,
+                start_bound: self.parse_window_frame_bound()?,
+                end_bound: None,
+            })
+        }
+    }
+
+    fn parse_window_frame_bound(&mut self) -> Result<WindowFrameBound> {
+        if self.consume_word("current") {
+            self.expect_word("row")?;
+            return Ok(WindowFrameBound::CurrentRow);
+        }
+
+        let offset = if self.consume_word("unbounded") {
+            None
+        } else {
+            Some(Box::new(self.parse_prefix()?))
+        };
+
+        if self.consume_word("preceding") {
+            Ok(WindowFrameBound::Preceding(offset))
+        } else {
+            self.expect_word("following")?;
+            Ok(WindowFrameBound::Following(offset))
+        }
+    }
+
+    /// Whether the next token starts a data type.
+    pub(crate) fn peek_data_type(&self) -> bool {
+        match self.peek_token() {
+            Token::Word(Word {
+                value,
+                quote_style: None,
+                keyword,
+                ..
+            }) => {
+                matches!(keyword, Some(Keyword::DataType(_)))
+                    || TYPE_NAMES.contains(&value.to_lowercase().as_str())
+            }
+            _ => false,
+        }
+    }
+
+    pub(crate) fn parse_data_type(&mut self) -> Result<DataType> {
+        let data_type = match self.next_token() {
+            Token::Word(Word {
+                keyword: Some(Keyword::DataType(keyword)),
+                ..
+            }) => match keyword {
+                DataTypeKeyword::Number => self.parse_number_type()?,
+                DataTypeKeyword::Int => DataType::Int,
+                DataTypeKeyword::BigInt => DataType::BigInt,
+                DataTypeKeyword::SmallInt => DataType::SmallInt,
+                DataTypeKeyword::TinyInt => DataType::TinyInt,
+                DataTypeKeyword::ByteInt => DataType::ByteInt,
+                DataTypeKeyword::Float => {
+                    // `DOUBLE PRECISION`
+                    self.consume_word("precision");
+                    DataType::Float
+                }
+                DataTypeKeyword::Varchar => DataType::Varchar(self.parse_type_length()?),
+                // `CHAR VARYING` and `NCHAR VARYING` are VARCHAR synonyms
+                DataTypeKeyword::Char if self.consume_word("varying") => {
+                    DataType::Varchar(self.parse_type_length()?)
+                }
+                DataTypeKeyword::Char => DataType::Char(self.parse_type_length()?),
+                DataTypeKeyword::Binary => DataType::Binary(self.parse_type_length()?),
+                DataTypeKeyword::Boolean => DataType::Boolean,
+            },
+            Token::Word(Word {
+                value: word,
+                quote_style: None,
+                ..
+            }) => match word.to_lowercase().as_str() {
+                "decimal" | "numeric" => self.parse_number_type()?,
+                "date" => DataType::Date,
+                "time" => DataType::Time(self.parse_type_length()?),
+                "timestamp" => {
+                    let precision = self.parse_type_length()?;
+                    DataType::Timestamp {
+                        time_zone: self.parse_time_zone_suffix()?,
+                        precision,
+                    }
+                }
+                "datetime" | "timestamp_ntz" | "timestampntz" => {
+                    self.parse_timestamp_type(TimestampTimeZone::None)?
+                }
+                "timestamp_ltz" | "timestampltz" => {
+                    self.parse_timestamp_type(TimestampTimeZone::Local)?
+                }
+                "timestamp_tz" | "timestamptz" => {
+                    self.parse_timestamp_type(TimestampTimeZone::WithTimeZone)?
+                }
+                "variant" => DataType::Variant,
+                "object" => {
+                    let mut fields = Vec::new();
+                    if self.consume_token(&Token::OpenParen) {
+                        if self.peek_token() != &Token::CloseParen {
+                            fields = self.parse_comma_separated(|parser| {
+                                let name = parser.parse_identifier()?;
+                                Ok((name, parser.parse_structured_element_type()?))
+                            })?;
+                        }
+                        self.expect_token(&Token::CloseParen)?;
+                    }
+                    DataType::Object(fields)
+                }
+                "array" => {
+                    if self.consume_token(&Token::OpenParen) {
+                        let element = self.parse_structured_element_type()?;
+                        self.expect_token(&Token::CloseParen)?;
+                        DataType::Array(Some(Box::new(element)))
+                    } else {
+                        DataType::Array(None)
+                    }
+                }
+                "map" => {
+                    self.expect_token(&Token::OpenParen)?;
+                    let key = self.parse_data_type()?;
+                    self.expect_token(&Token::Comma)?;
+                    let value = self.parse_structured_element_type()?;
+                    self.expect_token(&Token::CloseParen)?;
+                    DataType::Map {
+                        key: Box::new(key),
+                        value: Box::new(value),
+                    }
+                }
+                "geography" => DataType::Geography,
+                "geometry" => DataType::Geometry,
+                "vector" => {
+                    self.expect_token(&Token::OpenParen)?;
+                    let element = self.parse_data_type()?;
+                    self.expect_token(&Token::Comma)?;
+                    let dimension = self.parse_type_parameter()?;
+                    self.expect_token(&Token::CloseParen)?;
+                    DataType::Vector {
+                        element: Box::new(element),
+                        dimension,
+                    }
+                }
+                _ => bail!("Unknown data type {}", word),
+            },
+            token => return Err(self.unexpected("a data type", &token).into()),
+        };
+
+        Ok(data_type)
+    }
+
+    /// The type of an element of a structured ARRAY, OBJECT or MAP, which may
+    /// be declared `NOT NULL`.
+    fn parse_structured_element_type(&mut self) -> Result<DataType> {
+        let data_type = self.parse_data_type()?;
+        if self.consume_keyword(Keyword::Logical(Logical::Not)) {
+            self.expect_token(&Token::Null)?;
+        }
+
+        Ok(data_type)
+    }
+
+    /// Parses the optional `(precision [, scale])` of a NUMBER.
+    fn parse_number_type(&mut self) -> Result<DataType> {
+        let (mut precision, mut scale) = (None, None);
+        if self.consume_token(&Token::OpenParen) {
+            precision = Some(self.parse_type_parameter()?);
+            if self.consume_token(&Token::Comma) {
+                scale = Some(self.parse_type_parameter()?);
+            }
+            self.expect_token(&Token::CloseParen)?;
+        }
+
+        Ok(DataType::Number { precision, scale })
+    }
+
+    fn parse_timestamp_type(&mut self, time_zone: TimestampTimeZone) -> Result<DataType> {
+        Ok(DataType::Timestamp {
+            time_zone: Some(time_zone),
+            precision: self.parse_type_length()?,
+        })
+    }
+
+    /// Parses the `WITH [LOCAL] TIME ZONE` or `WITHOUT TIME ZONE` spellings
+    /// of the timestamp variants.
+    fn parse_time_zone_suffix(&mut self) -> Result<Option<TimestampTimeZone>> {
+        let time_zone = if self.peek_keyword() == Some(Keyword::DDL(DDLKeyword::With))
+            && (self.peek_nth_word(1, "time") || self.peek_nth_word(1, "local"))
+        {
+            self.next_token();
+            if self.consume_word("local") {
+                TimestampTimeZone::Local
+            } else {
+                TimestampTimeZone::WithTimeZone
+            }
+        } else if self.consume_word("without") {
+            TimestampTimeZone::None
+        } else {
+            return Ok(None);
+        };
+
+        self.expect_word("time")?;
+        self.expect_word("zone")?;
+        Ok(Some(time_zone))
+    }
+
+    /// Parses the optional `(n)` length or precision of a type.
+    fn parse_type_length(&mut self) -> Result<Option<u64>> {
+        if !self.consume_token(&Token::OpenParen) {
+            return Ok(None);
+        }
+
+        let length = self.parse_type_parameter()?;
+        self.expect_token(&Token::CloseParen)?;
+        Ok(Some(length))
+    }
+
+    fn parse_type_parameter(&mut self) -> Result<u64> {
+        match self.next_token() {
+            Token::Int(value) => match u64::try_from(value) {
+                Ok(value) => Ok(value),
+                Err(_) => bail!("Invalid data type parameter {}", value),
+            },
+            token => Err(self.unexpected("a data type parameter", &token).into()),
+        }
+    }
+
+    /// Whether the tokens after an opening parenthesis are the parameter list
+    /// of a lambda, as in `(a, b) -> a + b`.
+    fn peek_lambda_params(&self) -> bool {
+        let mut n = 0;
+        while self.peek_nth_identifier(n) {
+            match self.peek_nth_token(n + 1) {
+                Token::Comma => n += 2,
+                Token::CloseParen => return self.peek_nth_token(n + 2) == &Token::Arrow,
+                _ => return false,
+            }
+        }
+
+        false
+    }
+
+    /// Whether the next tokens start a query, e.g. inside a parenthesis.
+    pub(crate) fn peek_query_start(&self) -> bool {
+        matches!(
+            self.peek_keyword(),
+            Some(Keyword::DML(DMLKeyword::Select) | Keyword::DDL(DDLKeyword::With))
+        )
+    }
+}
+
+/// The type of a `DATE '2024-01-01'` style literal.
+fn typed_literal_type(word: &str) -> Option<DataType> {
+    let time_zone = match word.to_lowercase().as_str() {
+        "date" => return Some(DataType::Date),
+        "time" => return Some(DataType::Time(None)),
+        "timestamp" => None,
+        "timestamp_ltz" => Some(TimestampTimeZone::Local),
+        "timestamp_ntz" => Some(TimestampTimeZone::None),
+        "timestamp_tz" => Some(TimestampTimeZone::WithTimeZone),
+        _ => return None,
+    };
+
+    Some(DataType::Timestamp {
+        time_zone,
+        precision: None,
+    })
+}
+
+fn is_keyword_function(word: &Word) -> bool {
+    matches!(
+        word.keyword,
+        Some(
+            Keyword::JoinType(JoinType::Left | JoinType::Right)
+                | Keyword::DML(DMLKeyword::Insert)
+                | Keyword::Table
+        )
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::ast::data_type::{DataType, TimestampTimeZone};
+    use crate::ast::expression::{
+        BinaryOperator, Expr, FunctionArg, Ident, NullTreatment, ObjectName, Value,
+    };
+    use crate::functions::functions::FunctionKind;
+    use crate::parser::parser::Parser;
+
+    fn parse(sql: &str) -> Result<Expr> {
+        Parser::from_sql(sql).parse_expr()
+    }
+
+    fn ident(value: &str) -> Box<Expr> {
+        Box::new(Expr::Identifier(Ident::new(value)))
+    }
+
+    #[test]
+    fn parses_operators() -> Result<()> {
+        assert_eq!(
+            parse("first_name || ' ' || last_name")?,
+            Expr::BinaryOp {
+                left: Box::new(Expr::BinaryOp {
+                    left: ident("first_name"),
+                    op: BinaryOperator::StringConcat,
+                    right: Box::new(Expr::Value(Value::Varchar(" ".to_string()))),
+                }),
+                op: BinaryOperator::StringConcat,
+                right: ident("last_name"),
+            }
+        );
+
+        assert_eq!(
+            parse("payload:customer.name::varchar")?,
+            Expr::Cast {
+                expr: Box::new(Expr::JsonAccess {
+                    expr: ident("payload"),
+                    path: vec![Ident::new("customer"), Ident::new("name")],
+                }),
+                data_type: DataType::Varchar(None),
+                try_cast: false,
+            }
+        );
+
+        assert_eq!(
+            parse("tags[0] <> ?")?,
+            Expr::BinaryOp {
+                left: Box::new(Expr::Subscript {
+                    expr: ident("tags"),
+                    index: Box::new(Expr::Value(Value::Int(0))),
+                }),
+                op: BinaryOperator::NotEqual,
+                right: Box::new(Expr::Placeholder),
+            }
+        );
+
+        assert_eq!(
+            parse("{'a': 1, 'b': [1, 2]}")?,
+            Expr::Object(vec![
+                (
+                    Expr::Value(Value::Varchar("a".to_string())),
+                    Expr::Value(Value::Int(1)),
+                ),
+                (
+                    Expr::Value(Value::Varchar("b".to_string())),
+                    Expr::Array(vec![Expr::Value(Value::Int(1)), Expr::Value(Value::Int(2))]),
+                ),
+            ])
+        );
+
+        let Expr::Function(function) = parse("filter(scores, (s, i) -> s > i)")? else {
+            panic!("Expected a function call");
+        };
+        assert_eq!(
+            function.args[1].expr(),
+            &Expr::Lambda {
+                params: vec![Ident::new("s"), Ident::new("i")],
+                body: Box::new(Expr::BinaryOp {
+                    left: ident("s"),
+                    op: BinaryOperator::GreaterThan,
+                    right: ident("i"),
+                }),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_data_types_and_typed_literals() -> Result<()> {
+        let cases = [
+            (
+                "NUMBER(38, 2)",
+                DataType::Number {
+                    precision: Some(38),
+                    scale: Some(2),
+                },
+            ),
+            ("VARCHAR(16777216)", DataType::Varchar(Some(16777216))),
+            ("CHAR VARYING(10)", DataType::Varchar(Some(10))),
+            ("TIME(9)", DataType::Time(Some(9))),
+            (
+                "DATETIME",
+                DataType::Timestamp {
+                    time_zone: Some(TimestampTimeZone::None),
+                    precision: None,
+                },
+            ),
+            (
+                "TIMESTAMP_TZ(3)",
+                DataType::Timestamp {
+                    time_zone: Some(TimestampTimeZone::WithTimeZone),
+                    precision: Some(3),
+                },
+            ),
+            (
+                "TIMESTAMP WITH LOCAL TIME ZONE",
+                DataType::Timestamp {
+                    time_zone: Some(TimestampTimeZone::Local),
+                    precision: None,
+                },
+            ),
+            ("ARRAY", DataType::Array(None)),
+            (
+                "ARRAY(INT NOT NULL)",
+                DataType::Array(Some(Box::new(DataType::Int))),
+            ),
+            (
+                "OBJECT(city VARCHAR, zip NUMBER)",
+                DataType::Object(vec![
+                    (Ident::new("city"), DataType::Varchar(None)),
+                    (
+                        Ident::new("zip"),
+                        DataType::Number {
+                            precision: None,
+                            scale: None,
+                        },
+                    ),
+                ]),
+            ),
+            (
+                "VECTOR(FLOAT, 256)",
+                DataType::Vector {
+                    element: Box::new(DataType::Float),
+                    dimension: 256,
+                },
+            ),
+            ("GEOGRAPHY", DataType::Geography),
+        ];
+
+        for (sql, data_type) in cases {
+            assert_eq!(
+                Parser::from_sql(sql).parse_data_type()?,
+                data_type,
+                "{}",
+                sql
+            );
+        }
+
+        assert_eq!(
+            parse("DATE '2024-01-01' + INTERVAL '1 day'")?,
+            Expr::BinaryOp {
+                left: Box::new(Expr::TypedString {
+                    data_type: DataType::Date,
+                    value: "2024-01-01".to_string(),
+                }),
+                op: BinaryOperator::Plus,
+                right: Box::new(Expr::Interval("1 day".to_string())),
+            }
+        );
+
+        assert_eq!(
+            parse("raw::VARIANT")?,
+            Expr::Cast {
+                expr: ident("raw"),
+                data_type: DataType::Variant,
+                try_cast: false,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_function_calls() -> Result<()> {
+        let Expr::Function(function) = parse(
+            "LISTAGG(DISTINCT name, ', ') WITHIN GROUP (ORDER BY name) OVER (PARTITION BY dept)",
+        )?
+        else {
+            panic!("Expected a function call");
+        };
+        assert!(function.distinct);
+        assert_eq!(function.args.len(), 2);
+        assert_eq!(function.within_group.len(), 1);
+        assert!(function.over.is_some());
+        assert_eq!(
+            function.builtin().map(|builtin| builtin.kind),
+            Some(FunctionKind::Aggregate)
+        );
+
+        for sql in [
+            "LAG(amount IGNORE NULLS) OVER (ORDER BY day)",
+            "LAG(amount) IGNORE NULLS OVER (ORDER BY day)",
+        ] {
+            let Expr::Function(function) = parse(sql)? else {
+                panic!("Expected a function call");
+            };
+            assert_eq!(function.null_treatment, Some(NullTreatment::IgnoreNulls));
+        }
+
+        let Expr::Function(function) = parse("analytics.util.mask(value => email, keep => 4)")?
+        else {
+            panic!("Expected a function call");
+        };
+        assert_eq!(
+            function.name,
+            ObjectName(vec![
+                Ident::new("analytics"),
+                Ident::new("util"),
+                Ident::new("mask")
+            ])
+        );
+        assert_eq!(
+            function.args,
+            vec![
+                FunctionArg::Named {
+                    name: Ident::new("value"),
+                    value: *ident("email"),
+                },
+                FunctionArg::Named {
+                    name: Ident::new("keep"),
+                    value: Expr::Value(Value::Int(4)),
+                },
+            ]
+        );
+        assert!(function.builtin().is_none());
+
+        assert_eq!(
+            parse("TRY_CAST(raw AS NUMBER)")?,
+            Expr::Cast {
+                expr: ident("raw"),
+                data_type: DataType::Number {
+                    precision: None,
+                    scale: None,
+                },
+                try_cast: true,
+            }
+        );
+
+        Ok(())
+    }
+}
@@ -1 +1,8 @@
+pub mod error;
+mod expression;
+#[allow(clippy::module_inception)]
 pub mod parser;
+mod query;
+mod scripting;
+mod stage;
+mod statement;
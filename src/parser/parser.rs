@@ -1,6 +1,14 @@
-// use anyhow::Result;
+use anyhow::{bail, Result};
 
-use crate::lexer::lexer::{DDLKeyword, DMLKeyword, Lexer, Token};
+use crate::ast::expression::{Ident, ObjectName};
+use crate::ast::statement::Statement;
+use crate::lexer::lexer::{
+    DDLKeyword, DMLKeyword, Keyword, LexError, Lexer, Span, SpannedToken, Token, Word,
+};
+use crate::parser::error::ParseError;
+
+/// Unreserved words that start a clause of a query, besides keywords.
+const CLAUSE_WORDS: [&str; 3] = ["fetch", "minus", "offset"];
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Column {
@@ -122,25 +130,587 @@ impl ParserState {
     }
 }
 
-pub struct Parser {
-    pub tokens: Vec<Token>,
+/// The tokens a parser reads, lazily lexed or not.
+type TokenStream<'a> = Box<dyn Iterator<Item = Result<SpannedToken<'a>, LexError>> + 'a>;
+
+/// Parses tokens as they're lexed. It reads ahead to the end of the current
+/// statement and no further, so that it can stop after any statement without
+/// lexing the rest of the input.
+pub struct Parser<'a> {
+    tokens: TokenStream<'a>,
+    /// The tokens of the current statement, without comments, through the
+    /// next `;` unless the input is exhausted. They're kept until the next
+    /// statement starts, so that the parser can go back and skip a clause that
+    /// failed, even past the `;`.
+    lookahead: Vec<SpannedToken<'a>>,
+    /// The index of the next token, counting from the start of the input.
+    index: usize,
+    /// The index of the first token in `lookahead`.
+    lookahead_start: usize,
+    /// Where the last token read ends, which is also where EOF is.
+    read_end: usize,
+    /// The span of the last token consumed.
+    consumed: Span,
+    /// Set once the input has run out or failed to lex, after which
+    /// `lookahead` ends with [`Token::EOF`].
+    exhausted: bool,
+    /// The error that ended lexing early, in place of which the parser sees
+    /// [`Token::Illegal`].
+    lex_error: Option<LexError>,
+    /// Whether to recover from syntax errors rather than fail, see
+    /// [`Parser::parse_statements_with_recovery`].
+    recovering: bool,
+    errors: Vec<ParseError>,
 }
 
-impl Parser {
+impl<'a> Parser<'a> {
     pub fn new(file_path: String) -> Self {
         let input = std::fs::read_to_string(file_path).expect("Failed to read file");
-        let lexer = Lexer::new(input.into());
-        let tokens: Vec<Token> = lexer.get_tokens().into_iter().flatten().collect();
+        let tokens: Vec<_> = Lexer::new(&input)
+            .map(|token| {
+                token.map(|token| SpannedToken {
+                    token: token.token.into_owned(),
+                    span: token.span,
+                })
+            })
+            .collect();
+
+        Parser::from_tokens(Box::new(tokens.into_iter()))
+    }
+
+    /// Creates a parser that lexes `sql` as it goes. Lexing errors come up
+    /// when the parser gets to them.
+    pub fn from_sql(sql: &'a str) -> Self {
+        Parser::from_tokens(Box::new(Lexer::new(sql)))
+    }
+
+    fn from_tokens(tokens: TokenStream<'a>) -> Self {
+        let mut parser = Parser {
+            tokens,
+            lookahead: Vec::new(),
+            index: 0,
+            lookahead_start: 0,
+            read_end: 0,
+            consumed: Span::default(),
+            exhausted: false,
+            lex_error: None,
+            recovering: false,
+            errors: Vec::new(),
+        };
+
+        parser.read_statement();
+        parser
+    }
+
+    /// Reads ahead up to the next `;`, or to the end of the input.
+    fn read_statement(&mut self) {
+        while !self.exhausted
+            && self.lookahead.last().map(|token| &token.token) != Some(&Token::Semicolon)
+        {
+            self.read_token();
+        }
+    }
+
+    /// Reads ahead through the statement after the one read so far. The tokens
+    /// read so far are kept, so that a clause that failed past the `;` can
+    /// still be gone back to.
+    fn read_next_statement(&mut self) {
+        let len = self.lookahead.len();
+        while !self.exhausted && self.lookahead.len() == len {
+            self.read_token();
+        }
+        self.read_statement();
+    }
+
+    /// Drops the tokens consumed by the statements before the next one, which
+    /// recovery can't go back to anymore.
+    fn start_statement(&mut self) {
+        self.lookahead.drain(..self.index - self.lookahead_start);
+        self.lookahead_start = self.index;
+    }
+
+    /// Reads the rest of the input, for when it's needed as a whole.
+    fn read_to_end(&mut self) {
+        while !self.exhausted {
+            self.read_token();
+        }
+    }
+
+    fn read_token(&mut self) {
+        match self.tokens.next() {
+            Some(Ok(token)) if is_comment(&token.token) => {}
+            Some(Ok(token)) => {
+                self.read_end = token.span.end;
+                self.lookahead.push(token);
+            }
+            Some(Err(error)) => {
+                self.lookahead.push(SpannedToken {
+                    token: Token::Illegal,
+                    span: error.span,
+                });
+                self.read_end = error.span.end;
+                self.lex_error = Some(error);
+                self.push_eof();
+            }
+            None => self.push_eof(),
+        }
+    }
+
+    fn push_eof(&mut self) {
+        self.lookahead.push(SpannedToken {
+            token: Token::EOF,
+            span: Span {
+                start: self.read_end,
+                end: self.read_end,
+            },
+        });
+        self.exhausted = true;
+    }
+
+    /// Parses every `;`-separated statement in the input.
+    pub fn parse_statements(&mut self) -> Result<Vec<Statement>> {
+        // A token that failed to lex ends up as a parse error, but the lexing
+        // error is the one worth reporting
+        self.parse_statement_list()
+            .map_err(|error| self.lex_error.take().map_or(error, Into::into))
+    }
+
+    /// Parses every statement like [`Parser::parse_statements`], but carries on
+    /// past syntax errors. A statement that fails to parse is skipped up to the
+    /// next `;` and left as a [`Statement::Error`], and within a `SELECT`, a
+    /// clause that fails is skipped up to the next clause and left as an
+    /// [`Expr::Error`](crate::ast::expression::Expr::Error), so the rest of the
+    /// input still gets parsed.
+    pub fn parse_statements_with_recovery(&mut self) -> (Vec<Statement>, Vec<ParseError>) {
+        let (statements, errors) = self.parse_statements_with_spans();
+        let statements = statements.into_iter().map(|(statement, _)| statement);
+        (statements.collect(), errors)
+    }
+
+    /// Parses like [`Parser::parse_statements_with_recovery`], along with the
+    /// span of each statement from its first token to its last.
+    pub fn parse_statements_with_spans(&mut self) -> (Vec<(Statement, Span)>, Vec<ParseError>) {
+        self.recovering = true;
+        let mut statements = Vec::new();
+        loop {
+            while self.consume_token(&Token::Semicolon) {}
+            if self.peek_token() == &Token::EOF {
+                break;
+            }
+
+            self.start_statement();
+            let start = self.peek_span().start;
+            match self.parse_statement() {
+                Ok(statement) => {
+                    let span = Span {
+                        start,
+                        end: self.consumed.end,
+                    };
+                    statements.push((statement, span));
+                    if !self.consume_token(&Token::Semicolon) && self.peek_token() != &Token::EOF {
+                        let error = self.expected("`;` after statement");
+                        self.errors.push(error);
+                        self.skip_statement();
+                    }
+                }
+                Err(error) => {
+                    let error = self.locate_error(error);
+                    self.errors.push(error);
+                    self.skip_statement();
+                    let span = Span {
+                        start,
+                        end: self.consumed.end.max(start),
+                    };
+                    statements.push((Statement::Error(span), span));
+                }
+            }
+        }
+
+        self.recovering = false;
+        (statements, std::mem::take(&mut self.errors))
+    }
+
+    /// In recovery mode, turns a failure to parse a clause into the node made
+    /// by `error_node`, skipping ahead to the start of the next clause.
+    pub(crate) fn parse_clause<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Parser<'a>) -> Result<T>,
+        error_node: impl FnOnce(Span) -> T,
+    ) -> Result<T> {
+        if !self.recovering {
+            return parse(self);
+        }
+
+        let checkpoint = self.index;
+        let start = self.peek_span().start;
+        match parse(self) {
+            Ok(node) => Ok(node),
+            Err(error) => {
+                let error = self.locate_error(error);
+                self.errors.push(error);
+
+                // Go back to where the clause started, so that a clause keyword
+                // or `;` the failed attempt consumed isn't skipped
+                self.index = checkpoint;
+                self.skip_clause();
+                let end = if self.index > checkpoint {
+                    self.consumed.end
+                } else {
+                    start
+                };
+                Ok(error_node(Span { start, end }))
+            }
+        }
+    }
+
+    fn skip_statement(&mut self) {
+        while !matches!(self.peek_token(), Token::Semicolon | Token::EOF) {
+            self.next_token();
+        }
+    }
+
+    /// Skips to the next clause keyword, `;` or unmatched `)`, so that a
+    /// clause of a subquery doesn't skip past the end of the subquery.
+    fn skip_clause(&mut self) {
+        let mut depth = 0usize;
+        loop {
+            match self.peek_token() {
+                Token::Semicolon | Token::EOF => return,
+                Token::CloseParen if depth == 0 => return,
+                Token::CloseParen => depth -= 1,
+                Token::OpenParen => depth += 1,
+                _ if depth == 0 && self.peek_clause_start() => return,
+                _ => {}
+            }
+            self.next_token();
+        }
+    }
+
+    fn peek_clause_start(&self) -> bool {
+        matches!(
+            self.peek_keyword(),
+            Some(
+                Keyword::From
+                    | Keyword::Where
+                    | Keyword::Group
+                    | Keyword::Having
+                    | Keyword::Qualify
+                    | Keyword::Order
+                    | Keyword::Limit
+                    | Keyword::Union
+                    | Keyword::Except
+                    | Keyword::Intersect
+            )
+        ) || CLAUSE_WORDS.iter().any(|word| self.peek_word(word))
+    }
+
+    /// An error for when the next token isn't the `expected` one.
+    pub(crate) fn expected(&self, expected: impl Into<String>) -> ParseError {
+        let expected = expected.into();
+        let found = self.peek_token().to_string();
+        ParseError {
+            message: format!("Expected {}, found {}", expected, found),
+            expected: Some(expected),
+            found,
+            span: self.peek_span(),
+        }
+    }
+
+    /// An error for a token that was just consumed and turned out not to be
+    /// the `expected` one.
+    pub(crate) fn unexpected(&self, expected: &str, found: &Token) -> ParseError {
+        let found = found.to_string();
+        ParseError {
+            message: format!("Expected {}, found {}", expected, found),
+            expected: Some(expected.to_string()),
+            found,
+            span: self.consumed,
+        }
+    }
+
+    /// Locates an error that didn't come with a span at the next token, which
+    /// is where the parser gave up.
+    fn locate_error(&mut self, error: anyhow::Error) -> ParseError {
+        if let Some(error) = self.lex_error.take() {
+            return error.into();
+        }
+
+        match error.downcast::<ParseError>() {
+            Ok(error) => error,
+            Err(error) => ParseError {
+                message: error.to_string(),
+                expected: None,
+                found: self.peek_token().to_string(),
+                span: self.peek_span(),
+            },
+        }
+    }
+
+    /// The span of the next token.
+    pub(crate) fn peek_span(&self) -> Span {
+        self.lookahead
+            .get(self.index - self.lookahead_start)
+            .map_or(
+                Span {
+                    start: self.read_end,
+                    end: self.read_end,
+                },
+                |token| token.span,
+            )
+    }
+
+    fn parse_statement_list(&mut self) -> Result<Vec<Statement>> {
+        let mut statements = Vec::new();
+        loop {
+            while self.consume_token(&Token::Semicolon) {}
+            if self.peek_token() == &Token::EOF {
+                break;
+            }
+
+            self.start_statement();
+            statements.push(self.parse_statement()?);
+
+            if !self.consume_token(&Token::Semicolon) && self.peek_token() != &Token::EOF {
+                bail!("Expected `;` after statement, found {}", self.peek_token());
+            }
+        }
+
+        Ok(statements)
+    }
+
+    /// Fails unless only semicolons are left, e.g. after parsing the content of
+    /// a `$$` literal on its own.
+    pub(crate) fn expect_end_of_input(&mut self) -> Result<()> {
+        while self.consume_token(&Token::Semicolon) {}
+        self.expect_token(&Token::EOF)
+    }
+
+    /// Parses `source`, the content of a literal starting at `offset` in the
+    /// input, with `parse` followed by the end of input. Errors are moved to
+    /// where they are in the input, and in recovery mode, the errors recovered
+    /// from inside the literal are kept along with the others.
+    pub(crate) fn parse_nested<T>(
+        &mut self,
+        source: &str,
+        offset: usize,
+        parse: impl FnOnce(&mut Parser<'_>) -> Result<T>,
+    ) -> Result<T> {
+        let shift = |mut error: ParseError| {
+            error.span.start += offset;
+            error.span.end += offset;
+            error
+        };
+
+        let mut parser = Parser::from_sql(source);
+        parser.recovering = self.recovering;
+        let result = parse(&mut parser).and_then(|node| {
+            parser.expect_end_of_input()?;
+            Ok(node)
+        });
+        let result = result.map_err(|error| shift(parser.locate_error(error)).into());
+        self.errors
+            .extend(std::mem::take(&mut parser.errors).into_iter().map(shift));
+        result
+    }
+
+    /// Looks `n` tokens ahead, ignoring comments.
+    pub(crate) fn peek_nth_token(&self, n: usize) -> &Token<'a> {
+        self.lookahead
+            .get(self.index - self.lookahead_start + n)
+            .map_or(&Token::EOF, |token| &token.token)
+    }
+
+    pub(crate) fn peek_token(&self) -> &Token<'a> {
+        self.peek_nth_token(0)
+    }
+
+    pub(crate) fn next_token(&mut self) -> Token<'a> {
+        let token = match self.lookahead.get(self.index - self.lookahead_start) {
+            None
+            | Some(SpannedToken {
+                token: Token::EOF, ..
+            }) => return Token::EOF,
+            Some(token) => token.clone(),
+        };
+
+        self.index += 1;
+        self.consumed = token.span;
+        if self.index - self.lookahead_start == self.lookahead.len() {
+            self.read_next_statement();
+        }
+        token.token
+    }
+
+    pub(crate) fn consume_token(&mut self, expected: &Token) -> bool {
+        if self.peek_token() == expected {
+            self.next_token();
+            true
+        } else {
+            false
+        }
+    }
 
-        Parser { tokens }
+    pub(crate) fn expect_token(&mut self, expected: &Token) -> Result<()> {
+        if self.consume_token(expected) {
+            Ok(())
+        } else {
+            Err(self.expected(expected.to_string()).into())
+        }
+    }
+
+    /// The keyword of the `n`th token ahead, if it's an unquoted keyword.
+    /// Reserved or not, it may still be an identifier depending on where it is.
+    pub(crate) fn peek_nth_keyword(&self, n: usize) -> Option<Keyword> {
+        match self.peek_nth_token(n) {
+            Token::Word(word) => word.keyword,
+            _ => None,
+        }
+    }
+
+    pub(crate) fn peek_keyword(&self) -> Option<Keyword> {
+        self.peek_nth_keyword(0)
+    }
+
+    pub(crate) fn consume_keyword(&mut self, keyword: Keyword) -> bool {
+        if self.peek_keyword() == Some(keyword) {
+            self.next_token();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn expect_keyword(&mut self, keyword: Keyword) -> Result<()> {
+        if self.consume_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.expected(keyword.to_string()).into())
+        }
+    }
+
+    /// Whether the `n`th token ahead is the unquoted word `word`, for the many
+    /// contextual words such as `DECLARE` or `LOOP` that have no [`Keyword`].
+    pub(crate) fn peek_nth_word(&self, n: usize, word: &str) -> bool {
+        matches!(
+            self.peek_nth_token(n),
+            Token::Word(Word { value, quote_style: None, .. }) if value.eq_ignore_ascii_case(word)
+        )
+    }
+
+    pub(crate) fn peek_word(&self, word: &str) -> bool {
+        self.peek_nth_word(0, word)
+    }
+
+    pub(crate) fn consume_word(&mut self, word: &str) -> bool {
+        if self.peek_word(word) {
+            self.next_token();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn expect_word(&mut self, word: &str) -> Result<()> {
+        if self.consume_word(word) {
+            Ok(())
+        } else {
+            Err(self.expected(format!("`{}`", word.to_uppercase())).into())
+        }
+    }
+
+    /// Whether the `n`th token ahead can be a column or object name.
+    pub(crate) fn peek_nth_identifier(&self, n: usize) -> bool {
+        matches!(self.peek_nth_token(n), Token::Word(word) if word.is_column_name())
+    }
+
+    pub(crate) fn peek_identifier(&self) -> bool {
+        self.peek_nth_identifier(0)
+    }
+
+    pub(crate) fn parse_identifier(&mut self) -> Result<Ident> {
+        match self.next_token() {
+            Token::Word(word) if word.is_column_name() => Ok(Ident {
+                value: word.value.into_owned(),
+                quote_style: word.quote_style,
+            }),
+            token => Err(self.unexpected("an identifier", &token).into()),
+        }
+    }
+
+    /// Parses the name of a table or another object. Unlike column names,
+    /// its parts can't be join keywords such as `LEFT`.
+    pub(crate) fn parse_object_name(&mut self) -> Result<ObjectName> {
+        let mut idents = vec![self.parse_object_identifier()?];
+        while self.consume_token(&Token::Period) {
+            idents.push(self.parse_object_identifier()?);
+        }
+
+        Ok(ObjectName(idents))
+    }
+
+    pub(crate) fn parse_object_identifier(&mut self) -> Result<Ident> {
+        match self.peek_token() {
+            Token::Word(word) if !word.is_reserved() => self.parse_identifier(),
+            _ => {
+                let token = self.next_token();
+                Err(self.unexpected("an identifier", &token).into())
+            }
+        }
+    }
+
+    pub(crate) fn parse_comma_separated<T, F>(&mut self, mut f: F) -> Result<Vec<T>>
+    where
+        F: FnMut(&mut Parser<'a>) -> Result<T>,
+    {
+        let mut values = vec![f(self)?];
+        while self.consume_token(&Token::Comma) {
+            values.push(f(self)?);
+        }
+
+        Ok(values)
     }
 
-    pub fn get_selected_columns(&self) -> Vec<SelectedColumns> {
+    /// Parses `(a, b, c)` if the next token opens a parenthesis.
+    pub(crate) fn parse_parenthesized_identifiers(&mut self) -> Result<Vec<Ident>> {
+        if !self.consume_token(&Token::OpenParen) {
+            return Ok(vec![]);
+        }
+
+        let idents = self.parse_comma_separated(Parser::parse_identifier)?;
+        self.expect_token(&Token::CloseParen)?;
+        Ok(idents)
+    }
+
+    /// Skips a single token, or a whole parenthesized group if the next token
+    /// opens one. Used to step over clauses we don't model.
+    pub(crate) fn skip_token_or_group(&mut self) -> Result<()> {
+        if self.next_token() != Token::OpenParen {
+            return Ok(());
+        }
+
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next_token() {
+                Token::OpenParen => depth += 1,
+                Token::CloseParen => depth -= 1,
+                Token::EOF => bail!("Unbalanced parentheses"),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get_selected_columns(&mut self) -> Vec<SelectedColumns> {
+        self.read_to_end();
         let mut state = ParserState::new();
         let mut selected_columns = Vec::new();
         let mut current_table: Option<String> = None;
         let mut current_cte: Option<String> = None;
-        let mut tokens_iter = self.tokens.iter().peekable();
+        let mut tokens_iter = self.lookahead[self.index - self.lookahead_start..]
+            .iter()
+            .map(|token| &token.token)
+            .peekable();
 
         while let Some(token) = tokens_iter.next() {
             let next_token = tokens_iter.peek().unwrap_or(&&Token::EOF);
@@ -149,49 +719,54 @@ impl Parser {
                 Token::EOF => {
                     break;
                 }
-                Token::Ident(col)
-                    if state.in_select && !state.in_function && next_token != &&Token::Period =>
-                {
-                    state.add_column(col.clone());
-                }
-                Token::Comma
-                    if state.in_cte && state.function_paren_count == 0 && !state.in_select =>
-                {
-                    current_cte = if let Token::Ident(s) = next_token {
-                        Some(s.clone())
-                    } else {
-                        None
-                    };
-                }
-                Token::Table | Token::View => {
-                    if let Token::Ident(table_name) = next_token {
-                        current_table = Some(table_name.clone());
+                Token::Word(word) => match word.keyword {
+                    Some(Keyword::Table | Keyword::View) => {
+                        if let Some(table_name) = identifier(next_token) {
+                            current_table = Some(table_name);
+                        }
                     }
-                }
-                Token::DDL(DDLKeyword::With) => {
-                    if let Token::Ident(table_name) = next_token {
-                        current_cte = Some(table_name.clone());
-                        state.enter_cte()
+                    Some(Keyword::DDL(DDLKeyword::With)) => {
+                        if let Some(table_name) = identifier(next_token) {
+                            current_cte = Some(table_name);
+                            state.enter_cte()
+                        }
                     }
-                }
-                Token::DDL(DDLKeyword::Create) => {
-                    state.enter_object_creation();
-                }
-                Token::DML(DMLKeyword::Select) => {
-                    state.enter_select();
-                }
-                Token::From => {
-                    if current_cte.is_some() {
-                        state.update_selected_columns(&mut selected_columns, &current_cte.clone());
-                        current_cte = None;
-                    } else {
-                        state.in_cte = false;
-                        state.update_selected_columns(&mut selected_columns, &current_table);
+                    Some(Keyword::DDL(DDLKeyword::Create)) => {
+                        state.enter_object_creation();
                     }
-                    state.exit_select();
-                }
-                Token::ColumnFunction(_) | Token::Over => {
-                    state.enter_function();
+                    Some(Keyword::DML(DMLKeyword::Select)) => {
+                        state.enter_select();
+                    }
+                    Some(Keyword::From) => {
+                        if current_cte.is_some() {
+                            state.update_selected_columns(
+                                &mut selected_columns,
+                                &current_cte.clone(),
+                            );
+                            current_cte = None;
+                        } else {
+                            state.in_cte = false;
+                            state.update_selected_columns(&mut selected_columns, &current_table);
+                        }
+                        state.exit_select();
+                    }
+                    Some(Keyword::Cast | Keyword::Over) => {
+                        state.enter_function();
+                    }
+                    _ if !word.is_column_name() => {}
+                    // Any identifier followed by a parenthesis is a function call
+                    _ if next_token == &&Token::OpenParen => {
+                        state.enter_function();
+                    }
+                    _ if state.in_select && !state.in_function && next_token != &&Token::Period => {
+                        state.add_column(word.value.to_string());
+                    }
+                    _ => {}
+                },
+                Token::Comma
+                    if state.in_cte && state.function_paren_count == 0 && !state.in_select =>
+                {
+                    current_cte = identifier(next_token);
                 }
                 Token::OpenParen => {
                     state.open_paren();
@@ -207,15 +782,31 @@ impl Parser {
     }
 }
 
+/// The name of a word token that can be an identifier.
+fn identifier(token: &Token) -> Option<String> {
+    match token {
+        Token::Word(word) if word.is_column_name() => Some(word.value.to_string()),
+        _ => None,
+    }
+}
+
+fn is_comment(token: &Token) -> bool {
+    matches!(token, Token::InlineComment(_) | Token::BlockComment(_))
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
 
     use super::{Column, Parser, SelectedColumns};
+    use crate::ast::expression::Expr;
+    use crate::ast::query::SetExpr;
+    use crate::ast::statement::Statement;
+    use crate::lexer::lexer::Span;
 
     #[test]
     fn assert_finds_all_columns() -> Result<()> {
-        let parser = Parser::new("scripts/input.sql".into());
+        let mut parser = Parser::new("scripts/input.sql".into());
 
         let columns = {
             vec![
@@ -256,4 +847,63 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn assert_parses_statements_lazily() -> Result<()> {
+        let sql = "SELECT a FROM t; SELECT 'unterminated";
+
+        let mut parser = Parser::from_sql(sql);
+        assert!(matches!(parser.parse_statement()?, Statement::Query(_)));
+
+        let error = Parser::from_sql(sql).parse_statements().unwrap_err();
+        assert!(error.to_string().contains("nterminated"), "{error}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn assert_recovers_from_syntax_errors() -> Result<()> {
+        let sql = "SELECT a FROM t WHERE GROUP BY a;\nSELEC x;\nSELECT b FROM u GROUP b ORDER BY b;\nSELECT 'oops";
+
+        let (statements, errors) = Parser::from_sql(sql).parse_statements_with_recovery();
+
+        assert_eq!(statements.len(), 4);
+        let Statement::Query(query) = &statements[0] else {
+            panic!("Expected a query, found {:?}", statements[0]);
+        };
+        let SetExpr::Select(select) = &query.body else {
+            panic!("Expected a select, found {:?}", query.body);
+        };
+        // The missing condition is an empty error node, and GROUP BY survives
+        assert_eq!(
+            select.selection,
+            Some(Expr::Error(Span { start: 22, end: 22 }))
+        );
+        assert_eq!(select.group_by.len(), 1);
+        assert_eq!(statements[1], Statement::Error(Span { start: 34, end: 41 }));
+        assert!(matches!(&statements[2], Statement::Query(query) if query.order_by.len() == 1));
+
+        assert_eq!(errors.len(), 4);
+        assert_eq!(&sql[errors[0].span.start..errors[0].span.end], "GROUP");
+        assert_eq!(errors[2].message, "Expected `BY`, found `b`");
+        assert_eq!(errors[2].expected.as_deref(), Some("`BY`"));
+        assert_eq!(&sql[errors[2].span.start..errors[2].span.end], "b");
+        assert_eq!(errors[3].message, "Unterminated string literal");
+        assert_eq!(&sql[errors[3].span.start..errors[3].span.end], "'oops");
+
+        // Without recovery, the first error is the only one
+        assert!(Parser::from_sql(sql).parse_statements().is_err());
+
+        // A clause that fails at the `;` leaves the next statement alone
+        let sql = "SELECT a FROM t WHERE ;\nSELECT b FROM u;";
+        let (statements, errors) = Parser::from_sql(sql).parse_statements_with_recovery();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(&sql[errors[0].span.start..errors[0].span.end], ";");
+        assert!(matches!(
+            &statements[..],
+            [Statement::Query(_), Statement::Query(_)]
+        ));
+
+        Ok(())
+    }
 }
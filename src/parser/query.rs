@@ -0,0 +1,650 @@
+use anyhow::{bail, Result};
+
+use crate::ast::expression::{Expr, Ident, ObjectName};
+use crate::ast::query::{
+    Cte, Join, JoinConstraint, JoinOperator, OrderByExpr, Query, Select, SelectItem, SetExpr,
+    SetOperator, TableAlias, TableFactor, TableWithJoins, WildcardOptions, With,
+};
+use crate::ast::stage::KeyValueOption;
+use crate::lexer::lexer::{DDLKeyword, DMLKeyword, JoinType, Keyword, Token};
+use crate::parser::parser::Parser;
+
+/// Unreserved words that may follow a table or select item and so can't be an
+/// implicit alias.
+const RESERVED_FOR_ALIAS: [&str; 4] = ["except", "fetch", "limit", "offset"];
+
+impl<'a> Parser<'a> {
+    pub fn parse_query(&mut self) -> Result<Query> {
+        let with = if self.consume_keyword(Keyword::DDL(DDLKeyword::With)) {
+            Some(With {
+                recursive: self.consume_word("recursive"),
+                ctes: self.parse_comma_separated(Parser::parse_cte)?,
+            })
+        } else {
+            None
+        };
+
+        let body = self.parse_set_expr(0)?;
+
+        let order_by = if self.consume_keyword(Keyword::Order) {
+            self.expect_keyword(Keyword::By)?;
+            self.parse_comma_separated(Parser::parse_order_by_expr)?
+        } else {
+            vec![]
+        };
+
+        let limit = if self.consume_keyword(Keyword::Limit) {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        let offset = if self.consume_word("offset") {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        Ok(Query {
+            with,
+            body,
+            order_by,
+            limit,
+            offset,
+        })
+    }
+
+    fn parse_cte(&mut self) -> Result<Cte> {
+        let alias = self.parse_identifier()?;
+        let columns = self.parse_parenthesized_identifiers()?;
+        self.expect_keyword(Keyword::As)?;
+        self.expect_token(&Token::OpenParen)?;
+        let query = self.parse_query()?;
+        self.expect_token(&Token::CloseParen)?;
+
+        Ok(Cte {
+            alias,
+            columns,
+            query,
+        })
+    }
+
+    /// Parses set operations, with `INTERSECT` binding tighter than
+    /// `UNION`/`EXCEPT`.
+    fn parse_set_expr(&mut self, precedence: u8) -> Result<SetExpr> {
+        let mut expr = if self.consume_token(&Token::OpenParen) {
+            let query = self.parse_query()?;
+            self.expect_token(&Token::CloseParen)?;
+            SetExpr::Query(Box::new(query))
+        } else if self.consume_keyword(Keyword::Values) {
+            SetExpr::Values(self.parse_comma_separated(|parser| {
+                parser.expect_token(&Token::OpenParen)?;
+                let row = parser.parse_comma_separated(Parser::parse_expr)?;
+                parser.expect_token(&Token::CloseParen)?;
+                Ok(row)
+            })?)
+        } else {
+            SetExpr::Select(Box::new(self.parse_select()?))
+        };
+
+        loop {
+            let (op, next_precedence) = match self.peek_keyword() {
+                Some(Keyword::Union) => (SetOperator::Union, 10),
+                Some(Keyword::Except) => (SetOperator::Except, 10),
+                _ if self.peek_word("minus") => (SetOperator::Except, 10),
+                Some(Keyword::Intersect) => (SetOperator::Intersect, 20),
+                _ => break,
+            };
+            if precedence >= next_precedence {
+                break;
+            }
+
+            self.next_token();
+            let all = self.consume_keyword(Keyword::All);
+            if !all {
+                self.consume_keyword(Keyword::Distinct);
+            }
+
+            expr = SetExpr::SetOperation {
+                op,
+                all,
+                left: Box::new(expr),
+                right: Box::new(self.parse_set_expr(next_precedence)?),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_select(&mut self) -> Result<Select> {
+        self.expect_keyword(Keyword::DML(DMLKeyword::Select))?;
+
+        let distinct = self.consume_keyword(Keyword::Distinct);
+        if !distinct {
+            self.consume_keyword(Keyword::All);
+        }
+
+        // `TOP` isn't reserved, so `SELECT top FROM t` selects a column
+        let top = if self.peek_keyword() == Some(Keyword::Top)
+            && matches!(self.peek_nth_token(1), Token::Int(_))
+        {
+            self.next_token();
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        let projection = self.parse_clause(
+            |parser| parser.parse_comma_separated(Parser::parse_select_item),
+            |span| vec![SelectItem::UnnamedExpr(Expr::Error(span))],
+        )?;
+
+        let into = if self.consume_keyword(Keyword::Into) {
+            self.parse_comma_separated(Parser::parse_expr)?
+        } else {
+            vec![]
+        };
+
+        let from = if self.consume_keyword(Keyword::From) {
+            self.parse_clause(
+                |parser| parser.parse_comma_separated(Parser::parse_table_and_joins),
+                |_| vec![],
+            )?
+        } else {
+            vec![]
+        };
+
+        let selection = if self.consume_keyword(Keyword::Where) {
+            Some(self.parse_clause(Parser::parse_expr, Expr::Error)?)
+        } else {
+            None
+        };
+
+        let group_by = if self.consume_keyword(Keyword::Group) {
+            self.parse_clause(
+                |parser| {
+                    parser.expect_keyword(Keyword::By)?;
+                    parser.parse_comma_separated(Parser::parse_expr)
+                },
+                |span| vec![Expr::Error(span)],
+            )?
+        } else {
+            vec![]
+        };
+
+        let having = if self.consume_keyword(Keyword::Having) {
+            Some(self.parse_clause(Parser::parse_expr, Expr::Error)?)
+        } else {
+            None
+        };
+
+        let qualify = if self.consume_keyword(Keyword::Qualify) {
+            Some(self.parse_clause(Parser::parse_expr, Expr::Error)?)
+        } else {
+            None
+        };
+
+        Ok(Select {
+            distinct,
+            top,
+            projection,
+            into,
+            from,
+            selection,
+            group_by,
+            having,
+            qualify,
+        })
+    }
+
+    fn parse_select_item(&mut self) -> Result<SelectItem> {
+        if self.consume_token(&Token::Asterisk) {
+            return Ok(SelectItem::Wildcard(self.parse_wildcard_options()?));
+        }
+
+        if let Some(name) = self.parse_qualified_wildcard()? {
+            let options = self.parse_wildcard_options()?;
+            return Ok(SelectItem::QualifiedWildcard(name, options));
+        }
+
+        let expr = self.parse_expr()?;
+        match self.parse_optional_alias()? {
+            Some(alias) => Ok(SelectItem::ExprWithAlias { expr, alias }),
+            None => Ok(SelectItem::UnnamedExpr(expr)),
+        }
+    }
+
+    /// Parses `t.*` or `schema.t.*`, leaving the tokens alone otherwise.
+    fn parse_qualified_wildcard(&mut self) -> Result<Option<ObjectName>> {
+        let mut n = 0;
+        while self.peek_nth_identifier(n) && self.peek_nth_token(n + 1) == &Token::Period {
+            n += 2;
+        }
+
+        if n == 0 || self.peek_nth_token(n) != &Token::Asterisk {
+            return Ok(None);
+        }
+
+        let mut idents = Vec::new();
+        for _ in 0..n / 2 {
+            idents.push(self.parse_identifier()?);
+            self.expect_token(&Token::Period)?;
+        }
+        self.expect_token(&Token::Asterisk)?;
+
+        Ok(Some(ObjectName(idents)))
+    }
+
+    /// Parses `EXCLUDE`, `REPLACE` and `RENAME` after a wildcard, in the
+    /// order Snowflake requires. `EXCLUDE` and `RENAME` take a single item
+    /// without parentheses too.
+    fn parse_wildcard_options(&mut self) -> Result<WildcardOptions> {
+        let mut options = WildcardOptions::default();
+        if self.consume_word("exclude") {
+            options.exclude = match self.peek_token() {
+                Token::OpenParen => self.parse_parenthesized_identifiers()?,
+                _ => vec![self.parse_identifier()?],
+            };
+        }
+        if self.consume_word("replace") {
+            self.expect_token(&Token::OpenParen)?;
+            options.replace = self.parse_comma_separated(|parser| {
+                let expr = parser.parse_expr()?;
+                parser.expect_keyword(Keyword::As)?;
+                Ok((expr, parser.parse_identifier()?))
+            })?;
+            self.expect_token(&Token::CloseParen)?;
+        }
+        if self.consume_word("rename") {
+            let parse_rename = |parser: &mut Parser<'a>| {
+                let column = parser.parse_identifier()?;
+                parser.expect_keyword(Keyword::As)?;
+                Ok((column, parser.parse_identifier()?))
+            };
+            options.rename = match self.consume_token(&Token::OpenParen) {
+                true => {
+                    let rename = self.parse_comma_separated(parse_rename)?;
+                    self.expect_token(&Token::CloseParen)?;
+                    rename
+                }
+                false => vec![parse_rename(self)?],
+            };
+        }
+
+        Ok(options)
+    }
+
+    fn parse_optional_alias(&mut self) -> Result<Option<Ident>> {
+        if self.consume_keyword(Keyword::As) {
+            return Ok(Some(self.parse_identifier()?));
+        }
+
+        match self.peek_token() {
+            Token::Word(word)
+                if !word.is_reserved()
+                    && (word.quote_style.is_some()
+                        || !RESERVED_FOR_ALIAS
+                            .iter()
+                            .any(|reserved| word.value.eq_ignore_ascii_case(reserved))) =>
+            {
+                Ok(Some(self.parse_identifier()?))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn parse_optional_table_alias(&mut self) -> Result<Option<TableAlias>> {
+        // Join keywords can name a column but not a table
+        let alias = match self.consume_keyword(Keyword::As) {
+            true => Some(self.parse_object_identifier()?),
+            false => self.parse_optional_alias()?,
+        };
+        match alias {
+            Some(name) => Ok(Some(TableAlias {
+                name,
+                columns: self.parse_parenthesized_identifiers()?,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn parse_table_and_joins(&mut self) -> Result<TableWithJoins> {
+        let relation = self.parse_table_factor()?;
+        let mut joins = Vec::new();
+
+        loop {
+            let natural = self.consume_keyword(Keyword::JoinType(JoinType::Natural));
+            let join_operator = match self.peek_keyword() {
+                Some(Keyword::Join | Keyword::JoinType(JoinType::Inner)) => {
+                    self.consume_keyword(Keyword::JoinType(JoinType::Inner));
+                    self.expect_keyword(Keyword::Join)?;
+                    JoinOperator::Inner
+                }
+                Some(Keyword::JoinType(JoinType::Left)) => {
+                    self.parse_outer_join(Keyword::JoinType(JoinType::Left))?;
+                    JoinOperator::LeftOuter
+                }
+                Some(Keyword::JoinType(JoinType::Right)) => {
+                    self.parse_outer_join(Keyword::JoinType(JoinType::Right))?;
+                    JoinOperator::RightOuter
+                }
+                Some(Keyword::Full) => {
+                    self.parse_outer_join(Keyword::Full)?;
+                    JoinOperator::FullOuter
+                }
+                Some(Keyword::Cross) => {
+                    self.next_token();
+                    self.expect_keyword(Keyword::Join)?;
+                    joins.push(Join {
+                        relation: self.parse_table_factor()?,
+                        join_operator: JoinOperator::CrossJoin,
+                    });
+                    continue;
+                }
+                _ if natural => {
+                    bail!("Expected a join after NATURAL, found {}", self.peek_token())
+                }
+                _ => break,
+            };
+
+            let relation = self.parse_table_factor()?;
+            let constraint = if natural {
+                JoinConstraint::Natural
+            } else if self.consume_keyword(Keyword::On) {
+                JoinConstraint::On(self.parse_expr()?)
+            } else if self.consume_keyword(Keyword::Using) {
+                JoinConstraint::Using(self.parse_parenthesized_identifiers()?)
+            } else {
+                JoinConstraint::None
+            };
+
+            joins.push(Join {
+                relation,
+                join_operator: join_operator(constraint),
+            });
+        }
+
+        Ok(TableWithJoins { relation, joins })
+    }
+
+    fn parse_outer_join(&mut self, side: Keyword) -> Result<()> {
+        self.expect_keyword(side)?;
+        self.consume_keyword(Keyword::JoinType(JoinType::Outer));
+        self.expect_keyword(Keyword::Join)
+    }
+
+    pub(crate) fn parse_table_factor(&mut self) -> Result<TableFactor> {
+        let lateral = self.consume_keyword(Keyword::Lateral);
+
+        if self.consume_token(&Token::OpenParen) {
+            if self.peek_query_start() {
+                let subquery = self.parse_query()?;
+                self.expect_token(&Token::CloseParen)?;
+                return Ok(TableFactor::Derived {
+                    lateral,
+                    subquery: Box::new(subquery),
+                    alias: self.parse_optional_table_alias()?,
+                });
+            }
+
+            let table_with_joins = self.parse_table_and_joins()?;
+            self.expect_token(&Token::CloseParen)?;
+            return Ok(TableFactor::NestedJoin {
+                table_with_joins: Box::new(table_with_joins),
+                alias: self.parse_optional_table_alias()?,
+            });
+        }
+
+        if self.peek_stage_ref() {
+            let stage = self.parse_stage_ref()?;
+            let options = if self.consume_token(&Token::OpenParen) {
+                let options = self.parse_comma_separated(|parser| {
+                    let name = parser.parse_identifier()?;
+                    parser.expect_token(&Token::FatArrow)?;
+                    Ok(KeyValueOption {
+                        name,
+                        value: parser.parse_option_value()?,
+                    })
+                })?;
+                self.expect_token(&Token::CloseParen)?;
+                options
+            } else {
+                vec![]
+            };
+            return Ok(TableFactor::Stage {
+                stage,
+                options,
+                alias: self.parse_optional_table_alias()?,
+            });
+        }
+
+        if self.peek_keyword() == Some(Keyword::Table)
+            && self.peek_nth_token(1) == &Token::OpenParen
+        {
+            self.next_token();
+            self.expect_token(&Token::OpenParen)?;
+            let expr = self.parse_expr()?;
+            self.expect_token(&Token::CloseParen)?;
+            return Ok(TableFactor::TableFunction {
+                lateral,
+                expr,
+                alias: self.parse_optional_table_alias()?,
+            });
+        }
+
+        let name = self.parse_object_name()?;
+        if self.peek_token() == &Token::OpenParen {
+            let expr = self.parse_function_call(name)?;
+            return Ok(TableFactor::TableFunction {
+                lateral,
+                expr,
+                alias: self.parse_optional_table_alias()?,
+            });
+        }
+
+        Ok(TableFactor::Table {
+            name,
+            alias: self.parse_optional_table_alias()?,
+        })
+    }
+
+    pub(crate) fn parse_order_by_expr(&mut self) -> Result<OrderByExpr> {
+        let expr = self.parse_expr()?;
+
+        let asc = if self.consume_word("asc") {
+            Some(true)
+        } else if self.consume_word("desc") {
+            Some(false)
+        } else {
+            None
+        };
+
+        let nulls_first = if self.consume_word("nulls") {
+            if self.consume_word("first") {
+                Some(true)
+            } else {
+                self.expect_word("last")?;
+                Some(false)
+            }
+        } else {
+            None
+        };
+
+        Ok(OrderByExpr {
+            expr,
+            asc,
+            nulls_first,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::ast::expression::{BinaryOperator, Expr, Ident, ObjectName, Value};
+    use crate::ast::query::{
+        JoinConstraint, JoinOperator, SelectItem, SetExpr, TableFactor, WildcardOptions,
+    };
+    use crate::ast::statement::Statement;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn parses_select_with_join_and_filter() -> Result<()> {
+        let sql = "SELECT e.id, e.name AS employee_name, s.* \
+                   FROM employees e LEFT JOIN salaries s ON e.id = s.id \
+                   WHERE s.amount > 100 ORDER BY e.name DESC LIMIT 10";
+        let statements = Parser::from_sql(sql).parse_statements()?;
+
+        let Statement::Query(query) = &statements[0] else {
+            panic!("Expected a query, found {:?}", statements[0]);
+        };
+        let SetExpr::Select(select) = &query.body else {
+            panic!("Expected a select, found {:?}", query.body);
+        };
+
+        assert_eq!(
+            select.projection,
+            vec![
+                SelectItem::UnnamedExpr(Expr::CompoundIdentifier(vec![
+                    Ident::new("e"),
+                    Ident::new("id"),
+                ])),
+                SelectItem::ExprWithAlias {
+                    expr: Expr::CompoundIdentifier(vec![Ident::new("e"), Ident::new("name")]),
+                    alias: Ident::new("employee_name"),
+                },
+                SelectItem::QualifiedWildcard(
+                    ObjectName(vec![Ident::new("s")]),
+                    WildcardOptions::default()
+                ),
+            ]
+        );
+
+        let join = &select.from[0].joins[0];
+        assert!(matches!(
+            &join.relation,
+            TableFactor::Table { name, .. } if name.base_name() == &Ident::new("salaries")
+        ));
+        assert!(matches!(
+            join.join_operator,
+            JoinOperator::LeftOuter(JoinConstraint::On(_))
+        ));
+
+        assert_eq!(
+            select.selection,
+            Some(Expr::BinaryOp {
+                left: Box::new(Expr::CompoundIdentifier(vec![
+                    Ident::new("s"),
+                    Ident::new("amount"),
+                ])),
+                op: BinaryOperator::GreaterThan,
+                right: Box::new(Expr::Value(Value::Int(100))),
+            })
+        );
+        assert_eq!(query.order_by[0].asc, Some(false));
+        assert_eq!(query.limit, Some(Expr::Value(Value::Int(10))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_keywords_as_column_names() -> Result<()> {
+        let sql = "SELECT date, text, number, left, top, language, caller, rank, value, \
+                   \"Select\" AS \"order\" \
+                   FROM events end_of_day LEFT JOIN \"from\" ON date = \"from\".date";
+        let statements = Parser::from_sql(sql).parse_statements()?;
+
+        let Statement::Query(query) = &statements[0] else {
+            panic!("Expected a query, found {:?}", statements[0]);
+        };
+        let SetExpr::Select(select) = &query.body else {
+            panic!("Expected a select, found {:?}", query.body);
+        };
+
+        let columns = [
+            "date", "text", "number", "left", "top", "language", "caller", "rank", "value",
+        ];
+        assert_eq!(select.top, None);
+        assert_eq!(
+            select.projection[..columns.len()],
+            columns.map(|column| SelectItem::UnnamedExpr(Expr::Identifier(Ident::new(column))))
+        );
+        assert_eq!(
+            select.projection[columns.len()],
+            SelectItem::ExprWithAlias {
+                expr: Expr::Identifier(Ident::with_quote('"', "Select")),
+                alias: Ident::with_quote('"', "order"),
+            }
+        );
+
+        let TableFactor::Table { alias, .. } = &select.from[0].relation else {
+            panic!("Expected a table, found {:?}", select.from[0].relation);
+        };
+        assert_eq!(
+            alias.as_ref().map(|alias| &alias.name),
+            Some(&Ident::new("end_of_day"))
+        );
+        assert!(matches!(
+            select.from[0].joins[0].join_operator,
+            JoinOperator::LeftOuter(JoinConstraint::On(_))
+        ));
+
+        // Reserved words still need quoting, and join keywords only name columns
+        for sql in [
+            "SELECT select FROM t",
+            "SELECT a FROM join",
+            "SELECT a FROM natural",
+            "SELECT a FROM t JOIN left ON 1 = 1",
+            "SELECT a FROM t AS left",
+        ] {
+            assert!(Parser::from_sql(sql).parse_statements().is_err(), "{sql}");
+        }
+        assert!(Parser::from_sql("UPDATE t SET t.left = 1")
+            .parse_statements()
+            .is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_wildcard_options() -> Result<()> {
+        let sql = "SELECT * EXCLUDE ssn, t.* EXCLUDE (a, b) REPLACE (a + 1 AS a) \
+                   RENAME (b AS c, d AS e) FROM t";
+        let statement = Parser::from_sql(sql).parse_statement()?;
+        let Statement::Query(query) = statement else {
+            panic!("Expected a query, found {:?}", statement);
+        };
+        let SetExpr::Select(select) = &query.body else {
+            panic!("Expected a select, found {:?}", query.body);
+        };
+
+        let ident = |name: &str| Ident::new(name);
+        assert_eq!(
+            select.projection,
+            vec![
+                SelectItem::Wildcard(WildcardOptions {
+                    exclude: vec![ident("ssn")],
+                    ..Default::default()
+                }),
+                SelectItem::QualifiedWildcard(
+                    ObjectName(vec![ident("t")]),
+                    WildcardOptions {
+                        exclude: vec![ident("a"), ident("b")],
+                        replace: vec![(
+                            Expr::BinaryOp {
+                                left: Box::new(Expr::Identifier(ident("a"))),
+                                op: BinaryOperator::Plus,
+                                right: Box::new(Expr::Value(Value::Int(1))),
+                            },
+                            ident("a")
+                        )],
+                        rename: vec![(ident("b"), ident("c")), (ident("d"), ident("e"))],
+                    }
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+}
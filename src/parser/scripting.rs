@@ -0,0 +1,660 @@
+use anyhow::{bail, Result};
+
+use crate::ast::expression::{Expr, Ident};
+use crate::ast::scripting::{
+    Block, ConditionalBlock, CursorSource, Declaration, ExceptionHandler, ScriptStatement,
+};
+use crate::lexer::lexer::{Keyword, Logical, Token};
+use crate::parser::parser::Parser;
+
+impl<'a> Parser<'a> {
+    /// Parses `[DECLARE ...] BEGIN ... [EXCEPTION ...] END`.
+    pub fn parse_block(&mut self) -> Result<Block> {
+        let declarations = if self.consume_word("declare") {
+            self.parse_declarations()?
+        } else {
+            vec![]
+        };
+
+        self.expect_word("begin")?;
+        let statements = self.parse_script_statements()?;
+
+        let mut exception_handlers = Vec::new();
+        if self.consume_word("exception") {
+            while self.consume_keyword(Keyword::When) {
+                exception_handlers.push(self.parse_exception_handler()?);
+            }
+        }
+
+        self.expect_keyword(Keyword::End)?;
+
+        Ok(Block {
+            declarations,
+            statements,
+            exception_handlers,
+        })
+    }
+
+    /// Parses the body of a procedure or `EXECUTE IMMEDIATE`, which is either a
+    /// full block or a bare list of scripting statements.
+    pub(crate) fn parse_script_body(&mut self) -> Result<Block> {
+        if self.peek_word("declare") || self.peek_word("begin") {
+            let block = self.parse_block()?;
+            self.consume_token(&Token::Semicolon);
+            return Ok(block);
+        }
+
+        Ok(Block {
+            declarations: vec![],
+            statements: self.parse_script_statements()?,
+            exception_handlers: vec![],
+        })
+    }
+
+    /// Whether the next token starts scripting code rather than plain SQL.
+    pub(crate) fn peek_script_start(&self) -> bool {
+        self.peek_word("declare")
+            || self.peek_word("begin")
+            || self.peek_keyword() == Some(Keyword::Return)
+    }
+
+    fn parse_declarations(&mut self) -> Result<Vec<Declaration>> {
+        let mut declarations = Vec::new();
+        while !self.peek_word("begin") && self.peek_token() != &Token::EOF {
+            let name = self.parse_identifier()?;
+            declarations.push(self.parse_declaration(name)?);
+            self.expect_token(&Token::Semicolon)?;
+        }
+
+        Ok(declarations)
+    }
+
+    /// Parses everything after the name in a `DECLARE` section entry or a
+    /// `LET` statement.
+    fn parse_declaration(&mut self, name: Ident) -> Result<Declaration> {
+        if self.consume_word("cursor") {
+            self.expect_keyword(Keyword::For)?;
+            let source = if self.peek_query_start() || self.peek_token() == &Token::OpenParen {
+                CursorSource::Query(Box::new(self.parse_query()?))
+            } else {
+                CursorSource::ResultSet(self.parse_identifier()?)
+            };
+            return Ok(Declaration::Cursor { name, source });
+        }
+
+        if self.consume_word("resultset") {
+            return Ok(Declaration::ResultSet {
+                name,
+                default: self.parse_optional_default()?,
+            });
+        }
+
+        if self.consume_word("exception") {
+            let (code, message) = if self.consume_token(&Token::OpenParen) {
+                let code = self.parse_expr()?;
+                self.expect_token(&Token::Comma)?;
+                let message = self.parse_expr()?;
+                self.expect_token(&Token::CloseParen)?;
+                (Some(code), Some(message))
+            } else {
+                (None, None)
+            };
+            return Ok(Declaration::Exception {
+                name,
+                code,
+                message,
+            });
+        }
+
+        let data_type = if self.peek_data_type() {
+            Some(self.parse_data_type()?)
+        } else {
+            None
+        };
+
+        Ok(Declaration::Variable {
+            name,
+            data_type,
+            default: self.parse_optional_default()?,
+        })
+    }
+
+    /// Parses `DEFAULT expr` or `:= expr`.
+    fn parse_optional_default(&mut self) -> Result<Option<Expr>> {
+        if self.consume_word("default") || self.consume_token(&Token::Assign) {
+            Ok(Some(self.parse_expr()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn parse_exception_handler(&mut self) -> Result<ExceptionHandler> {
+        let exceptions = if self.consume_word("other") {
+            vec![]
+        } else {
+            let mut exceptions = vec![self.parse_identifier()?];
+            while self.consume_keyword(Keyword::Logical(Logical::Or)) {
+                exceptions.push(self.parse_identifier()?);
+            }
+            exceptions
+        };
+        self.expect_keyword(Keyword::Then)?;
+
+        Ok(ExceptionHandler {
+            exceptions,
+            statements: self.parse_script_statements()?,
+        })
+    }
+
+    /// Parses `;`-terminated scripting statements up to the keyword that ends
+    /// the enclosing construct.
+    fn parse_script_statements(&mut self) -> Result<Vec<ScriptStatement>> {
+        let mut statements = Vec::new();
+        loop {
+            while self.consume_token(&Token::Semicolon) {}
+            if self.peek_script_terminator() {
+                break;
+            }
+
+            statements.push(self.parse_script_statement()?);
+            self.expect_token(&Token::Semicolon)?;
+        }
+
+        Ok(statements)
+    }
+
+    fn peek_script_terminator(&self) -> bool {
+        self.peek_token() == &Token::EOF
+            || matches!(
+                self.peek_keyword(),
+                Some(Keyword::End | Keyword::Else | Keyword::When)
+            )
+            || self.peek_word("elseif")
+            || self.peek_word("exception")
+            || self.peek_word("until")
+    }
+
+    fn parse_script_statement(&mut self) -> Result<ScriptStatement> {
+        if self.peek_nth_token(1) == &Token::Assign {
+            let name = self.parse_identifier()?;
+            self.expect_token(&Token::Assign)?;
+            return Ok(ScriptStatement::Assign {
+                name,
+                value: self.parse_expr()?,
+            });
+        }
+
+        if self.peek_token() == &Token::Null {
+            self.next_token();
+            return Ok(ScriptStatement::Null);
+        }
+
+        match self.peek_keyword() {
+            Some(Keyword::If) => return self.parse_if(),
+            Some(Keyword::Case) => return self.parse_case_statement(),
+            Some(Keyword::For) => return self.parse_for(),
+            Some(Keyword::Return) => {
+                self.next_token();
+                if self.peek_token() == &Token::Semicolon {
+                    return Ok(ScriptStatement::Return(None));
+                }
+                return Ok(ScriptStatement::Return(Some(self.parse_expr()?)));
+            }
+            _ => {}
+        }
+
+        if self.peek_word("begin") && !self.peek_transaction_start() || self.peek_word("declare") {
+            return Ok(ScriptStatement::Block(self.parse_block()?));
+        }
+
+        if self.consume_word("let") {
+            let name = self.parse_identifier()?;
+            return Ok(ScriptStatement::Let(self.parse_declaration(name)?));
+        }
+
+        if self.consume_word("while") {
+            let condition = self.parse_expr()?;
+            let terminator = self.parse_loop_start()?;
+            let statements = self.parse_script_statements()?;
+            return Ok(ScriptStatement::While {
+                condition,
+                statements,
+                label: self.parse_loop_end(terminator)?,
+            });
+        }
+
+        if self.consume_word("repeat") {
+            let statements = self.parse_script_statements()?;
+            self.expect_word("until")?;
+            let until = self.parse_expr()?;
+            return Ok(ScriptStatement::Repeat {
+                statements,
+                until,
+                label: self.parse_loop_end("repeat")?,
+            });
+        }
+
+        if self.consume_word("loop") {
+            let statements = self.parse_script_statements()?;
+            return Ok(ScriptStatement::Loop {
+                statements,
+                label: self.parse_loop_end("loop")?,
+            });
+        }
+
+        if self.consume_word("break") || self.consume_word("exit") {
+            return Ok(ScriptStatement::Break(self.parse_optional_label()?));
+        }
+
+        if self.consume_word("continue") || self.consume_word("iterate") {
+            return Ok(ScriptStatement::Continue(self.parse_optional_label()?));
+        }
+
+        if self.consume_word("raise") {
+            return Ok(ScriptStatement::Raise(self.parse_optional_label()?));
+        }
+
+        if self.consume_word("open") {
+            let cursor = self.parse_identifier()?;
+            let using = if self.consume_keyword(Keyword::Using) {
+                self.expect_token(&Token::OpenParen)?;
+                let using = self.parse_comma_separated(Parser::parse_expr)?;
+                self.expect_token(&Token::CloseParen)?;
+                using
+            } else {
+                vec![]
+            };
+            return Ok(ScriptStatement::Open { cursor, using });
+        }
+
+        if self.consume_word("fetch") {
+            let cursor = self.parse_identifier()?;
+            self.expect_keyword(Keyword::Into)?;
+            return Ok(ScriptStatement::Fetch {
+                cursor,
+                into: self.parse_comma_separated(Parser::parse_identifier)?,
+            });
+        }
+
+        if self.consume_word("close") {
+            return Ok(ScriptStatement::Close(self.parse_identifier()?));
+        }
+
+        Ok(ScriptStatement::Sql(self.parse_statement()?))
+    }
+
+    /// Parses `IF (cond) THEN ... [ELSEIF (cond) THEN ...] [ELSE ...] END IF`.
+    fn parse_if(&mut self) -> Result<ScriptStatement> {
+        self.expect_keyword(Keyword::If)?;
+        let mut branches = vec![self.parse_conditional_block()?];
+        while self.consume_word("elseif") {
+            branches.push(self.parse_conditional_block()?);
+        }
+
+        let else_statements = if self.consume_keyword(Keyword::Else) {
+            Some(self.parse_script_statements()?)
+        } else {
+            None
+        };
+
+        self.expect_keyword(Keyword::End)?;
+        self.expect_keyword(Keyword::If)?;
+
+        Ok(ScriptStatement::If {
+            branches,
+            else_statements,
+        })
+    }
+
+    fn parse_conditional_block(&mut self) -> Result<ConditionalBlock> {
+        let condition = self.parse_expr()?;
+        self.expect_keyword(Keyword::Then)?;
+        Ok(ConditionalBlock {
+            condition,
+            statements: self.parse_script_statements()?,
+        })
+    }
+
+    /// Parses both the simple and the searched forms of the scripting `CASE`.
+    fn parse_case_statement(&mut self) -> Result<ScriptStatement> {
+        self.expect_keyword(Keyword::Case)?;
+        let operand = if self.peek_keyword() != Some(Keyword::When) {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        let mut branches = Vec::new();
+        while self.consume_keyword(Keyword::When) {
+            branches.push(self.parse_conditional_block()?);
+        }
+
+        let else_statements = if self.consume_keyword(Keyword::Else) {
+            Some(self.parse_script_statements()?)
+        } else {
+            None
+        };
+
+        self.expect_keyword(Keyword::End)?;
+        self.expect_keyword(Keyword::Case)?;
+
+        Ok(ScriptStatement::Case {
+            operand,
+            branches,
+            else_statements,
+        })
+    }
+
+    /// Parses the counter-based and the cursor-based `FOR` loops.
+    fn parse_for(&mut self) -> Result<ScriptStatement> {
+        self.expect_keyword(Keyword::For)?;
+        let variable = self.parse_identifier()?;
+        self.expect_keyword(Keyword::In)?;
+        let reverse = self.consume_word("reverse");
+        let start = self.parse_expr()?;
+
+        if self.consume_word("to") {
+            let end = self.parse_expr()?;
+            let terminator = self.parse_loop_start()?;
+            let statements = self.parse_script_statements()?;
+            return Ok(ScriptStatement::ForRange {
+                variable,
+                reverse,
+                start,
+                end,
+                statements,
+                label: self.parse_loop_end(terminator)?,
+            });
+        }
+
+        let Expr::Identifier(cursor) = start else {
+            bail!(
+                "Expected a cursor name or a range in FOR loop, found {:?}",
+                start
+            );
+        };
+        let terminator = self.parse_loop_start()?;
+        let statements = self.parse_script_statements()?;
+
+        Ok(ScriptStatement::ForCursor {
+            variable,
+            cursor,
+            statements,
+            label: self.parse_loop_end(terminator)?,
+        })
+    }
+
+    /// Parses the `DO` or `LOOP` that opens a loop body, returning the keyword
+    /// that must close it: `END FOR`/`END WHILE` or `END LOOP`.
+    fn parse_loop_start(&mut self) -> Result<&'static str> {
+        if self.consume_word("do") {
+            Ok("")
+        } else {
+            self.expect_word("loop")?;
+            Ok("loop")
+        }
+    }
+
+    /// Parses `END <keyword> [label]`. An empty `keyword` accepts the `FOR` or
+    /// `WHILE` matching a loop opened with `DO`.
+    fn parse_loop_end(&mut self, keyword: &str) -> Result<Option<Ident>> {
+        self.expect_keyword(Keyword::End)?;
+        if keyword.is_empty() {
+            if !self.consume_keyword(Keyword::For) {
+                self.expect_word("while")?;
+            }
+        } else {
+            self.expect_word(keyword)?;
+        }
+
+        self.parse_optional_label()
+    }
+
+    fn parse_optional_label(&mut self) -> Result<Option<Ident>> {
+        match self.peek_token() {
+            Token::Word(word) if !word.is_reserved() => Ok(Some(self.parse_identifier()?)),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::ast::data_type::DataType;
+    use crate::ast::expression::{BinaryOperator, Expr, Ident, Value};
+    use crate::ast::scripting::{CursorSource, Declaration, ScriptStatement};
+    use crate::ast::statement::{ExecuteImmediate, RoutineBody, Statement};
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn parses_procedure_body() -> Result<()> {
+        let sql = r#"
+CREATE OR REPLACE PROCEDURE update_salaries(threshold NUMBER)
+RETURNS VARCHAR
+LANGUAGE SQL
+AS
+$$
+DECLARE
+  counter INT DEFAULT 0;
+  c1 CURSOR FOR SELECT id, salary FROM salaries;
+  too_many EXCEPTION (-20001, 'Too many rows');
+BEGIN
+  FOR rec IN c1 DO
+    counter := counter + 1;
+  END FOR;
+  IF (counter > 100) THEN
+    RAISE too_many;
+  ELSEIF (counter = 0) THEN
+    RETURN 'nothing to do';
+  ELSE
+    UPDATE salaries SET salary = salary * 1.1 WHERE salary < :threshold;
+  END IF;
+  LET res RESULTSET := (SELECT * FROM salaries);
+  RETURN 'done';
+EXCEPTION
+  WHEN too_many THEN
+    RETURN 'too many';
+  WHEN OTHER THEN
+    INSERT INTO errors (message) VALUES ('failed');
+    RETURN 'failed';
+END;
+$$;
+"#;
+        let statements = Parser::from_sql(sql).parse_statements()?;
+        assert_eq!(statements.len(), 1);
+
+        let Statement::CreateRoutine(routine) = &statements[0] else {
+            panic!("Expected a procedure, found {:?}", statements[0]);
+        };
+        let RoutineBody::Script(block) = &routine.body else {
+            panic!("Expected a scripting body, found {:?}", routine.body);
+        };
+
+        assert_eq!(
+            block.declarations[0],
+            Declaration::Variable {
+                name: Ident::new("counter"),
+                data_type: Some(DataType::Int),
+                default: Some(Expr::Value(Value::Int(0))),
+            }
+        );
+        assert!(matches!(
+            &block.declarations[1],
+            Declaration::Cursor {
+                source: CursorSource::Query(_),
+                ..
+            }
+        ));
+        assert!(matches!(
+            &block.declarations[2],
+            Declaration::Exception { code: Some(_), .. }
+        ));
+
+        let ScriptStatement::ForCursor {
+            statements: loop_body,
+            ..
+        } = &block.statements[0]
+        else {
+            panic!("Expected a FOR loop, found {:?}", block.statements[0]);
+        };
+        assert_eq!(
+            loop_body[0],
+            ScriptStatement::Assign {
+                name: Ident::new("counter"),
+                value: Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier(Ident::new("counter"))),
+                    op: BinaryOperator::Plus,
+                    right: Box::new(Expr::Value(Value::Int(1))),
+                },
+            }
+        );
+
+        let ScriptStatement::If {
+            branches,
+            else_statements,
+        } = &block.statements[1]
+        else {
+            panic!("Expected an IF, found {:?}", block.statements[1]);
+        };
+        assert_eq!(branches.len(), 2);
+        assert!(else_statements.is_some());
+
+        assert!(matches!(
+            &block.statements[2],
+            ScriptStatement::Let(Declaration::ResultSet {
+                default: Some(Expr::Subquery(_)),
+                ..
+            })
+        ));
+        assert_eq!(block.exception_handlers.len(), 2);
+        assert!(block.exception_handlers[1].exceptions.is_empty());
+
+        // The UPDATE in the ELSE branch, the resultset query is an expression,
+        // and the INSERT in the handler
+        let nested = block.sql_statements();
+        assert_eq!(nested.len(), 2);
+        assert!(matches!(nested[0], Statement::Update { .. }));
+        assert!(matches!(nested[1], Statement::Insert { .. }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_loops_in_anonymous_block() -> Result<()> {
+        let sql = r#"
+DECLARE
+  total INT;
+BEGIN
+  total := 0;
+  FOR i IN REVERSE 1 TO 10 DO
+    total := total + i;
+  END FOR;
+  WHILE (total > 0) LOOP
+    total := total - 1;
+  END LOOP;
+  REPEAT
+    total := total + 1;
+  UNTIL (total >= 5)
+  END REPEAT;
+  LOOP
+    BREAK;
+  END LOOP;
+  CASE (total)
+    WHEN 5 THEN
+      RETURN total;
+    ELSE
+      NULL;
+  END CASE;
+  BEGIN TRANSACTION;
+  DELETE FROM staging WHERE loaded;
+  COMMIT;
+END;
+"#;
+        let statements = Parser::from_sql(sql).parse_statements()?;
+        let Statement::Block(block) = &statements[0] else {
+            panic!("Expected a block, found {:?}", statements[0]);
+        };
+
+        assert!(matches!(
+            &block.statements[1],
+            ScriptStatement::ForRange { reverse: true, .. }
+        ));
+        assert!(matches!(
+            &block.statements[2],
+            ScriptStatement::While { .. }
+        ));
+        assert!(matches!(
+            &block.statements[3],
+            ScriptStatement::Repeat { .. }
+        ));
+        assert!(matches!(
+            &block.statements[4],
+            ScriptStatement::Loop { statements, .. } if statements == &vec![ScriptStatement::Break(None)]
+        ));
+        assert!(matches!(
+            &block.statements[5],
+            ScriptStatement::Case {
+                operand: Some(_),
+                ..
+            }
+        ));
+        assert_eq!(
+            block.statements[6],
+            ScriptStatement::Sql(Statement::StartTransaction)
+        );
+        assert!(matches!(
+            &block.statements[7],
+            ScriptStatement::Sql(Statement::Delete { .. })
+        ));
+        assert_eq!(block.statements[8], ScriptStatement::Sql(Statement::Commit));
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_dollar_quoted_bodies() -> Result<()> {
+        let sql = r#"
+CREATE PROCEDURE js_proc()
+RETURNS STRING
+LANGUAGE JAVASCRIPT
+AS $$ return 'it''s { done }'; $$;
+
+EXECUTE IMMEDIATE $$
+BEGIN
+  INSERT INTO audit SELECT $1 FROM staging;
+END;
+$$;
+"#;
+        let statements = Parser::from_sql(sql).parse_statements()?;
+
+        let Statement::CreateRoutine(routine) = &statements[0] else {
+            panic!("Expected a procedure, found {:?}", statements[0]);
+        };
+        assert_eq!(
+            routine.body,
+            RoutineBody::Unparsed(" return 'it''s { done }'; ".to_string())
+        );
+
+        let Statement::ExecuteImmediate(ExecuteImmediate::Block(block)) = &statements[1] else {
+            panic!("Expected EXECUTE IMMEDIATE, found {:?}", statements[1]);
+        };
+        assert!(matches!(
+            block.sql_statements()[0],
+            Statement::Insert { .. }
+        ));
+
+        // Errors inside a body are located in the script and recovered from
+        let sql = "CREATE PROCEDURE p() RETURNS INT LANGUAGE SQL AS $$\nBEGIN\n  SELECT a FROM t WHERE GROUP BY a;\n  RETURN 1;\nEND;\n$$;\nEXECUTE IMMEDIATE $$ BEGIN SELEC 1; END $$;";
+        let (statements, errors) = Parser::from_sql(sql).parse_statements_with_recovery();
+
+        assert!(matches!(statements[0], Statement::CreateRoutine(_)));
+        assert!(matches!(statements[1], Statement::Error(_)));
+        assert_eq!(errors.len(), 2);
+        assert_eq!(&sql[errors[0].span.start..errors[0].span.end], "GROUP");
+        assert_eq!(&sql[errors[1].span.start..errors[1].span.end], "SELEC");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,543 @@
+use anyhow::{bail, Result};
+
+use crate::ast::expression::{Ident, ObjectName, Value};
+use crate::ast::stage::{
+    CopyInto, CopyLocation, FileFormat, KeyValueOption, OptionValue, Stage, StageRef,
+};
+use crate::ast::statement::Statement;
+use crate::lexer::lexer::{Keyword, Token, Word};
+use crate::parser::parser::Parser;
+
+impl<'a> Parser<'a> {
+    /// A stage is either an `@` reference or a quoted `'@stage/path with spaces/'`.
+    pub(crate) fn peek_stage_ref(&self) -> bool {
+        match self.peek_token() {
+            Token::StageRef(_) => true,
+            Token::Varchar(literal) => literal.value.starts_with('@'),
+            _ => false,
+        }
+    }
+
+    pub(crate) fn parse_stage_ref(&mut self) -> Result<StageRef> {
+        match self.next_token() {
+            Token::StageRef(text) => parse_stage_text(&text),
+            Token::Varchar(literal) if literal.value.starts_with('@') => {
+                parse_stage_text(&literal.value.into_owned()[1..])
+            }
+            token => Err(self.unexpected("a stage", &token).into()),
+        }
+    }
+
+    /// `COPY INTO <table | stage | 'url'> FROM <stage | 'url' | table | (query)> ...`
+    pub(crate) fn parse_copy_into(&mut self) -> Result<Statement> {
+        self.expect_word("copy")?;
+        self.expect_keyword(Keyword::Into)?;
+        let into = self.parse_copy_location()?;
+        self.expect_keyword(Keyword::From)?;
+        let from = self.parse_copy_location()?;
+
+        let mut copy = CopyInto {
+            into,
+            from,
+            files: vec![],
+            pattern: None,
+            file_format: None,
+            partition_by: None,
+            options: vec![],
+        };
+
+        loop {
+            if self.consume_word("partition") {
+                self.expect_keyword(Keyword::By)?;
+                copy.partition_by = Some(self.parse_expr()?);
+                continue;
+            }
+            if !self.peek_option() {
+                break;
+            }
+
+            let option = self.parse_key_value_option()?;
+            match (option.name.value.to_lowercase().as_str(), option.value) {
+                ("files", OptionValue::List(files)) => {
+                    copy.files = files
+                        .into_iter()
+                        .map(option_string)
+                        .collect::<Result<_>>()?;
+                }
+                ("pattern", value) => copy.pattern = Some(option_string(value)?),
+                ("file_format", value) => copy.file_format = Some(file_format(value)?),
+                (_, value) => copy.options.push(KeyValueOption {
+                    name: option.name,
+                    value,
+                }),
+            }
+        }
+
+        Ok(Statement::CopyInto(Box::new(copy)))
+    }
+
+    fn parse_copy_location(&mut self) -> Result<CopyLocation> {
+        if self.peek_stage_ref() {
+            return Ok(CopyLocation::Stage(self.parse_stage_ref()?));
+        }
+
+        match self.peek_token() {
+            Token::Varchar(_) => {
+                let Token::Varchar(literal) = self.next_token() else {
+                    unreachable!("checked by the match");
+                };
+                Ok(CopyLocation::External(literal.value.into_owned()))
+            }
+            Token::OpenParen => {
+                self.next_token();
+                let query = self.parse_query()?;
+                self.expect_token(&Token::CloseParen)?;
+                Ok(CopyLocation::Query(Box::new(query)))
+            }
+            _ => Ok(CopyLocation::Table {
+                name: self.parse_object_name()?,
+                columns: self.parse_parenthesized_identifiers()?,
+            }),
+        }
+    }
+
+    /// `PUT file://<path> @stage [options]`
+    pub(crate) fn parse_put(&mut self) -> Result<Statement> {
+        self.expect_word("put")?;
+        let file = self.parse_file_uri()?;
+        let stage = self.parse_stage_ref()?;
+        Ok(Statement::Put {
+            file,
+            stage,
+            options: self.parse_key_value_options()?,
+        })
+    }
+
+    /// `GET @stage file://<path> [options]`
+    pub(crate) fn parse_get(&mut self) -> Result<Statement> {
+        self.expect_word("get")?;
+        let stage = self.parse_stage_ref()?;
+        let file = self.parse_file_uri()?;
+        Ok(Statement::Get {
+            stage,
+            file,
+            options: self.parse_key_value_options()?,
+        })
+    }
+
+    /// `{ LIST | LS | REMOVE | RM } @stage [PATTERN = '<regex>']`
+    pub(crate) fn parse_list_or_remove(&mut self) -> Result<Statement> {
+        let remove = if self.consume_word("list") || self.consume_word("ls") {
+            false
+        } else if self.consume_word("remove") || self.consume_word("rm") {
+            true
+        } else {
+            bail!("Expected LIST or REMOVE, found {}", self.peek_token())
+        };
+        let stage = self.parse_stage_ref()?;
+        let pattern = if self.consume_word("pattern") {
+            self.expect_token(&Token::Equal)?;
+            Some(option_string(self.parse_option_value()?)?)
+        } else {
+            None
+        };
+
+        Ok(if remove {
+            Statement::Remove { stage, pattern }
+        } else {
+            Statement::List { stage, pattern }
+        })
+    }
+
+    /// `CREATE STAGE` and `CREATE FILE FORMAT`, whose bodies are only options.
+    pub(crate) fn parse_create_stage_or_file_format(
+        &mut self,
+        or_replace: bool,
+        temporary: bool,
+    ) -> Result<Statement> {
+        let stage = self.consume_word("stage");
+        if !stage {
+            self.expect_word("file")?;
+            self.expect_word("format")?;
+        }
+        let if_not_exists = self.parse_if_not_exists()?;
+        let name = self.parse_object_name()?;
+        let options = self.parse_key_value_options()?;
+
+        Ok(if stage {
+            Statement::CreateStage {
+                or_replace,
+                temporary,
+                if_not_exists,
+                name,
+                options,
+            }
+        } else {
+            Statement::CreateFileFormat {
+                or_replace,
+                temporary,
+                if_not_exists,
+                name,
+                options,
+            }
+        })
+    }
+
+    fn parse_file_uri(&mut self) -> Result<String> {
+        match self.next_token() {
+            Token::FileUri(uri) => Ok(uri.into_owned()),
+            Token::Varchar(literal) => Ok(literal.value.into_owned()),
+            token => Err(self.unexpected("a file:// URI", &token).into()),
+        }
+    }
+
+    fn peek_option(&self) -> bool {
+        matches!(self.peek_token(), Token::Word(_)) && self.peek_nth_token(1) == &Token::Equal
+    }
+
+    /// Parses `NAME = value` options separated by whitespace or commas.
+    fn parse_key_value_options(&mut self) -> Result<Vec<KeyValueOption>> {
+        let mut options = Vec::new();
+        while self.peek_option() {
+            options.push(self.parse_key_value_option()?);
+            self.consume_token(&Token::Comma);
+        }
+
+        Ok(options)
+    }
+
+    fn parse_key_value_option(&mut self) -> Result<KeyValueOption> {
+        // Option names such as `TYPE` are never ambiguous, so any word goes
+        let name = match self.next_token() {
+            Token::Word(Word {
+                value, quote_style, ..
+            }) => Ident {
+                value: value.into_owned(),
+                quote_style,
+            },
+            token => return Err(self.unexpected("an option name", &token).into()),
+        };
+        self.expect_token(&Token::Equal)?;
+        Ok(KeyValueOption {
+            name,
+            value: self.parse_option_value()?,
+        })
+    }
+
+    pub(crate) fn parse_option_value(&mut self) -> Result<OptionValue> {
+        let value = match self.next_token() {
+            Token::OpenParen if self.peek_option() => {
+                let options = self.parse_key_value_options()?;
+                self.expect_token(&Token::CloseParen)?;
+                return Ok(OptionValue::Options(options));
+            }
+            Token::OpenParen => {
+                let values = if self.peek_token() == &Token::CloseParen {
+                    vec![]
+                } else {
+                    self.parse_comma_separated(Parser::parse_option_value)?
+                };
+                self.expect_token(&Token::CloseParen)?;
+                return Ok(OptionValue::List(values));
+            }
+            Token::Word(Word {
+                value, quote_style, ..
+            }) => {
+                return Ok(OptionValue::Ident(Ident {
+                    value: value.into_owned(),
+                    quote_style,
+                }))
+            }
+            Token::Varchar(literal) => Value::Varchar(literal.value.into_owned()),
+            Token::Int(value) => Value::Int(value),
+            Token::Decimal(value) => Value::Decimal(value.into_owned()),
+            Token::Float(value) => Value::Float(value),
+            Token::Bool(value) => Value::Bool(value),
+            Token::Null => Value::Null,
+            token => return Err(self.unexpected("an option value", &token).into()),
+        };
+
+        Ok(OptionValue::Value(value))
+    }
+}
+
+/// Splits the text following an `@` into the stage name and path.
+fn parse_stage_text(text: &str) -> Result<StageRef> {
+    let mut in_quotes = false;
+    let name_end = text
+        .char_indices()
+        .find(|&(_, ch)| {
+            if ch == '"' {
+                in_quotes = !in_quotes;
+            }
+            ch == '/' && !in_quotes
+        })
+        .map_or(text.len(), |(index, _)| index);
+
+    let (name, path) = text.split_at(name_end);
+    let path = path.strip_prefix('/').filter(|path| !path.is_empty());
+    let stage = if name == "~" {
+        Stage::User
+    } else if let Some(table) = name.strip_prefix('%') {
+        Stage::Table(parse_dotted_name(table)?)
+    } else {
+        Stage::Named(parse_dotted_name(name)?)
+    };
+
+    Ok(StageRef {
+        stage,
+        path: path.map(str::to_string),
+    })
+}
+
+/// Splits `db."my schema".name` on the dots outside of quotes.
+fn parse_dotted_name(text: &str) -> Result<ObjectName> {
+    let mut idents = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for ch in text.chars() {
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            '.' if !in_quotes => idents.push(Ident::new(std::mem::take(&mut current))),
+            ch => current.push(ch),
+        }
+    }
+    idents.push(Ident::new(current));
+
+    if in_quotes || idents.iter().any(|ident| ident.value.is_empty()) {
+        bail!("Invalid name {:?}", text);
+    }
+    Ok(ObjectName(idents))
+}
+
+fn option_string(value: OptionValue) -> Result<String> {
+    match value {
+        OptionValue::Value(Value::Varchar(value)) => Ok(value),
+        OptionValue::Ident(ident) => Ok(ident.value),
+        value => bail!("Expected a string option, found {:?}", value),
+    }
+}
+
+fn file_format(value: OptionValue) -> Result<FileFormat> {
+    match value {
+        OptionValue::Options(options) => {
+            match options
+                .iter()
+                .position(|option| option.name.value.eq_ignore_ascii_case("format_name"))
+            {
+                Some(index) => {
+                    let name = option_string(options[index].value.clone())?;
+                    Ok(FileFormat::Named(parse_dotted_name(&name)?))
+                }
+                None => Ok(FileFormat::Options(options)),
+            }
+        }
+        OptionValue::Ident(_) | OptionValue::Value(Value::Varchar(_)) => Ok(FileFormat::Named(
+            parse_dotted_name(&option_string(value)?)?,
+        )),
+        value => bail!("Expected a file format, found {:?}", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::ast::expression::{Expr, Ident, ObjectName, Value};
+    use crate::ast::query::{SelectItem, SetExpr, TableAlias, TableFactor};
+    use crate::ast::stage::{
+        CopyInto, CopyLocation, FileFormat, KeyValueOption, OptionValue, Stage, StageRef,
+    };
+    use crate::ast::statement::Statement;
+    use crate::parser::parser::Parser;
+
+    fn option(name: &str, value: OptionValue) -> KeyValueOption {
+        KeyValueOption {
+            name: Ident::new(name),
+            value,
+        }
+    }
+
+    #[test]
+    fn parses_copy_into_table() -> Result<()> {
+        let sql = "COPY INTO raw.orders (id, amount) FROM @raw.landing/orders/ \
+                   FILE_FORMAT = (TYPE = CSV FIELD_DELIMITER = '|' SKIP_HEADER = 1 NULL_IF = ('', 'NULL')) \
+                   PATTERN = '.*[.]csv' ON_ERROR = CONTINUE";
+        let statements = Parser::from_sql(sql).parse_statements()?;
+
+        assert_eq!(
+            statements,
+            vec![Statement::CopyInto(Box::new(CopyInto {
+                into: CopyLocation::Table {
+                    name: ObjectName(vec![Ident::new("raw"), Ident::new("orders")]),
+                    columns: vec![Ident::new("id"), Ident::new("amount")],
+                },
+                from: CopyLocation::Stage(StageRef {
+                    stage: Stage::Named(ObjectName(vec![Ident::new("raw"), Ident::new("landing")])),
+                    path: Some("orders/".to_string()),
+                }),
+                files: vec![],
+                pattern: Some(".*[.]csv".to_string()),
+                file_format: Some(FileFormat::Options(vec![
+                    option("TYPE", OptionValue::Ident(Ident::new("CSV"))),
+                    option(
+                        "FIELD_DELIMITER",
+                        OptionValue::Value(Value::Varchar("|".to_string()))
+                    ),
+                    option("SKIP_HEADER", OptionValue::Value(Value::Int(1))),
+                    option(
+                        "NULL_IF",
+                        OptionValue::List(vec![
+                            OptionValue::Value(Value::Varchar("".to_string())),
+                            OptionValue::Value(Value::Varchar("NULL".to_string())),
+                        ])
+                    ),
+                ])),
+                partition_by: None,
+                options: vec![option(
+                    "ON_ERROR",
+                    OptionValue::Ident(Ident::new("CONTINUE"))
+                )],
+            }))]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_copy_into_stage_from_query() -> Result<()> {
+        let sql = "COPY INTO @~/unload/ FROM (SELECT id, region FROM orders) \
+                   PARTITION BY region FILE_FORMAT = (FORMAT_NAME = 'util.parquet') HEADER = TRUE";
+        let statements = Parser::from_sql(sql).parse_statements()?;
+
+        let Statement::CopyInto(copy) = &statements[0] else {
+            panic!("Expected COPY INTO, found {:?}", statements[0]);
+        };
+        assert!(copy.is_unload());
+        assert_eq!(
+            copy.into,
+            CopyLocation::Stage(StageRef {
+                stage: Stage::User,
+                path: Some("unload/".to_string()),
+            })
+        );
+        assert!(matches!(copy.from, CopyLocation::Query(_)));
+        assert_eq!(
+            copy.partition_by,
+            Some(Expr::Identifier(Ident::new("region")))
+        );
+        assert_eq!(
+            copy.file_format,
+            Some(FileFormat::Named(ObjectName(vec![
+                Ident::new("util"),
+                Ident::new("parquet")
+            ])))
+        );
+        assert_eq!(
+            copy.options,
+            vec![option("HEADER", OptionValue::Value(Value::Bool(true)))]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_file_commands() -> Result<()> {
+        let sql = "PUT file:///tmp/orders.csv @%orders AUTO_COMPRESS = FALSE; \
+                   GET @my_stage/out/ 'file:///tmp/out dir/'; \
+                   LS @my_stage PATTERN = '.*gz'; \
+                   RM @\"My Stage\"/old/;";
+        let statements = Parser::from_sql(sql).parse_statements()?;
+        let my_stage = Stage::Named(ObjectName(vec![Ident::new("my_stage")]));
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Put {
+                    file: "file:///tmp/orders.csv".to_string(),
+                    stage: StageRef {
+                        stage: Stage::Table(ObjectName(vec![Ident::new("orders")])),
+                        path: None,
+                    },
+                    options: vec![option(
+                        "AUTO_COMPRESS",
+                        OptionValue::Value(Value::Bool(false))
+                    )],
+                },
+                Statement::Get {
+                    stage: StageRef {
+                        stage: my_stage.clone(),
+                        path: Some("out/".to_string()),
+                    },
+                    file: "file:///tmp/out dir/".to_string(),
+                    options: vec![],
+                },
+                Statement::List {
+                    stage: StageRef {
+                        stage: my_stage,
+                        path: None,
+                    },
+                    pattern: Some(".*gz".to_string()),
+                },
+                Statement::Remove {
+                    stage: StageRef {
+                        stage: Stage::Named(ObjectName(vec![Ident::new("My Stage")])),
+                        path: Some("old/".to_string()),
+                    },
+                    pattern: None,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_select_from_stage() -> Result<()> {
+        let sql = "SELECT t.$1, $2 FROM @landing (FILE_FORMAT => 'csv', PATTERN => '.*') t";
+        let statements = Parser::from_sql(sql).parse_statements()?;
+
+        let Statement::Query(query) = &statements[0] else {
+            panic!("Expected a query, found {:?}", statements[0]);
+        };
+        let SetExpr::Select(select) = &query.body else {
+            panic!("Expected a select, found {:?}", query.body);
+        };
+
+        assert_eq!(
+            select.projection,
+            vec![
+                SelectItem::UnnamedExpr(Expr::QualifiedPositional {
+                    qualifier: Ident::new("t"),
+                    position: 1,
+                }),
+                SelectItem::UnnamedExpr(Expr::Positional(2)),
+            ]
+        );
+        assert_eq!(
+            select.from[0].relation,
+            TableFactor::Stage {
+                stage: StageRef {
+                    stage: Stage::Named(ObjectName(vec![Ident::new("landing")])),
+                    path: None,
+                },
+                options: vec![
+                    option(
+                        "FILE_FORMAT",
+                        OptionValue::Value(Value::Varchar("csv".to_string()))
+                    ),
+                    option(
+                        "PATTERN",
+                        OptionValue::Value(Value::Varchar(".*".to_string()))
+                    ),
+                ],
+                alias: Some(TableAlias {
+                    name: Ident::new("t"),
+                    columns: vec![],
+                }),
+            }
+        );
+
+        Ok(())
+    }
+}
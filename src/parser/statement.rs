@@ -0,0 +1,588 @@
+use anyhow::{bail, Result};
+
+use crate::ast::expression::Ident;
+use crate::ast::query::TableWithJoins;
+use crate::ast::statement::{
+    Assignment, ColumnDef, CreateRoutine, ExecuteImmediate, Merge, MergeAction, MergeClause,
+    ObjectType, RoutineArg, RoutineBody, RoutineKind, RoutineReturns, Statement,
+};
+use crate::lexer::lexer::{DDLKeyword, DMLKeyword, Keyword, Logical, Token};
+use crate::parser::parser::Parser;
+
+/// Words that start a table constraint rather than a column in `CREATE TABLE`.
+const TABLE_CONSTRAINTS: [&str; 4] = ["constraint", "primary", "unique", "foreign"];
+
+impl<'a> Parser<'a> {
+    pub fn parse_statement(&mut self) -> Result<Statement> {
+        match self.peek_keyword() {
+            Some(Keyword::DML(DMLKeyword::Select) | Keyword::DDL(DDLKeyword::With)) => {
+                Ok(Statement::Query(Box::new(self.parse_query()?)))
+            }
+            _ if self.peek_token() == &Token::OpenParen => {
+                Ok(Statement::Query(Box::new(self.parse_query()?)))
+            }
+            Some(Keyword::DML(DMLKeyword::Insert)) => self.parse_insert(),
+            Some(Keyword::DML(DMLKeyword::Update)) => self.parse_update(),
+            Some(Keyword::DML(DMLKeyword::Delete)) => self.parse_delete(),
+            Some(Keyword::DML(DMLKeyword::Call)) => self.parse_call(),
+            Some(Keyword::DDL(DDLKeyword::Create)) => self.parse_create(),
+            Some(Keyword::DDL(DDLKeyword::Drop)) => self.parse_drop(),
+            Some(Keyword::DDL(DDLKeyword::Truncate)) => self.parse_truncate(),
+            Some(Keyword::Execute) => self.parse_execute_immediate(),
+            _ if self.peek_word("merge") => self.parse_merge(),
+            _ if self.peek_word("copy") => self.parse_copy_into(),
+            _ if self.peek_word("put") => self.parse_put(),
+            _ if self.peek_word("get") => self.parse_get(),
+            _ if ["list", "ls", "remove", "rm"]
+                .iter()
+                .any(|word| self.peek_word(word)) =>
+            {
+                self.parse_list_or_remove()
+            }
+            _ if self.peek_word("begin") && self.peek_transaction_start() => {
+                self.next_token();
+                if !self.consume_word("transaction") {
+                    self.consume_word("work");
+                }
+                Ok(Statement::StartTransaction)
+            }
+            _ if self.peek_word("start") => {
+                self.next_token();
+                self.expect_word("transaction")?;
+                Ok(Statement::StartTransaction)
+            }
+            _ if self.peek_word("commit") => {
+                self.next_token();
+                self.consume_word("work");
+                Ok(Statement::Commit)
+            }
+            _ if self.peek_word("rollback") => {
+                self.next_token();
+                self.consume_word("work");
+                Ok(Statement::Rollback)
+            }
+            _ if self.peek_word("declare") || self.peek_word("begin") => {
+                Ok(Statement::Block(Box::new(self.parse_block()?)))
+            }
+            _ => bail!("Expected a statement, found {}", self.peek_token()),
+        }
+    }
+
+    /// `BEGIN` starts a transaction rather than a block when it stands alone or
+    /// is followed by `TRANSACTION`/`WORK`.
+    pub(crate) fn peek_transaction_start(&self) -> bool {
+        matches!(self.peek_nth_token(1), Token::Semicolon | Token::EOF)
+            || self.peek_nth_word(1, "transaction")
+            || self.peek_nth_word(1, "work")
+    }
+
+    fn parse_insert(&mut self) -> Result<Statement> {
+        self.expect_keyword(Keyword::DML(DMLKeyword::Insert))?;
+        let overwrite = self.consume_word("overwrite");
+        self.expect_keyword(Keyword::Into)?;
+        let table_name = self.parse_object_name()?;
+
+        let columns = if self.peek_token() == &Token::OpenParen
+            && !matches!(
+                self.peek_nth_keyword(1),
+                Some(Keyword::DML(DMLKeyword::Select) | Keyword::DDL(DDLKeyword::With))
+            ) {
+            self.parse_parenthesized_identifiers()?
+        } else {
+            vec![]
+        };
+
+        Ok(Statement::Insert {
+            overwrite,
+            table_name,
+            columns,
+            source: Box::new(self.parse_query()?),
+        })
+    }
+
+    fn parse_update(&mut self) -> Result<Statement> {
+        self.expect_keyword(Keyword::DML(DMLKeyword::Update))?;
+        let table = Box::new(TableWithJoins {
+            relation: self.parse_table_factor()?,
+            joins: vec![],
+        });
+
+        self.expect_keyword(Keyword::Set)?;
+        let assignments = self.parse_assignments()?;
+
+        let from = if self.consume_keyword(Keyword::From) {
+            self.parse_comma_separated(Parser::parse_table_and_joins)?
+        } else {
+            vec![]
+        };
+
+        let selection = if self.consume_keyword(Keyword::Where) {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        Ok(Statement::Update {
+            table,
+            assignments,
+            from,
+            selection,
+        })
+    }
+
+    /// `col = expr, ...` after `SET`.
+    fn parse_assignments(&mut self) -> Result<Vec<Assignment>> {
+        self.parse_comma_separated(|parser| {
+            let mut target = parser.parse_identifier()?;
+            while parser.consume_token(&Token::Period) {
+                target = parser.parse_identifier()?;
+            }
+            parser.expect_token(&Token::Equal)?;
+            Ok(Assignment {
+                target,
+                value: parser.parse_expr()?,
+            })
+        })
+    }
+
+    fn parse_merge(&mut self) -> Result<Statement> {
+        self.expect_word("merge")?;
+        self.expect_keyword(Keyword::Into)?;
+        let target = self.parse_table_factor()?;
+        self.expect_keyword(Keyword::Using)?;
+        let source = self.parse_table_factor()?;
+        self.expect_keyword(Keyword::On)?;
+        let on = self.parse_expr()?;
+
+        let mut clauses = Vec::new();
+        while self.consume_keyword(Keyword::When) {
+            let matched = !self.consume_keyword(Keyword::Logical(Logical::Not));
+            self.expect_word("matched")?;
+            let predicate = if self.consume_keyword(Keyword::Logical(Logical::And)) {
+                Some(self.parse_expr()?)
+            } else {
+                None
+            };
+            self.expect_keyword(Keyword::Then)?;
+
+            let action = match self.next_token() {
+                Token::Word(word) if word.keyword == Some(Keyword::DML(DMLKeyword::Update)) => {
+                    self.expect_keyword(Keyword::Set)?;
+                    MergeAction::Update(self.parse_assignments()?)
+                }
+                Token::Word(word) if word.keyword == Some(Keyword::DML(DMLKeyword::Delete)) => {
+                    MergeAction::Delete
+                }
+                Token::Word(word) if word.keyword == Some(Keyword::DML(DMLKeyword::Insert)) => {
+                    let columns = if self.peek_token() == &Token::OpenParen {
+                        self.parse_parenthesized_identifiers()?
+                    } else {
+                        vec![]
+                    };
+                    self.expect_keyword(Keyword::Values)?;
+                    self.expect_token(&Token::OpenParen)?;
+                    let values = self.parse_comma_separated(Parser::parse_expr)?;
+                    self.expect_token(&Token::CloseParen)?;
+                    MergeAction::Insert { columns, values }
+                }
+                token => return Err(self.unexpected("UPDATE, DELETE or INSERT", &token).into()),
+            };
+            clauses.push(MergeClause {
+                matched,
+                predicate,
+                action,
+            });
+        }
+        if clauses.is_empty() {
+            return Err(self.expected("`WHEN`").into());
+        }
+
+        Ok(Statement::Merge(Box::new(Merge {
+            target,
+            source,
+            on,
+            clauses,
+        })))
+    }
+
+    fn parse_delete(&mut self) -> Result<Statement> {
+        self.expect_keyword(Keyword::DML(DMLKeyword::Delete))?;
+        self.expect_keyword(Keyword::From)?;
+        let table_name = self.parse_object_name()?;
+
+        let using = if self.consume_keyword(Keyword::Using) {
+            self.parse_comma_separated(Parser::parse_table_and_joins)?
+        } else {
+            vec![]
+        };
+
+        let selection = if self.consume_keyword(Keyword::Where) {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        Ok(Statement::Delete {
+            table_name,
+            using,
+            selection,
+        })
+    }
+
+    fn parse_call(&mut self) -> Result<Statement> {
+        self.expect_keyword(Keyword::DML(DMLKeyword::Call))?;
+        let name = self.parse_object_name()?;
+
+        self.expect_token(&Token::OpenParen)?;
+        let args = if self.peek_token() == &Token::CloseParen {
+            vec![]
+        } else {
+            self.parse_comma_separated(Parser::parse_function_arg)?
+        };
+        self.expect_token(&Token::CloseParen)?;
+
+        Ok(Statement::Call { name, args })
+    }
+
+    fn parse_create(&mut self) -> Result<Statement> {
+        self.expect_keyword(Keyword::DDL(DDLKeyword::Create))?;
+        let or_replace = if self.consume_keyword(Keyword::Logical(Logical::Or)) {
+            self.expect_keyword(Keyword::DDL(DDLKeyword::Replace))?;
+            true
+        } else {
+            false
+        };
+
+        let temporary = self.consume_keyword(Keyword::Temporary);
+        for modifier in ["transient", "volatile", "secure"] {
+            self.consume_word(modifier);
+        }
+        let materialized = self.consume_keyword(Keyword::Materialized);
+        if self.peek_word("stage") || self.peek_word("file") {
+            return self.parse_create_stage_or_file_format(or_replace, temporary);
+        }
+
+        match self.peek_keyword() {
+            Some(Keyword::Table) => {
+                self.next_token();
+                self.parse_create_table(or_replace, temporary)
+            }
+            Some(Keyword::View) => {
+                self.next_token();
+                self.parse_create_view(or_replace, materialized)
+            }
+            Some(Keyword::Procedure) => {
+                self.next_token();
+                self.parse_create_routine(RoutineKind::Procedure, or_replace)
+            }
+            Some(Keyword::Function) => {
+                self.next_token();
+                self.parse_create_routine(RoutineKind::Function, or_replace)
+            }
+            _ => bail!("Unsupported CREATE statement for {}", self.next_token()),
+        }
+    }
+
+    /// Whether the next token is the `AS` before a view query or routine
+    /// body, or the end of the statement.
+    fn peek_as_or_end(&self) -> bool {
+        matches!(self.peek_token(), Token::Semicolon | Token::EOF)
+            || self.peek_keyword() == Some(Keyword::As)
+    }
+
+    pub(crate) fn parse_if_not_exists(&mut self) -> Result<bool> {
+        if self.consume_keyword(Keyword::If) {
+            self.expect_keyword(Keyword::Logical(Logical::Not))?;
+            self.expect_keyword(Keyword::Exists)?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn parse_create_table(&mut self, or_replace: bool, temporary: bool) -> Result<Statement> {
+        let if_not_exists = self.parse_if_not_exists()?;
+        let name = self.parse_object_name()?;
+
+        let columns = if self.consume_token(&Token::OpenParen) {
+            let columns = self.parse_column_defs()?;
+            self.expect_token(&Token::CloseParen)?;
+            columns
+        } else {
+            vec![]
+        };
+        let copy_of = if self.consume_word("clone") || self.consume_keyword(Keyword::Like) {
+            Some(self.parse_object_name()?)
+        } else {
+            None
+        };
+
+        // Skip table options such as CLUSTER BY or COMMENT until a CTAS query
+        while !self.peek_as_or_end() {
+            self.skip_token_or_group()?;
+        }
+
+        let query = if self.consume_keyword(Keyword::As) {
+            Some(Box::new(self.parse_query()?))
+        } else {
+            None
+        };
+
+        Ok(Statement::CreateTable {
+            or_replace,
+            temporary,
+            if_not_exists,
+            name,
+            columns,
+            copy_of,
+            query,
+        })
+    }
+
+    /// Parses the column definitions of `CREATE TABLE t (...)`, skipping
+    /// constraints and column options we don't model.
+    fn parse_column_defs(&mut self) -> Result<Vec<ColumnDef>> {
+        let mut columns = Vec::new();
+        loop {
+            if TABLE_CONSTRAINTS.iter().any(|word| self.peek_word(word)) {
+                self.skip_column_def_rest()?;
+            } else {
+                let name = self.parse_identifier()?;
+                let data_type = self.parse_data_type()?;
+                let mut not_null = false;
+                while !matches!(
+                    self.peek_token(),
+                    Token::Comma | Token::CloseParen | Token::EOF
+                ) {
+                    if self.consume_keyword(Keyword::Logical(Logical::Not)) {
+                        self.expect_token(&Token::Null)?;
+                        not_null = true;
+                    } else {
+                        self.skip_token_or_group()?;
+                    }
+                }
+                columns.push(ColumnDef {
+                    name,
+                    data_type,
+                    not_null,
+                });
+            }
+
+            if !self.consume_token(&Token::Comma) {
+                break;
+            }
+        }
+
+        Ok(columns)
+    }
+
+    fn skip_column_def_rest(&mut self) -> Result<()> {
+        while !matches!(
+            self.peek_token(),
+            Token::Comma | Token::CloseParen | Token::EOF
+        ) {
+            self.skip_token_or_group()?;
+        }
+
+        Ok(())
+    }
+
+    fn parse_create_view(&mut self, or_replace: bool, materialized: bool) -> Result<Statement> {
+        let if_not_exists = self.parse_if_not_exists()?;
+        let name = self.parse_object_name()?;
+
+        let mut columns = Vec::new();
+        if self.consume_token(&Token::OpenParen) {
+            loop {
+                columns.push(self.parse_identifier()?);
+                self.skip_column_def_rest()?;
+                if !self.consume_token(&Token::Comma) {
+                    break;
+                }
+            }
+            self.expect_token(&Token::CloseParen)?;
+        }
+
+        while !self.peek_as_or_end() {
+            self.skip_token_or_group()?;
+        }
+        self.expect_keyword(Keyword::As)?;
+
+        Ok(Statement::CreateView {
+            or_replace,
+            materialized,
+            if_not_exists,
+            name,
+            columns,
+            query: Box::new(self.parse_query()?),
+        })
+    }
+
+    fn parse_create_routine(&mut self, kind: RoutineKind, or_replace: bool) -> Result<Statement> {
+        let name = self.parse_object_name()?;
+
+        self.expect_token(&Token::OpenParen)?;
+        let args = if self.peek_token() == &Token::CloseParen {
+            vec![]
+        } else {
+            self.parse_comma_separated(|parser| {
+                let name = parser.parse_identifier()?;
+                let data_type = parser.parse_data_type()?;
+                let default = if parser.consume_word("default") {
+                    Some(parser.parse_expr()?)
+                } else {
+                    None
+                };
+                Ok(RoutineArg {
+                    name,
+                    data_type,
+                    default,
+                })
+            })?
+        };
+        self.expect_token(&Token::CloseParen)?;
+
+        let mut returns = None;
+        let mut language = None;
+        let mut execute_as = None;
+        while !self.peek_as_or_end() {
+            match self.peek_keyword() {
+                Some(Keyword::Returns) => {
+                    self.next_token();
+                    returns = Some(if self.consume_keyword(Keyword::Table) {
+                        self.expect_token(&Token::OpenParen)?;
+                        let columns = self.parse_column_defs()?;
+                        self.expect_token(&Token::CloseParen)?;
+                        RoutineReturns::Table(columns)
+                    } else {
+                        RoutineReturns::DataType(self.parse_data_type()?)
+                    });
+                }
+                Some(Keyword::Language) => {
+                    self.next_token();
+                    language = Some(self.parse_identifier()?);
+                }
+                Some(Keyword::Execute) => {
+                    self.next_token();
+                    self.expect_keyword(Keyword::As)?;
+                    execute_as = Some(self.parse_identifier()?);
+                }
+                _ => self.skip_token_or_group()?,
+            }
+        }
+        self.expect_keyword(Keyword::As)?;
+
+        let is_sql = language
+            .as_ref()
+            .is_none_or(|language| language.value.eq_ignore_ascii_case("sql"));
+        // The body starts past the opening `$$` or quote. Escapes in a quoted
+        // body still throw the positions after them off.
+        let start = self.peek_span().start;
+        let (source, offset) = match self.next_token() {
+            Token::DollarString(source) => (source, start + 2),
+            Token::Varchar(literal) => (literal.value, start + 1),
+            token => return Err(self.unexpected("a routine body", &token).into()),
+        };
+        let body = if is_sql {
+            self.parse_nested(&source, offset, |parser| parser.parse_routine_body(&kind))?
+        } else {
+            RoutineBody::Unparsed(source.into_owned())
+        };
+
+        Ok(Statement::CreateRoutine(Box::new(CreateRoutine {
+            kind,
+            or_replace,
+            name,
+            args,
+            returns,
+            language,
+            execute_as,
+            body,
+        })))
+    }
+
+    /// Parses the body of a SQL procedure or function. Procedures hold a
+    /// scripting block, functions hold a query or an expression.
+    fn parse_routine_body(&mut self, kind: &RoutineKind) -> Result<RoutineBody> {
+        if kind == &RoutineKind::Function {
+            if self.peek_query_start() {
+                return Ok(RoutineBody::Query(Box::new(self.parse_query()?)));
+            }
+            if !self.peek_script_start() {
+                return Ok(RoutineBody::Expr(self.parse_expr()?));
+            }
+        }
+
+        Ok(RoutineBody::Script(self.parse_script_body()?))
+    }
+
+    fn parse_drop(&mut self) -> Result<Statement> {
+        self.expect_keyword(Keyword::DDL(DDLKeyword::Drop))?;
+        self.consume_keyword(Keyword::Materialized);
+
+        let object_type = match self.next_token() {
+            Token::Word(word) => match word.keyword {
+                Some(Keyword::Table) => ObjectType::Table,
+                Some(Keyword::View) => ObjectType::View,
+                Some(Keyword::Procedure) => ObjectType::Procedure,
+                Some(Keyword::Function) => ObjectType::Function,
+                _ => ObjectType::Other(Ident::new(word.value)),
+            },
+            token => return Err(self.unexpected("an object type", &token).into()),
+        };
+
+        let if_exists = if self.consume_keyword(Keyword::If) {
+            self.expect_keyword(Keyword::Exists)?;
+            true
+        } else {
+            false
+        };
+        let name = self.parse_object_name()?;
+
+        // Argument types of procedures and functions, CASCADE/RESTRICT
+        while !matches!(self.peek_token(), Token::Semicolon | Token::EOF) {
+            self.skip_token_or_group()?;
+        }
+
+        Ok(Statement::Drop {
+            object_type,
+            if_exists,
+            name,
+        })
+    }
+
+    fn parse_truncate(&mut self) -> Result<Statement> {
+        self.expect_keyword(Keyword::DDL(DDLKeyword::Truncate))?;
+        self.consume_keyword(Keyword::Table);
+        let if_exists = if self.consume_keyword(Keyword::If) {
+            self.expect_keyword(Keyword::Exists)?;
+            true
+        } else {
+            false
+        };
+
+        Ok(Statement::Truncate {
+            if_exists,
+            table_name: self.parse_object_name()?,
+        })
+    }
+
+    fn parse_execute_immediate(&mut self) -> Result<Statement> {
+        self.expect_keyword(Keyword::Execute)?;
+        self.expect_word("immediate")?;
+
+        if let Token::DollarString(source) = self.peek_token() {
+            let source = source.clone();
+            let offset = self.peek_span().start + 2;
+            self.next_token();
+            let block = self.parse_nested(&source, offset, |parser| parser.parse_script_body())?;
+            return Ok(Statement::ExecuteImmediate(ExecuteImmediate::Block(
+                Box::new(block),
+            )));
+        }
+
+        Ok(Statement::ExecuteImmediate(ExecuteImmediate::Expr(
+            self.parse_expr()?,
+        )))
+    }
+}
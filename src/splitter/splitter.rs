@@ -0,0 +1,363 @@
+use crate::lexer::lexer::{Lexer, Span, SpannedToken, Token};
+
+/// Words that close a nested scripting construct rather than a block when they
+/// follow `END`, as in `END IF` or `END LOOP`.
+const NESTED_BLOCK_ENDS: [&str; 5] = ["for", "if", "loop", "repeat", "while"];
+
+/// Words after which a scripting block may start, besides `;` and the `AS`
+/// of a `CREATE` statement.
+const BLOCK_STARTS_AFTER: [&str; 7] = ["begin", "declare", "do", "else", "loop", "repeat", "then"];
+
+/// A statement of a script, as written in the source.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatementSource<'a> {
+    /// The statement from its first token to its last, without the `;` or any
+    /// leading comments.
+    pub text: &'a str,
+    pub span: Span,
+    pub kind: StatementKind,
+}
+
+/// What a statement does, going by its leading words.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatementKind {
+    Query,
+    Insert,
+    Update,
+    Delete,
+    Merge,
+    Create,
+    Alter,
+    Drop,
+    Truncate,
+    Copy,
+    /// `PUT`, `GET`, `LIST` or `REMOVE`
+    Stage,
+    Call,
+    Use,
+    /// `SET` or `UNSET` of session variables
+    Set,
+    Show,
+    Describe,
+    /// `GRANT` or `REVOKE`
+    Grant,
+    /// `BEGIN [TRANSACTION]`, `START TRANSACTION`, `COMMIT` or `ROLLBACK`
+    Transaction,
+    /// An anonymous Snowflake Scripting block
+    Block,
+    ExecuteImmediate,
+    Other,
+}
+
+/// Splits a script into statements without parsing it. Semicolons inside
+/// strings, comments, `$$` bodies and scripting blocks don't end a statement.
+///
+/// Lexing errors don't stop the split: everything from the statement that
+/// failed to lex to the end of the script becomes the last statement.
+pub fn split_statements(sql: &str) -> Vec<StatementSource<'_>> {
+    let mut tokens = Vec::new();
+    let mut failed_at = None;
+    for token in Lexer::new(sql) {
+        match token {
+            Ok(token)
+                if matches!(
+                    token.token,
+                    Token::InlineComment(_) | Token::BlockComment(_)
+                ) => {}
+            Ok(token) => tokens.push(token),
+            Err(error) => {
+                failed_at = Some(error.span.start);
+                break;
+            }
+        }
+    }
+
+    let mut statements = Vec::new();
+    let mut start = None;
+    let mut depth = 0usize;
+    let mut declaring = false;
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = &tokens[i].token;
+        if token == &Token::Semicolon && depth == 0 {
+            if let Some(first) = start.take() {
+                statements.push(statement(sql, &tokens[first..i]));
+            }
+            declaring = false;
+            i += 1;
+            continue;
+        }
+
+        let at_start = start.is_none();
+        start.get_or_insert(i);
+
+        let in_create = start.is_some_and(|first| {
+            unquoted_word(&tokens[first].token)
+                .is_some_and(|word| word.eq_ignore_ascii_case("create"))
+        });
+        let block_may_start =
+            at_start || i > 0 && starts_block_after(&tokens[i - 1].token, in_create);
+        match unquoted_word(token) {
+            Some(word) if word.eq_ignore_ascii_case("declare") && at_start => {
+                depth += 1;
+                declaring = true;
+            }
+            Some(word)
+                if word.eq_ignore_ascii_case("begin")
+                    && block_may_start
+                    && !is_transaction_start(tokens.get(i + 1))
+                    && !is_column_name(tokens.get(i + 1)) =>
+            {
+                if declaring {
+                    declaring = false;
+                } else {
+                    depth += 1;
+                }
+            }
+            // CASE only needs counting inside blocks, where its END would
+            // otherwise close the block
+            Some(word) if word.eq_ignore_ascii_case("case") && depth > 0 => depth += 1,
+            Some(word) if word.eq_ignore_ascii_case("end") && depth > 0 => {
+                let next = tokens
+                    .get(i + 1)
+                    .and_then(|token| unquoted_word(&token.token));
+                if next.is_some_and(|next| next.eq_ignore_ascii_case("case")) {
+                    depth -= 1;
+                    i += 1;
+                } else if !next.is_some_and(|next| {
+                    NESTED_BLOCK_ENDS
+                        .iter()
+                        .any(|end| next.eq_ignore_ascii_case(end))
+                }) {
+                    depth -= 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    match (start, failed_at) {
+        (Some(first), Some(_)) => statements.push(unterminated(
+            sql,
+            tokens[first].span.start,
+            &tokens[first..],
+        )),
+        (None, Some(failed_at)) => statements.push(unterminated(sql, failed_at, &[])),
+        (Some(first), None) => statements.push(statement(sql, &tokens[first..])),
+        (None, None) => {}
+    }
+
+    statements
+}
+
+fn statement<'a>(sql: &'a str, tokens: &[SpannedToken<'_>]) -> StatementSource<'a> {
+    let span = Span {
+        start: tokens[0].span.start,
+        end: tokens[tokens.len() - 1].span.end,
+    };
+
+    StatementSource {
+        text: &sql[span.start..span.end],
+        span,
+        kind: classify(tokens),
+    }
+}
+
+/// The rest of a script that failed to lex, starting at `start`.
+fn unterminated<'a>(
+    sql: &'a str,
+    start: usize,
+    tokens: &[SpannedToken<'_>],
+) -> StatementSource<'a> {
+    let text = sql[start..].trim_end();
+    StatementSource {
+        text,
+        span: Span {
+            start,
+            end: start + text.len(),
+        },
+        kind: classify(tokens),
+    }
+}
+
+fn classify(tokens: &[SpannedToken<'_>]) -> StatementKind {
+    let word = |n: usize| tokens.get(n).and_then(|token| unquoted_word(&token.token));
+    let Some(first) = word(0) else {
+        return match tokens.first() {
+            Some(SpannedToken {
+                token: Token::OpenParen,
+                ..
+            }) => StatementKind::Query,
+            _ => StatementKind::Other,
+        };
+    };
+
+    match first.to_ascii_lowercase().as_str() {
+        "select" | "with" | "values" => StatementKind::Query,
+        "insert" => StatementKind::Insert,
+        "update" => StatementKind::Update,
+        "delete" => StatementKind::Delete,
+        "merge" => StatementKind::Merge,
+        "create" => StatementKind::Create,
+        "alter" => StatementKind::Alter,
+        "drop" => StatementKind::Drop,
+        "truncate" => StatementKind::Truncate,
+        "copy" => StatementKind::Copy,
+        "put" | "get" | "list" | "ls" | "remove" | "rm" => StatementKind::Stage,
+        "call" => StatementKind::Call,
+        "use" => StatementKind::Use,
+        "set" | "unset" => StatementKind::Set,
+        "show" => StatementKind::Show,
+        "describe" | "desc" => StatementKind::Describe,
+        "grant" | "revoke" => StatementKind::Grant,
+        "begin" if is_transaction_start(tokens.get(1)) => StatementKind::Transaction,
+        "begin" | "declare" => StatementKind::Block,
+        "start" | "commit" | "rollback" => StatementKind::Transaction,
+        "execute" if word(1).is_some_and(|word| word.eq_ignore_ascii_case("immediate")) => {
+            StatementKind::ExecuteImmediate
+        }
+        _ => StatementKind::Other,
+    }
+}
+
+/// Whether a `BEGIN` followed by `next` starts a transaction rather than a
+/// block, the same way the parser tells them apart.
+fn is_transaction_start(next: Option<&SpannedToken<'_>>) -> bool {
+    match next.map(|token| &token.token) {
+        None | Some(Token::Semicolon) => true,
+        Some(token) => unquoted_word(token).is_some_and(|word| {
+            word.eq_ignore_ascii_case("transaction") || word.eq_ignore_ascii_case("work")
+        }),
+    }
+}
+
+/// Whether a `BEGIN` followed by `next` is a column name or alias, as in
+/// `SELECT a AS begin, b FROM t`, rather than the start of a block.
+fn is_column_name(next: Option<&SpannedToken<'_>>) -> bool {
+    match next.map(|token| &token.token) {
+        Some(Token::Comma | Token::CloseParen) => true,
+        Some(token) => unquoted_word(token).is_some_and(|word| word.eq_ignore_ascii_case("from")),
+        None => false,
+    }
+}
+
+/// Whether a block may start after `token`. `AS` only introduces a body in
+/// `CREATE` statements, elsewhere it precedes an alias.
+fn starts_block_after(token: &Token<'_>, in_create: bool) -> bool {
+    token == &Token::Semicolon
+        || unquoted_word(token).is_some_and(|word| {
+            in_create && word.eq_ignore_ascii_case("as")
+                || BLOCK_STARTS_AFTER
+                    .iter()
+                    .any(|w| word.eq_ignore_ascii_case(w))
+        })
+}
+
+fn unquoted_word<'t>(token: &'t Token<'_>) -> Option<&'t str> {
+    match token {
+        Token::Word(word) if word.quote_style.is_none() => Some(&word.value),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_statements, StatementKind};
+
+    #[test]
+    fn splits_on_top_level_semicolons_only() {
+        let script = r#"
+-- setup
+USE ROLE analyst;;
+CREATE PROCEDURE p() RETURNS STRING LANGUAGE SQL AS $$
+BEGIN
+    INSERT INTO log VALUES ('a;b');
+    RETURN 'done';
+END;
+$$;
+DECLARE
+    total INTEGER DEFAULT 0;
+BEGIN
+    FOR i IN 1 TO 3 DO
+        total := total + CASE WHEN i > 1 THEN i ELSE 0 END;
+    END FOR;
+    IF (total > 0) THEN
+        BEGIN
+            DELETE FROM t WHERE x = ';';
+        END;
+    END IF;
+    RETURN total;
+END;
+BEGIN TRANSACTION;
+SELECT begin, "end;" /* ; */ FROM t;
+(SELECT 1)
+"#;
+
+        let statements = split_statements(script);
+        let kinds: Vec<_> = statements.iter().map(|statement| statement.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                StatementKind::Use,
+                StatementKind::Create,
+                StatementKind::Block,
+                StatementKind::Transaction,
+                StatementKind::Query,
+                StatementKind::Query,
+            ]
+        );
+
+        assert_eq!(statements[0].text, "USE ROLE analyst");
+        assert!(statements[2].text.starts_with("DECLARE"));
+        assert!(statements[2].text.ends_with("RETURN total;\nEND"));
+        assert_eq!(statements[4].text, r#"SELECT begin, "end;" /* ; */ FROM t"#);
+        assert_eq!(
+            &script[statements[5].span.start..statements[5].span.end],
+            "(SELECT 1)"
+        );
+    }
+
+    #[test]
+    fn ends_statements_at_semicolons_after_comments() {
+        let statements = split_statements("SELECT 1 /* ; */; SELECT 2 /* x */; SELECT 3");
+        let texts: Vec<_> = statements.iter().map(|statement| statement.text).collect();
+        assert_eq!(texts, vec!["SELECT 1", "SELECT 2", "SELECT 3"]);
+
+        // An unterminated comment ends the script rather than the split
+        let statements = split_statements("SELECT 1; /* open");
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[1].text, "/* open");
+    }
+
+    #[test]
+    fn treats_begin_as_a_name_outside_blocks() {
+        let statements = split_statements(
+            "SELECT a AS begin, b FROM t; SELECT 2; CREATE VIEW v AS SELECT x AS begin FROM t; \
+             CREATE PROCEDURE p() RETURNS INT LANGUAGE SQL AS BEGIN RETURN 1; END; SELECT 3",
+        );
+        let kinds: Vec<_> = statements.iter().map(|statement| statement.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                StatementKind::Query,
+                StatementKind::Query,
+                StatementKind::Create,
+                StatementKind::Create,
+                StatementKind::Query,
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_the_rest_of_a_script_that_fails_to_lex() {
+        let statements = split_statements("SELECT 1; INSERT INTO t VALUES ('oops); SELECT 2;\n");
+
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[1].kind, StatementKind::Insert);
+        assert_eq!(
+            statements[1].text,
+            "INSERT INTO t VALUES ('oops); SELECT 2;"
+        );
+    }
+}
@@ -0,0 +1,680 @@
+//! Type inference for expressions, following Snowflake's rules for literals,
+//! arithmetic, casts and the return types of built-in functions.
+//!
+//! The [`Binder`] infers the type of each output column as it binds a
+//! statement, so [`Binder::lineage`] reports the schema of a view or `CREATE
+//! TABLE ... AS` without running it. Columns of base tables are typed when
+//! declared with [`Binder::add_typed_table`], e.g. from a
+//! [`Catalog`](crate::catalog::catalog::Catalog).
+//!
+//! Comparisons between values of different kinds, such as a VARCHAR with a
+//! NUMBER, are recorded as [`ImplicitCast`]s. Snowflake converts one side,
+//! which fails at runtime on values that don't convert and keeps the column
+//! from being used for pruning.
+
+use crate::ast::data_type::DataType;
+use crate::ast::expression::{BinaryOperator, Expr, FunctionCall, UnaryOperator, Value};
+use crate::ast::query::Query;
+use crate::ast::statement::Statement;
+use crate::ast::visitor::Visitor;
+use crate::binder::binder::Binder;
+use crate::functions::functions::ReturnType;
+use crate::lexer::lexer::Span;
+
+/// The largest precision of a NUMBER.
+const MAX_PRECISION: u64 = 38;
+
+/// A value Snowflake converts to another type to evaluate an expression, e.g.
+/// the VARCHAR `'10'` in `amount = '10'`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImplicitCast {
+    /// The expression whose value is converted.
+    pub expr: Expr,
+    pub from: DataType,
+    pub to: DataType,
+    /// The statement the cast is in, as given to [`Binder::implicit_casts`],
+    /// since expressions don't carry spans.
+    pub span: Span,
+}
+
+/// The kinds of values Snowflake compares without a conversion.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Family {
+    Numeric,
+    Text,
+    Binary,
+    Boolean,
+    Temporal,
+    SemiStructured,
+    Spatial,
+}
+
+impl Binder {
+    /// Binds `statement`, found at `span` in the source, and returns the
+    /// implicit casts of its predicates, select lists and assignments, in the
+    /// order they're bound.
+    pub fn implicit_casts(&mut self, statement: &Statement, span: Span) -> Vec<ImplicitCast> {
+        self.visit_statement(statement);
+        let mut casts = std::mem::take(&mut self.casts);
+        self.take_bindings();
+        for cast in &mut casts {
+            cast.span = span;
+        }
+        casts
+    }
+
+    /// Records that `expr` is converted from `from` to `to`. The cast is
+    /// located once its statement is bound.
+    fn implicit_cast(&mut self, expr: &Expr, from: DataType, to: DataType) {
+        self.casts.push(ImplicitCast {
+            expr: expr.clone(),
+            from,
+            to,
+            span: Span::default(),
+        });
+    }
+
+    /// The type of `expr` in the scope being bound, `None` when it can't be
+    /// told. Subqueries must have been bound first, and implicit casts are
+    /// recorded along the way.
+    pub(crate) fn infer(&mut self, expr: &Expr) -> Option<DataType> {
+        match expr {
+            Expr::Identifier(ident) => self.column_type(std::slice::from_ref(ident)),
+            Expr::CompoundIdentifier(parts) => self.column_type(parts),
+            Expr::Value(value) => literal_type(value),
+            Expr::TypedString { data_type, .. } => Some(canonical(data_type)),
+            Expr::BinaryOp { left, op, right } => {
+                let left_type = self.infer(left);
+                let right_type = self.infer(right);
+                match op {
+                    BinaryOperator::Plus
+                    | BinaryOperator::Minus
+                    | BinaryOperator::Multiply
+                    | BinaryOperator::Divide
+                    | BinaryOperator::Modulo => {
+                        self.arithmetic(op, (left, left_type), (right, right_type))
+                    }
+                    BinaryOperator::StringConcat => Some(DataType::Varchar(
+                        text_length(&left_type)
+                            .zip(text_length(&right_type))
+                            .map(|(left, right)| left + right),
+                    )),
+                    BinaryOperator::And | BinaryOperator::Or => Some(DataType::Boolean),
+                    _ => {
+                        self.compare((left, &left_type), (right, &right_type));
+                        Some(DataType::Boolean)
+                    }
+                }
+            }
+            Expr::UnaryOp {
+                op: UnaryOperator::Not,
+                expr,
+            } => {
+                self.infer(expr);
+                Some(DataType::Boolean)
+            }
+            Expr::UnaryOp { expr, .. } => self.infer(expr),
+            Expr::IsNull { expr, .. } => {
+                self.infer(expr);
+                Some(DataType::Boolean)
+            }
+            Expr::InList { expr, list, .. } => {
+                let data_type = self.infer(expr);
+                for item in list {
+                    let item_type = self.infer(item);
+                    self.compare((expr, &data_type), (item, &item_type));
+                }
+                Some(DataType::Boolean)
+            }
+            Expr::InSubquery { expr, subquery, .. } => {
+                let data_type = self.infer(expr);
+                let subquery_type = self.subquery_type(subquery);
+                self.compare(
+                    (expr, &data_type),
+                    (&Expr::Subquery(subquery.clone()), &subquery_type),
+                );
+                Some(DataType::Boolean)
+            }
+            Expr::Between {
+                expr, low, high, ..
+            } => {
+                let data_type = self.infer(expr);
+                for bound in [low, high] {
+                    let bound_type = self.infer(bound);
+                    self.compare((expr, &data_type), (bound, &bound_type));
+                }
+                Some(DataType::Boolean)
+            }
+            // Both sides of LIKE are converted to text
+            Expr::Like { expr, pattern, .. } => {
+                for expr in [expr, pattern] {
+                    let data_type = self.infer(expr);
+                    let family = data_type.as_ref().and_then(family);
+                    if let (Some(from), Some(family)) = (data_type, family) {
+                        if !matches!(family, Family::Text | Family::SemiStructured) {
+                            self.implicit_cast(expr, from, DataType::Varchar(None));
+                        }
+                    }
+                }
+                Some(DataType::Boolean)
+            }
+            Expr::Exists { .. } => Some(DataType::Boolean),
+            Expr::Case {
+                operand,
+                conditions,
+                else_result,
+            } => {
+                let operand = operand
+                    .as_deref()
+                    .map(|operand| (operand, self.infer(operand)));
+                let mut data_type = None;
+                for (condition, result) in conditions {
+                    let condition_type = self.infer(condition);
+                    if let Some((operand, operand_type)) = &operand {
+                        self.compare((operand, operand_type), (condition, &condition_type));
+                    }
+                    data_type = common_type(data_type, self.infer(result));
+                }
+                if let Some(else_result) = else_result {
+                    data_type = common_type(data_type, self.infer(else_result));
+                }
+                data_type
+            }
+            Expr::Cast {
+                expr, data_type, ..
+            } => {
+                self.infer(expr);
+                Some(canonical(data_type))
+            }
+            // Elements of semi-structured values are VARIANTs
+            Expr::JsonAccess { expr, .. } => {
+                self.infer(expr);
+                Some(DataType::Variant)
+            }
+            Expr::Subscript { expr, index } => {
+                self.infer(expr);
+                self.infer(index);
+                Some(DataType::Variant)
+            }
+            Expr::Array(items) => {
+                for item in items {
+                    self.infer(item);
+                }
+                Some(DataType::Array(None))
+            }
+            Expr::Object(entries) => {
+                for (key, value) in entries {
+                    self.infer(key);
+                    self.infer(value);
+                }
+                Some(DataType::Object(vec![]))
+            }
+            Expr::Function(function) => self.infer_function(function),
+            Expr::Nested(expr) => self.infer(expr),
+            Expr::Subquery(query) => self.subquery_type(query),
+            // Lambda parameters take the type of elements of arrays whose
+            // types aren't tracked
+            Expr::Lambda { .. }
+            | Expr::Error(_)
+            | Expr::Variable(_)
+            | Expr::SessionVariable(_)
+            | Expr::Positional(_)
+            | Expr::QualifiedPositional { .. }
+            | Expr::Interval(_)
+            | Expr::Placeholder
+            | Expr::Wildcard => None,
+        }
+    }
+
+    fn infer_function(&mut self, function: &FunctionCall) -> Option<DataType> {
+        let args: Vec<Option<DataType>> = function
+            .args
+            .iter()
+            .map(|arg| self.infer(arg.expr()))
+            .collect();
+        let builtin = function.builtin()?;
+        match (builtin.name, args.first().cloned().flatten()) {
+            ("SUM", Some(data_type)) => return Some(aggregate_type(data_type, 0, 0)),
+            ("AVG", Some(data_type)) => return Some(aggregate_type(data_type, 6, 12)),
+            _ => {}
+        }
+        match &builtin.return_type {
+            ReturnType::Fixed(data_type) => Some(data_type.clone()),
+            ReturnType::Argument(index) => result_arguments(builtin.name, *index, args.len())
+                .into_iter()
+                .filter_map(|index| args.get(index).cloned())
+                .fold(None, common_type),
+            ReturnType::Table(_) => None,
+        }
+    }
+
+    /// The type of the one column of a subquery bound as part of the
+    /// expression being typed.
+    fn subquery_type(&self, query: &Query) -> Option<DataType> {
+        let address = query as *const Query as usize;
+        self.subquery_types
+            .iter()
+            .rev()
+            .find(|(subquery, _)| *subquery == address)
+            .and_then(|(_, data_type)| data_type.clone())
+    }
+
+    /// The type of `left op right`, following Snowflake's rules for the
+    /// precision and scale of NUMBER results. Text operands are converted
+    /// to numbers.
+    fn arithmetic(
+        &mut self,
+        op: &BinaryOperator,
+        left: (&Expr, Option<DataType>),
+        right: (&Expr, Option<DataType>),
+    ) -> Option<DataType> {
+        let mut operands = [left, right];
+        for i in 0..2 {
+            let (expr, Some(from)) = &operands[i] else {
+                continue;
+            };
+            if family(from) != Some(Family::Text) {
+                continue;
+            }
+            let to = match &operands[1 - i].1 {
+                Some(other) if family(other) == Some(Family::Numeric) => other.clone(),
+                _ => unknown_number(),
+            };
+            self.implicit_cast(expr, from.clone(), to.clone());
+            operands[i].1 = Some(to);
+        }
+
+        let [(_, left), (_, right)] = operands;
+        let (left, right) = (left?, right?);
+        match (family(&left)?, family(&right)?) {
+            (Family::Numeric, Family::Numeric) => {
+                if left == DataType::Float || right == DataType::Float {
+                    return Some(DataType::Float);
+                }
+                let ((left_precision, left_scale), (right_precision, right_scale)) =
+                    (number_parts(&left)?, number_parts(&right)?);
+                let (left_digits, right_digits) = (
+                    left_precision.saturating_sub(left_scale),
+                    right_precision.saturating_sub(right_scale),
+                );
+                let (digits, scale) = match op {
+                    BinaryOperator::Plus | BinaryOperator::Minus => (
+                        left_digits.max(right_digits) + 1,
+                        left_scale.max(right_scale),
+                    ),
+                    BinaryOperator::Multiply => (
+                        left_digits + right_digits,
+                        (left_scale + right_scale).min(left_scale.max(right_scale).max(12)),
+                    ),
+                    BinaryOperator::Divide => (
+                        left_digits + right_scale,
+                        left_scale.max((left_scale + 6).min(12)),
+                    ),
+                    _ => (left_digits.max(right_digits), left_scale.max(right_scale)),
+                };
+                Some(number(digits, scale))
+            }
+            // Subtracting dates counts the days between them, while adding
+            // days keeps the date or timestamp
+            (Family::Temporal, Family::Temporal) if *op == BinaryOperator::Minus => {
+                Some(number(MAX_PRECISION, 0))
+            }
+            (Family::Temporal, Family::Numeric) => Some(left),
+            (Family::Numeric, Family::Temporal) if *op == BinaryOperator::Plus => Some(right),
+            _ => None,
+        }
+    }
+
+    /// Records the conversion comparing `left` with `right` takes when their
+    /// types are of different kinds. Snowflake converts the text side, or
+    /// otherwise the right side.
+    fn compare(&mut self, left: (&Expr, &Option<DataType>), right: (&Expr, &Option<DataType>)) {
+        let ((left, Some(left_type)), (right, Some(right_type))) = (left, right) else {
+            return;
+        };
+        let (Some(left_family), Some(right_family)) = (family(left_type), family(right_type))
+        else {
+            return;
+        };
+        // VARIANTs compare with anything by their contents
+        if left_family == right_family
+            || left_family == Family::SemiStructured
+            || right_family == Family::SemiStructured
+        {
+            return;
+        }
+
+        let (expr, from, to) = match left_family {
+            Family::Text => (left, left_type, right_type),
+            _ => (right, right_type, left_type),
+        };
+        self.implicit_cast(expr, from.clone(), to.clone());
+    }
+}
+
+/// `data_type` as Snowflake stores it, e.g. `INT` as `NUMBER(38,0)` and
+/// `CHAR(n)` as `VARCHAR(n)`.
+pub fn canonical(data_type: &DataType) -> DataType {
+    match data_type {
+        DataType::Int
+        | DataType::BigInt
+        | DataType::SmallInt
+        | DataType::TinyInt
+        | DataType::ByteInt => number(MAX_PRECISION, 0),
+        DataType::Number { precision, scale } => DataType::Number {
+            precision: Some(precision.unwrap_or(MAX_PRECISION)),
+            scale: Some(scale.unwrap_or(0)),
+        },
+        DataType::Char(length) => DataType::Varchar(Some(length.unwrap_or(1))),
+        data_type => data_type.clone(),
+    }
+}
+
+/// The type values of `left` and `right` share, e.g. the branches of a
+/// `CASE` or the columns of a `UNION`. `None` for values of different kinds,
+/// which Snowflake may not be able to convert.
+pub fn common_type(left: Option<DataType>, right: Option<DataType>) -> Option<DataType> {
+    let (left, right) = match (left, right) {
+        (Some(left), Some(right)) => (left, right),
+        (left, right) => return left.or(right),
+    };
+    if left == right {
+        return Some(left);
+    }
+
+    match (&left, &right) {
+        (DataType::Float, other) | (other, DataType::Float)
+            if family(other) == Some(Family::Numeric) =>
+        {
+            Some(DataType::Float)
+        }
+        (DataType::Varchar(_) | DataType::Char(_), DataType::Varchar(_) | DataType::Char(_)) => {
+            let length = text_length(&Some(left.clone())).zip(text_length(&Some(right.clone())));
+            Some(DataType::Varchar(
+                length.map(|(left, right)| left.max(right)),
+            ))
+        }
+        (DataType::Date, DataType::Timestamp { .. }) => Some(right),
+        (DataType::Timestamp { .. }, DataType::Date) => Some(left),
+        (
+            DataType::Timestamp {
+                time_zone,
+                precision,
+            },
+            DataType::Timestamp {
+                time_zone: other_time_zone,
+                precision: other_precision,
+            },
+        ) if time_zone == other_time_zone => Some(DataType::Timestamp {
+            time_zone: time_zone.clone(),
+            precision: (*precision).max(*other_precision),
+        }),
+        _ if family(&left) == Some(Family::Numeric) && family(&right) == Some(Family::Numeric) => {
+            match (number_parts(&left), number_parts(&right)) {
+                (Some((left_precision, left_scale)), Some((right_precision, right_scale))) => {
+                    let digits = left_precision
+                        .saturating_sub(left_scale)
+                        .max(right_precision.saturating_sub(right_scale));
+                    Some(number(digits, left_scale.max(right_scale)))
+                }
+                _ => Some(unknown_number()),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The type of a literal, e.g. `NUMBER(3,2)` for `1.50` and `VARCHAR(5)` for
+/// `'hello'`. `None` for `NULL`, which takes any type.
+fn literal_type(value: &Value) -> Option<DataType> {
+    match value {
+        Value::Int(value) => Some(number(value.unsigned_abs().to_string().len() as u64, 0)),
+        Value::Decimal(value) if value.contains(['e', 'E']) => Some(DataType::Float),
+        Value::Decimal(value) => {
+            let digits = value.trim_start_matches(['-', '+']);
+            let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+            let integer = integer.trim_start_matches('0').len() as u64;
+            let scale = fraction.len() as u64;
+            Some(number(integer, scale))
+        }
+        Value::Float(_) => Some(DataType::Float),
+        Value::Varchar(value) => Some(DataType::Varchar(Some(value.chars().count() as u64))),
+        Value::Binary(value) => Some(DataType::Binary(Some(value.len() as u64))),
+        Value::Bool(_) => Some(DataType::Boolean),
+        Value::Null => None,
+    }
+}
+
+/// The type `SUM` or `AVG` of `data_type` returns, whose scale grows by
+/// `scale` up to `max_scale` for averages.
+fn aggregate_type(data_type: DataType, scale: u64, max_scale: u64) -> DataType {
+    match number_parts(&data_type) {
+        Some((_, argument_scale)) => number(
+            MAX_PRECISION,
+            argument_scale.max((argument_scale + scale).min(max_scale)),
+        ),
+        None if data_type == DataType::Float => DataType::Float,
+        None => unknown_number(),
+    }
+}
+
+/// The arguments whose common type a function returns, for those returning
+/// the type of an argument: `IFF(c, a, b)` may return either branch, for
+/// example, where `MAX(x)` returns the type of `x`.
+fn result_arguments(name: &str, index: usize, count: usize) -> Vec<usize> {
+    match name {
+        "COALESCE" | "GREATEST" | "LEAST" | "IFNULL" | "NVL" => (0..count).collect(),
+        "IFF" | "NVL2" => (1..count).collect(),
+        // DECODE(expr, search, result, ..., default)
+        "DECODE" => (2..count)
+            .step_by(2)
+            .chain(count.is_multiple_of(2).then(|| count - 1))
+            .collect(),
+        _ => vec![index],
+    }
+}
+
+fn family(data_type: &DataType) -> Option<Family> {
+    let family = match data_type {
+        DataType::Number { .. }
+        | DataType::Int
+        | DataType::BigInt
+        | DataType::SmallInt
+        | DataType::TinyInt
+        | DataType::ByteInt
+        | DataType::Float => Family::Numeric,
+        DataType::Varchar(_) | DataType::Char(_) => Family::Text,
+        DataType::Binary(_) => Family::Binary,
+        DataType::Boolean => Family::Boolean,
+        DataType::Date | DataType::Time(_) | DataType::Timestamp { .. } => Family::Temporal,
+        DataType::Variant | DataType::Object(_) | DataType::Array(_) | DataType::Map { .. } => {
+            Family::SemiStructured
+        }
+        DataType::Geography | DataType::Geometry => Family::Spatial,
+        DataType::Vector { .. } => return None,
+    };
+    Some(family)
+}
+
+/// The precision and scale of a fixed-point number, when they're known.
+fn number_parts(data_type: &DataType) -> Option<(u64, u64)> {
+    match data_type {
+        DataType::Number {
+            precision: Some(precision),
+            scale,
+        } => Some((*precision, scale.unwrap_or(0))),
+        DataType::Int
+        | DataType::BigInt
+        | DataType::SmallInt
+        | DataType::TinyInt
+        | DataType::ByteInt => Some((MAX_PRECISION, 0)),
+        _ => None,
+    }
+}
+
+/// The length of a text value, `None` when it's unbounded or not text.
+fn text_length(data_type: &Option<DataType>) -> Option<u64> {
+    match data_type {
+        Some(DataType::Varchar(length)) => *length,
+        Some(DataType::Char(length)) => Some(length.unwrap_or(1)),
+        _ => None,
+    }
+}
+
+/// A NUMBER with `digits` before the decimal point and `scale` after it,
+/// keeping to the largest precision.
+fn number(digits: u64, scale: u64) -> DataType {
+    let precision = (digits + scale).clamp(1, MAX_PRECISION);
+    DataType::Number {
+        precision: Some(precision),
+        scale: Some(scale.min(precision)),
+    }
+}
+
+/// A NUMBER of unknown precision and scale, as some functions return.
+fn unknown_number() -> DataType {
+    DataType::Number {
+        precision: None,
+        scale: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::catalog::catalog::Catalog;
+    use crate::diagnostics::diagnostics::{Diagnostic, Renderer};
+    use crate::lexer::lexer::Span;
+    use crate::parser::parser::Parser;
+
+    const TABLES: &str = "
+CREATE TABLE raw.orders (
+    id INT NOT NULL,
+    customer_id NUMBER,
+    amount NUMBER(12, 2),
+    status VARCHAR(10),
+    placed_at TIMESTAMP_NTZ,
+    payload VARIANT
+);
+CREATE TABLE raw.customers (id VARCHAR(36), name CHAR(20));";
+
+    #[test]
+    fn infers_the_output_types_of_views() -> Result<()> {
+        let sql = "
+CREATE VIEW analytics.order_facts AS
+WITH totals AS (
+    SELECT customer_id, SUM(amount) AS spent, AVG(amount) AS average, COUNT(*) AS orders
+    FROM raw.orders GROUP BY customer_id
+)
+SELECT
+    o.id,
+    o.amount * 1.5 AS scaled,
+    o.amount + o.id AS total,
+    o.status || '!' AS label,
+    IFF(o.amount > 100, 'large', 'small') AS size,
+    CASE WHEN o.amount > 0 THEN 1 ELSE 2.50 END AS sign,
+    o.payload:item.sku AS sku,
+    o.payload:qty::INT AS qty,
+    DATE_TRUNC('day', o.placed_at) AS day,
+    o.placed_at::DATE - 1 AS previous_day,
+    t.spent,
+    t.average,
+    t.orders,
+    (SELECT MAX(amount) FROM raw.orders) AS largest,
+    o.amount = 0 OR o.status IS NULL AS flagged,
+    COALESCE(c.name, o.status) AS who,
+    NULL AS nothing
+FROM raw.orders o
+JOIN totals t ON t.customer_id = o.customer_id
+LEFT JOIN raw.customers c ON c.name = o.status";
+        let mut catalog = Catalog::from_sql(TABLES);
+        catalog.add_sql(sql);
+
+        let view = catalog
+            .table(&["ORDER_FACTS".to_string()])
+            .expect("the view's columns");
+        let columns: Vec<String> = view
+            .columns
+            .iter()
+            .map(|column| match &column.data_type {
+                Some(data_type) => format!("{} {}", column.name, data_type),
+                None => column.name.clone(),
+            })
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                "ID NUMBER(38,0)",
+                "SCALED NUMBER(14,3)",
+                "TOTAL NUMBER(38,2)",
+                "LABEL VARCHAR(11)",
+                "SIZE VARCHAR(5)",
+                "SIGN NUMBER(3,2)",
+                "SKU VARIANT",
+                "QTY NUMBER(38,0)",
+                "DAY TIMESTAMP_NTZ",
+                "PREVIOUS_DAY DATE",
+                "SPENT NUMBER(38,2)",
+                "AVERAGE NUMBER(38,8)",
+                "ORDERS NUMBER(38,0)",
+                "LARGEST NUMBER(12,2)",
+                "FLAGGED BOOLEAN",
+                "WHO VARCHAR(20)",
+                "NOTHING",
+            ]
+        );
+
+        // Views over the view see its types
+        let statement = Parser::from_sql("SELECT spent / orders FROM analytics.order_facts")
+            .parse_statement()?;
+        let lineage = catalog.binder().lineage(&statement).unwrap();
+        let data_type = lineage.columns.unwrap()[0].data_type.clone();
+        assert_eq!(data_type.unwrap().to_string(), "NUMBER(38,8)");
+
+        Ok(())
+    }
+
+    #[test]
+    fn reports_implicit_casts() -> Result<()> {
+        let sql = "
+SELECT o.id, o.amount + '1' AS padded
+FROM raw.orders o JOIN raw.customers c ON c.id = o.customer_id
+WHERE o.status = 1
+  AND o.id IN ('1', 2)
+  AND o.amount > 0
+  AND o.payload:status = 'open'
+  AND o.placed_at > '2024-01-01'
+  AND EXISTS (SELECT 1 FROM raw.customers WHERE name LIKE o.customer_id)";
+        let statement = Parser::from_sql(sql).parse_statement()?;
+        let mut binder = Catalog::from_sql(TABLES).binder();
+
+        let casts: Vec<String> = binder
+            .implicit_casts(&statement, Span::default())
+            .iter()
+            .map(|cast| Diagnostic::from(cast).message)
+            .collect();
+        assert_eq!(
+            casts,
+            vec![
+                "c.id is implicitly cast from VARCHAR(36) to NUMBER(38,0)",
+                "'1' is implicitly cast from VARCHAR(1) to NUMBER(12,2)",
+                "o.customer_id is implicitly cast from NUMBER(38,0) to VARCHAR",
+                "o.status is implicitly cast from VARCHAR(10) to NUMBER(1,0)",
+                "'1' is implicitly cast from VARCHAR(1) to NUMBER(38,0)",
+                "'2024-01-01' is implicitly cast from VARCHAR(10) to TIMESTAMP_NTZ",
+            ]
+        );
+
+        // Casts are located by their statement
+        let sql = "SELECT 1;\nSELECT id FROM raw.orders WHERE status = 1;";
+        let (statements, _) = Parser::from_sql(sql).parse_statements_with_spans();
+        let (statement, span) = &statements[1];
+        let casts = binder.implicit_casts(statement, *span);
+        let rendered = Renderer::new(false).render(&(&casts[0]).into(), "q.sql", sql);
+        assert!(rendered.contains(" --> q.sql:2:1\n"));
+        assert!(rendered.contains("2 | SELECT id FROM raw.orders WHERE status = 1;\n"));
+
+        Ok(())
+    }
+}